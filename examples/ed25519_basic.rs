@@ -11,6 +11,8 @@ use std::str::FromStr;
 use der::asn1::BitString;
 use ed25519_dalek::{Signature as Ed25519DalekSignature, Signer, SigningKey, VerifyingKey};
 use polyproto::certs::PublicKeyInfo;
+use polyproto::errors::base::InvalidInput;
+use polyproto::errors::composite::{ConversionError, SigningError};
 use polyproto::key::{PrivateKey, PublicKey};
 use polyproto::signature::Signature;
 use rand::rngs::OsRng;
@@ -26,7 +28,7 @@ fn main() {
 
     // Create and sign a message
     let message_unsigned = "hi my name is flori".as_bytes();
-    let signature = priv_key.sign(message_unsigned);
+    let signature = priv_key.sign(message_unsigned).unwrap();
     println!(
         "Signature of the message \"{}\": {:?}",
         String::from_utf8_lossy(message_unsigned),
@@ -83,28 +85,37 @@ impl Signature for Ed25519Signature {
 
     // The algorithm identifier for a given signature implementation is constant. We just need
     // to define it here.
-    fn algorithm_identifier() -> AlgorithmIdentifierOwned {
-        AlgorithmIdentifierOwned {
+    fn algorithm_identifier() -> Option<AlgorithmIdentifierOwned> {
+        Some(AlgorithmIdentifierOwned {
             // This is the OID for Ed25519. It is defined in the IANA registry.
             oid: ObjectIdentifier::from_str("1.3.101.112").unwrap(),
             // For this example, we don't need or want any parameters.
             parameters: None,
-        }
+        })
+    }
+
+    // The algorithm identifier of a signature value. As our signature type only supports a
+    // single algorithm, this is the same as the algorithm identifier above.
+    fn signature_algorithm(&self) -> AlgorithmIdentifierOwned {
+        self.algorithm.clone()
     }
 
     #[cfg(not(tarpaulin_include))]
-    fn from_bytes(signature: &[u8]) -> Self {
-        let mut signature_vec = signature.to_vec();
-        signature_vec.resize(64, 0);
-        let signature_array: [u8; 64] = {
-            let mut array = [0; 64];
-            array.copy_from_slice(&signature_vec[..]);
-            array
-        };
-        Self {
+    fn try_from_bytes(signature: &[u8]) -> Result<Self, ConversionError> {
+        // Ed25519 signatures are exactly 64 bytes long. Anything else is not a valid signature.
+        let signature_array: [u8; 64] = signature.try_into().map_err(|_| InvalidInput::Length {
+            min_length: 64,
+            max_length: 64,
+            actual_length: signature.len().to_string(),
+        })?;
+        Ok(Self {
             signature: Ed25519DalekSignature::from_bytes(&signature_array),
-            algorithm: Self::algorithm_identifier(),
-        }
+            algorithm: Self::algorithm_identifier().unwrap(),
+        })
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.signature.to_bytes().to_vec()
     }
 }
 
@@ -136,12 +147,15 @@ impl PrivateKey<Ed25519Signature> for Ed25519PrivateKey {
 
     // Signs a message. The beauty of having to wrap the ed25519-dalek crate is that we can
     // harness all of its functionality, such as the `sign` method.
-    fn sign(&self, data: &[u8]) -> Ed25519Signature {
-        let signature = self.key.sign(data);
-        Ed25519Signature {
+    fn sign(&self, data: &[u8]) -> Result<Ed25519Signature, SigningError> {
+        let signature = self
+            .key
+            .try_sign(data)
+            .map_err(|e| SigningError::Failed(e.to_string()))?;
+        Ok(Ed25519Signature {
             signature,
-            algorithm: self.algorithm_identifier(),
-        }
+            algorithm: Ed25519Signature::algorithm_identifier().unwrap(),
+        })
     }
 }
 
@@ -184,25 +198,23 @@ impl PublicKey<Ed25519Signature> for Ed25519PublicKey {
     #[cfg(not(tarpaulin_include))]
     fn public_key_info(&self) -> PublicKeyInfo {
         PublicKeyInfo {
-            algorithm: Ed25519Signature::algorithm_identifier(),
+            algorithm: Ed25519Signature::algorithm_identifier().unwrap(),
             public_key_bitstring: BitString::from_bytes(&self.key.to_bytes()).unwrap(),
         }
     }
 
     #[cfg(not(tarpaulin_include))]
-    fn try_from_public_key_info(
-        public_key_info: PublicKeyInfo,
-    ) -> Result<Self, polyproto::errors::composite::ConversionError> {
-        let mut key_vec = public_key_info.public_key_bitstring.raw_bytes().to_vec();
-        key_vec.resize(32, 0);
-        let signature_array: [u8; 32] = {
-            let mut array = [0; 32];
-            array.copy_from_slice(&key_vec[..]);
-            array
-        };
-        Ok(Self {
-            key: VerifyingKey::from_bytes(&signature_array).unwrap(),
-        })
+    fn try_from_public_key_info(public_key_info: PublicKeyInfo) -> Result<Self, ConversionError> {
+        // Ed25519 public keys are exactly 32 bytes long.
+        let key_bytes = public_key_info.public_key_bitstring.raw_bytes();
+        let key_array: [u8; 32] = key_bytes.try_into().map_err(|_| InvalidInput::Length {
+            min_length: 32,
+            max_length: 32,
+            actual_length: key_bytes.len().to_string(),
+        })?;
+        let key = VerifyingKey::from_bytes(&key_array)
+            .map_err(|e| InvalidInput::Malformed(e.to_string()))?;
+        Ok(Self { key })
     }
 }
 
@@ -6,8 +6,8 @@ use http::HeaderMap;
 use httptest::matchers::request;
 use httptest::responders::json_encoded;
 use httptest::{Expectation, Server};
-use polyproto::api::core::current_unix_time;
 use polyproto::api::HttpClient;
+use polyproto::clock::{Clock, SystemClock};
 use polyproto::types::routes::core::v1::GET_CHALLENGE_STRING;
 use serde_json::json;
 
@@ -20,7 +20,7 @@ async fn setup_example() -> Server {
         ))
         .respond_with(json_encoded(json!({
             "challenge": "abcd".repeat(8),
-            "expires": current_unix_time() + 100
+            "expires": SystemClock::new().now() + 100
         }))),
     );
     server
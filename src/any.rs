@@ -0,0 +1,313 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::sync::{PoisonError, RwLock};
+
+use der::asn1::BitString;
+use spki::{AlgorithmIdentifierOwned, ObjectIdentifier, SignatureBitStringEncoding};
+
+use crate::certs::{decode_public_key, PublicKeyInfo};
+use crate::errors::{ConversionError, InvalidCert, InvalidInput, PublicKeyError};
+use crate::key::PublicKey;
+use crate::signature::Signature;
+
+/// The algorithms registered using [register_algorithm()], keyed by the OID of their signature
+/// algorithm.
+static REGISTRY: RwLock<BTreeMap<ObjectIdentifier, RegisteredAlgorithm>> =
+    RwLock::new(BTreeMap::new());
+
+/// [PublicKey::verify_signature()] of a registered public key type, taking the encoded public key,
+/// the signature algorithm, the encoded signature and the signed data.
+type VerifySignature =
+    fn(&PublicKeyInfo, &AlgorithmIdentifierOwned, &[u8], &[u8]) -> Result<(), PublicKeyError>;
+
+/// A [Signature] and [PublicKey] implementation registered using [register_algorithm()], with its
+/// methods erased to function pointers.
+#[derive(Clone)]
+struct RegisteredAlgorithm {
+    /// The [Signature::algorithm_identifier()] of the registered signature type
+    signature_algorithm: AlgorithmIdentifierOwned,
+    /// [Signature::check_algorithm()] of the registered signature type
+    check_algorithm: fn(&AlgorithmIdentifierOwned) -> Result<(), InvalidCert>,
+    /// Checks, whether the bytes decode into a signature of the registered type
+    check_signature: fn(&AlgorithmIdentifierOwned, &[u8]) -> Result<(), ConversionError>,
+    /// Checks, whether the [PublicKeyInfo] decodes into a public key of the registered type
+    check_public_key: fn(PublicKeyInfo) -> Result<(), ConversionError>,
+    /// [PublicKey::verify_signature()] of the registered public key type
+    verify_signature: VerifySignature,
+    /// [PublicKey::key_identifier()] of the registered public key type
+    key_identifier: fn(&PublicKeyInfo) -> Option<Vec<u8>>,
+}
+
+impl RegisteredAlgorithm {
+    /// Erases the methods of `S` and `P`. Returns `None`, if `S` does not have a single algorithm.
+    fn new<S: Signature + 'static, P: PublicKey<S> + 'static>() -> Option<Self> {
+        Some(Self {
+            signature_algorithm: S::algorithm_identifier()?,
+            check_algorithm: S::check_algorithm,
+            check_signature: |algorithm, signature| {
+                S::try_from_algorithm_and_bytes(algorithm, signature).map(drop)
+            },
+            check_public_key: |public_key_info| {
+                decode_public_key::<S, P>(public_key_info).map(drop)
+            },
+            verify_signature: |public_key_info, algorithm, signature, data| {
+                let public_key = decode_public_key::<S, P>(public_key_info.clone())
+                    .map_err(|_| PublicKeyError::BadPublicKeyInfo)?;
+                let signature = S::try_from_algorithm_and_bytes(algorithm, signature)
+                    .map_err(|_| PublicKeyError::BadSignature)?;
+                public_key.verify_signature(&signature, data)
+            },
+            key_identifier: |public_key_info| {
+                decode_public_key::<S, P>(public_key_info.clone())
+                    .ok()?
+                    .key_identifier()
+            },
+        })
+    }
+}
+
+/// Registers the signature algorithm of `S`, whose signatures are verified using public keys of
+/// type `P`. Afterwards, [AnySignature]s and [AnyPublicKey]s of this algorithm can be decoded and
+/// verified. Replaces any algorithm previously registered with the same OID.
+///
+/// The registry is shared by the whole process, so that algorithms only need to be registered
+/// once, e.g. when starting up a client or home server.
+///
+/// Fails, if `S` does not have a single [Signature::algorithm_identifier()], like [AnySignature]
+/// itself.
+pub fn register_algorithm<S: Signature + 'static, P: PublicKey<S> + 'static>(
+) -> Result<(), ConversionError> {
+    let algorithm = RegisteredAlgorithm::new::<S, P>().ok_or_else(|| {
+        InvalidInput::Malformed(
+            "Only signature types with a single algorithm can be registered".to_string(),
+        )
+    })?;
+    log::trace!(
+        "[register_algorithm()] Registering algorithm {}",
+        algorithm.signature_algorithm.oid
+    );
+    REGISTRY
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(algorithm.signature_algorithm.oid, algorithm);
+    Ok(())
+}
+
+/// Returns the signature algorithms registered using [register_algorithm()], ordered by OID.
+pub fn registered_algorithms() -> Vec<AlgorithmIdentifierOwned> {
+    registry()
+        .into_iter()
+        .map(|algorithm| algorithm.signature_algorithm)
+        .collect()
+}
+
+/// Returns a copy of all registered algorithms, so that the registry is not locked while calling
+/// into them.
+fn registry() -> Vec<RegisteredAlgorithm> {
+    REGISTRY
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .values()
+        .cloned()
+        .collect()
+}
+
+/// Looks up the algorithm registered with the OID of `algorithm`.
+fn registered(algorithm: &AlgorithmIdentifierOwned) -> Result<RegisteredAlgorithm, InvalidCert> {
+    let registered = REGISTRY
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&algorithm.oid)
+        .cloned();
+    registered.ok_or_else(|| {
+        log::debug!(
+            "[registered()] Algorithm {} has not been registered",
+            algorithm.oid
+        );
+        InvalidCert::UnsupportedAlgorithm { oid: algorithm.oid }
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A [Signature] of any algorithm registered using [register_algorithm()]. Together with
+/// [AnyPublicKey], this allows handling [IdCert](crate::certs::idcert::IdCert)s,
+/// [IdCsr](crate::certs::idcsr::IdCsr)s and [IdCrl](crate::certs::idcrl::IdCrl)s of different
+/// algorithms using the same types, e.g. `IdCert<AnySignature, AnyPublicKey>`. The algorithm is
+/// determined at runtime, when decoding a signature.
+///
+/// An `AnySignature` stores the algorithm it has been created with, as well as the encoded
+/// signature. Use [AnySignature::downcast()] to obtain the signature of the registered type.
+///
+/// ## Decoding
+///
+/// Since the algorithm of an `AnySignature` is not known before decoding it, it can only be
+/// decoded using [Signature::try_from_algorithm_and_bytes()], which fails for algorithms which have
+/// not been registered. [Signature::try_from_bytes()] always fails.
+pub struct AnySignature {
+    algorithm: AlgorithmIdentifierOwned,
+    signature: Vec<u8>,
+}
+
+impl AnySignature {
+    /// Creates an [AnySignature] from a signature of a concrete type. Its algorithm does not need
+    /// to be registered, but verifying the signature will fail if it is not.
+    pub fn new<S: Signature>(signature: &S) -> Self {
+        Self {
+            algorithm: signature.signature_algorithm(),
+            signature: signature.to_bytes(),
+        }
+    }
+
+    /// Converts this signature into a signature of the concrete type `S`. Fails, if `S` does not
+    /// support the algorithm of this signature.
+    pub fn downcast<S: Signature>(&self) -> Result<S, ConversionError> {
+        S::try_from_algorithm_and_bytes(&self.algorithm, &self.signature)
+    }
+}
+
+impl Display for AnySignature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:", self.algorithm.oid)?;
+        for byte in self.signature.iter() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl SignatureBitStringEncoding for AnySignature {
+    fn to_bitstring(&self) -> der::Result<BitString> {
+        BitString::from_bytes(&self.signature)
+    }
+}
+
+impl Signature for AnySignature {
+    type Signature = Vec<u8>;
+
+    fn as_signature(&self) -> &Self::Signature {
+        &self.signature
+    }
+
+    /// Returns `None`, as an [AnySignature] does not have a single algorithm. Use
+    /// [Signature::signature_algorithm()] to obtain the algorithm of a signature value, or
+    /// [registered_algorithms()] for all supported algorithms.
+    fn algorithm_identifier() -> Option<AlgorithmIdentifierOwned> {
+        None
+    }
+
+    fn signature_algorithm(&self) -> AlgorithmIdentifierOwned {
+        self.algorithm.clone()
+    }
+
+    fn check_algorithm(algorithm: &AlgorithmIdentifierOwned) -> Result<(), InvalidCert> {
+        (registered(algorithm)?.check_algorithm)(algorithm)
+    }
+
+    fn try_from_algorithm_and_bytes(
+        algorithm: &AlgorithmIdentifierOwned,
+        signature: &[u8],
+    ) -> Result<Self, ConversionError> {
+        (registered(algorithm)?.check_signature)(algorithm, signature)?;
+        Ok(Self {
+            algorithm: algorithm.clone(),
+            signature: signature.to_vec(),
+        })
+    }
+
+    fn try_from_bytes(_signature: &[u8]) -> Result<Self, ConversionError> {
+        Err(InvalidInput::Malformed(
+            "The algorithm of an AnySignature must be known to decode it".to_string(),
+        )
+        .into())
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.signature.clone()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A [PublicKey] of any algorithm registered using [register_algorithm()], verifying
+/// [AnySignature]s. The public key is decoded using the registered public key types, until one of
+/// them accepts it.
+///
+/// An `AnyPublicKey` stores the encoded public key, as well as the signature algorithm it has been
+/// decoded or created for, if known. Use [AnyPublicKey::downcast()] to obtain the public key of the
+/// registered type.
+pub struct AnyPublicKey {
+    signature_algorithm: Option<AlgorithmIdentifierOwned>,
+    public_key_info: PublicKeyInfo,
+}
+
+impl AnyPublicKey {
+    /// Creates an [AnyPublicKey] from a public key of a concrete type. Its algorithm does not need
+    /// to be registered, but verifying signatures will fail if it is not.
+    pub fn new<S: Signature, P: PublicKey<S>>(public_key: &P) -> Self {
+        Self {
+            signature_algorithm: public_key.algorithm_identifier(),
+            public_key_info: public_key.public_key_info(),
+        }
+    }
+
+    /// Converts this public key into a public key of the concrete type `P`. Fails, if `P` cannot
+    /// decode this public key, or decodes it as a key of a different algorithm.
+    pub fn downcast<S: Signature, P: PublicKey<S>>(&self) -> Result<P, ConversionError> {
+        decode_public_key::<S, P>(self.public_key_info.clone())
+    }
+}
+
+impl PublicKey<AnySignature> for AnyPublicKey {
+    /// Verifies the signature using the algorithm registered for the algorithm of the signature.
+    /// Fails with [PublicKeyError::BadPublicKeyInfo], if this public key cannot be used with that
+    /// algorithm.
+    fn verify_signature(
+        &self,
+        signature: &AnySignature,
+        data: &[u8],
+    ) -> Result<(), PublicKeyError> {
+        let registered =
+            registered(&signature.algorithm).map_err(|_| PublicKeyError::BadSignature)?;
+        (registered.verify_signature)(
+            &self.public_key_info,
+            &signature.algorithm,
+            &signature.signature,
+            data,
+        )
+    }
+
+    fn public_key_info(&self) -> PublicKeyInfo {
+        self.public_key_info.clone()
+    }
+
+    fn algorithm_identifier(&self) -> Option<AlgorithmIdentifierOwned> {
+        self.signature_algorithm.clone()
+    }
+
+    fn try_from_public_key_info(public_key_info: PublicKeyInfo) -> Result<Self, ConversionError> {
+        for registered in registry() {
+            if (registered.check_public_key)(public_key_info.clone()).is_ok() {
+                return Ok(Self {
+                    signature_algorithm: Some(registered.signature_algorithm),
+                    public_key_info,
+                });
+            }
+        }
+        log::debug!(
+            "[AnyPublicKey::try_from_public_key_info()] No registered algorithm accepts public keys of algorithm {}",
+            public_key_info.algorithm.oid
+        );
+        Err(InvalidCert::UnsupportedAlgorithm {
+            oid: public_key_info.algorithm.oid,
+        }
+        .into())
+    }
+
+    fn key_identifier(&self) -> Option<Vec<u8>> {
+        let registered = registered(self.signature_algorithm.as_ref()?).ok()?;
+        (registered.key_identifier)(&self.public_key_info)
+    }
+}
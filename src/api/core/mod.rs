@@ -2,15 +2,15 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use std::time::UNIX_EPOCH;
-
 use crate::types::x509_cert::SerialNumber;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
 use crate::certs::idcert::IdCert;
+pub use crate::certs::idcert::IdCertExt;
 use crate::certs::idcsr::IdCsr;
 use crate::certs::{PublicKeyInfo, SessionId};
+use crate::clock::{Clock, FixedClock, SystemClock};
 use crate::errors::{ConversionError, RequestError};
 use crate::key::PublicKey;
 use crate::signature::Signature;
@@ -20,11 +20,9 @@ use crate::types::{ChallengeString, EncryptedPkm};
 use super::{HttpClient, HttpResult};
 
 /// Get the current UNIX timestamp according to the system clock.
+#[deprecated(note = "Use the `Clock` of the `HttpClient` or `SystemClock::now()` instead")]
 pub fn current_unix_time() -> u64 {
-    std::time::SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_secs()
+    SystemClock::new().now()
 }
 
 // Core Routes: No registration needed
@@ -59,12 +57,7 @@ impl HttpClient {
         let pem = HttpClient::handle_response::<String>(request_response).await?;
         log::debug!("Received IdCert: \n{}", pem);
         let id_cert = IdCert::<S, P>::from_pem_unchecked(&pem)?;
-        match id_cert.full_verify_home_server(
-            std::time::SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_secs(),
-        ) {
+        match id_cert.full_verify_home_server_with_clock(self.clock.as_ref()) {
             Ok(_) => (),
             Err(e) => return Err(RequestError::ConversionError(e.into())),
         };
@@ -77,7 +70,9 @@ impl HttpClient {
     /// ## Safety guarantees
     ///
     /// The resulting [IdCert] is verified and has the same safety guarantees as specified under
-    /// [IdCert::full_verify_home_server()], as this method calls that method internally.
+    /// [IdCert::full_verify_home_server()], as this method calls that method internally. If no
+    /// timestamp is provided, the certificate is verified against the clock of this client, see
+    /// [HttpClient::set_clock()].
     pub async fn get_server_id_cert<S: Signature, P: PublicKey<S>>(
         &self,
         unix_time: Option<u64>,
@@ -92,7 +87,13 @@ impl HttpClient {
         let response = request.send().await;
         let pem = HttpClient::handle_response::<String>(response).await?;
         let id_cert = IdCert::<S, P>::from_pem_unchecked(&pem)?;
-        match id_cert.full_verify_home_server(unix_time.unwrap_or(current_unix_time())) {
+        let verification = match unix_time {
+            Some(time) => id_cert.full_verify_home_server_with_clock(
+                &FixedClock::new(time).with_skew_tolerance(self.clock.skew_tolerance()),
+            ),
+            None => id_cert.full_verify_home_server_with_clock(self.clock.as_ref()),
+        };
+        match verification {
             Ok(_) => (),
             Err(e) => return Err(RequestError::ConversionError(e.into())),
         };
@@ -288,20 +289,9 @@ impl HttpClient {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-/// Represents an [IdCert] with an additional field `invalidated` which indicates whether the
-/// certificate has been invalidated. This type is used in the API as a response to the
-/// `GET /.p2/core/v1/idcert/actor/:fid`
-/// route. Can be converted to and (try)from [IdCertExtJson].
-pub struct IdCertExt<S: Signature, P: PublicKey<S>> {
-    /// The [IdCert] itself
-    pub id_cert: IdCert<S, P>,
-    /// Whether the certificate has been marked as invalidated
-    pub invalidated: bool,
-}
-
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
-/// Stringly typed version of [IdCertExt], used for serialization and deserialization.
+/// Stringly typed version of [IdCertExt], used for serialization and deserialization. Can be
+/// converted to and (try)from [IdCertExt].
 pub struct IdCertExtJson {
     /// The [IdCert] as a PEM encoded string
     pub id_cert: String,
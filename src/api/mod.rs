@@ -2,10 +2,13 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use std::sync::Arc;
+
 use serde::Deserialize;
 use serde_json::from_str;
 use url::Url;
 
+use crate::clock::{Clock, SystemClock};
 use crate::errors::RequestError;
 
 /// The `core` module contains all API routes for implementing the core polyproto protocol in a client or server.
@@ -32,6 +35,7 @@ pub struct HttpClient {
     pub client: reqwest::Client,
     headers: reqwest::header::HeaderMap,
     pub(crate) url: Url,
+    pub(crate) clock: Arc<dyn Clock + Send + Sync>,
 }
 
 /// A type alias for the result of an HTTP request.
@@ -53,6 +57,7 @@ impl HttpClient {
             client,
             headers,
             url,
+            clock: Arc::new(SystemClock::new()),
         })
     }
 
@@ -72,6 +77,12 @@ impl HttpClient {
         Ok(())
     }
 
+    /// Sets the [Clock] used to verify time-sensitive responses, such as the validity period of
+    /// received [IdCert](crate::certs::idcert::IdCert)s. Defaults to the [SystemClock].
+    pub fn set_clock(&mut self, clock: impl Clock + Send + Sync + 'static) {
+        self.clock = Arc::new(clock);
+    }
+
     /// Sends a request and returns the response.
     pub async fn request<T: Into<reqwest::Body>>(
         &self,
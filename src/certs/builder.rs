@@ -0,0 +1,354 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::time::{Duration, UNIX_EPOCH};
+
+use der::asn1::Uint;
+use spki::AlgorithmIdentifierOwned;
+use x509_cert::name::Name;
+use x509_cert::time::{Time, Validity};
+
+use crate::errors::BuilderError;
+use crate::key::{PrivateKey, PublicKey};
+use crate::signature::Signature;
+use crate::types::FederationId;
+use crate::Constrained;
+
+use super::capabilities::Capabilities;
+use super::idcert::IdCert;
+use super::idcerttbs::IdCertTbs;
+use super::idcsr::IdCsr;
+use super::name::{ActorName, HomeServerName};
+use super::policy::ValidationPolicy;
+use super::{signing_algorithm, SessionId, Target};
+
+/// Builder for [IdCsr]s. Derives the subject [Name] of the CSR from typed components instead of
+/// requiring a hand-written RDN sequence, and reports missing or inconsistent inputs before
+/// anything is signed.
+///
+/// - Actor CSRs require a [FederationId] and a [SessionId]. The home server domain is derived from
+///   the federation ID; if it is given explicitly, it must match.
+/// - Home server CSRs require a home server domain, and must not have a [FederationId] or
+///   [SessionId].
+///
+/// If no [Capabilities] are supplied, [Capabilities::default_actor()] or
+/// [Capabilities::default_home_server()] are used, depending on the [Target].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct IdCsrBuilder {
+    federation_id: Option<FederationId>,
+    session_id: Option<SessionId>,
+    home_server_domain: Option<String>,
+    capabilities: Option<Capabilities>,
+}
+
+impl IdCsrBuilder {
+    /// Creates a new, empty [IdCsrBuilder].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the [FederationId] of the actor requesting the certificate.
+    pub fn federation_id(mut self, federation_id: FederationId) -> Self {
+        self.federation_id = Some(federation_id);
+        self
+    }
+
+    /// Sets the [SessionId] of the actor requesting the certificate.
+    pub fn session_id(mut self, session_id: SessionId) -> Self {
+        self.session_id = Some(session_id);
+        self
+    }
+
+    /// Sets the domain of the home server, e.g. `polyphony.chat`.
+    pub fn home_server_domain(mut self, domain: &str) -> Self {
+        self.home_server_domain = Some(domain.to_string());
+        self
+    }
+
+    /// Overrides the [Capabilities] preset which would otherwise be chosen based on the [Target].
+    pub fn capabilities(mut self, capabilities: Capabilities) -> Self {
+        self.capabilities = Some(capabilities);
+        self
+    }
+
+    /// Derives the subject [Name] for the given [Target] from the supplied components, without
+    /// signing anything. The resulting `Name` passes [Constrained] validation for `target`.
+    pub fn subject(&self, target: Target) -> Result<Name, BuilderError> {
+        let name = match target {
+            Target::Actor => self.actor_subject()?,
+            Target::HomeServer => self.home_server_subject()?,
+        };
+        name.validate(Some(target))?;
+        Ok(name)
+    }
+
+    /// Builds and signs an actor [IdCsr]. See [IdCsrBuilder] for the required components.
+    pub fn build_actor<S: Signature, P: PublicKey<S>>(
+        self,
+        signing_key: &impl PrivateKey<S, PublicKey = P>,
+    ) -> Result<IdCsr<S, P>, BuilderError> {
+        self.build(Target::Actor, signing_key)
+    }
+
+    /// Builds and signs a home server [IdCsr]. See [IdCsrBuilder] for the required components.
+    pub fn build_home_server<S: Signature, P: PublicKey<S>>(
+        self,
+        signing_key: &impl PrivateKey<S, PublicKey = P>,
+    ) -> Result<IdCsr<S, P>, BuilderError> {
+        self.build(Target::HomeServer, signing_key)
+    }
+
+    fn build<S: Signature, P: PublicKey<S>>(
+        self,
+        target: Target,
+        signing_key: &impl PrivateKey<S, PublicKey = P>,
+    ) -> Result<IdCsr<S, P>, BuilderError> {
+        let subject = self.subject(target)?;
+        let capabilities = match self.capabilities {
+            Some(capabilities) => capabilities,
+            None => match target {
+                Target::Actor => Capabilities::default_actor(),
+                Target::HomeServer => Capabilities::default_home_server(),
+            },
+        };
+        capabilities.validate(Some(target))?;
+        log::trace!(
+            "[IdCsrBuilder::build()] building CSR for subject {} with target {:?}",
+            subject,
+            target
+        );
+        Ok(IdCsr::new(
+            &subject,
+            signing_key,
+            &capabilities,
+            Some(target),
+        )?)
+    }
+
+    fn actor_subject(&self) -> Result<Name, BuilderError> {
+        let federation_id = self
+            .federation_id
+            .as_ref()
+            .ok_or(BuilderError::MissingField {
+                field: "federation_id",
+            })?;
+        let session_id = self.session_id.as_ref().ok_or(BuilderError::MissingField {
+            field: "session_id",
+        })?;
+        // A valid FederationId always contains exactly one @
+        let (_, domain) =
+            federation_id
+                .split_once('@')
+                .ok_or(BuilderError::InvalidHomeServerDomain(
+                    federation_id.to_string(),
+                ))?;
+        if let Some(home_server_domain) = &self.home_server_domain {
+            if home_server_domain != domain {
+                return Err(BuilderError::DomainMismatch {
+                    federation_id: federation_id.to_string(),
+                    home_server_domain: home_server_domain.clone(),
+                });
+            }
+        }
+        home_server_name(domain)?;
+        Ok(ActorName::new(federation_id, session_id)?.into())
+    }
+
+    fn home_server_subject(&self) -> Result<Name, BuilderError> {
+        if self.federation_id.is_some() {
+            return Err(BuilderError::UnexpectedField {
+                field: "federation_id",
+            });
+        }
+        if self.session_id.is_some() {
+            return Err(BuilderError::UnexpectedField {
+                field: "session_id",
+            });
+        }
+        let domain = self
+            .home_server_domain
+            .as_ref()
+            .ok_or(BuilderError::MissingField {
+                field: "home_server_domain",
+            })?;
+        Ok(home_server_name(domain)?.into())
+    }
+}
+
+/// Builder for [IdCertTbs]s and [IdCert]s, issued by a home server in exchange for an [IdCsr].
+/// Derives the issuer [Name] from the home server domain and the [Validity] from a starting point
+/// in time and a duration, and reports missing or inconsistent inputs before anything is signed.
+///
+/// Besides the polyproto specification, the certificate must meet the rules of the
+/// [ValidationPolicy] set using [IdCertBuilder::policy()], which defaults to
+/// [ValidationPolicy::spec_default()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdCertBuilder<S: Signature, P: PublicKey<S>> {
+    id_csr: IdCsr<S, P>,
+    serial_number: Option<Uint>,
+    home_server_domain: Option<String>,
+    not_before: Option<u64>,
+    valid_for: Option<Duration>,
+    policy: ValidationPolicy,
+}
+
+impl<S: Signature, P: PublicKey<S>> IdCertBuilder<S, P> {
+    /// Creates a new [IdCertBuilder] for the given [IdCsr].
+    pub fn new(id_csr: IdCsr<S, P>) -> Self {
+        Self {
+            id_csr,
+            serial_number: None,
+            home_server_domain: None,
+            not_before: None,
+            valid_for: None,
+            policy: ValidationPolicy::spec_default(),
+        }
+    }
+
+    /// Sets the serial number of the certificate.
+    pub fn serial_number(mut self, serial_number: Uint) -> Self {
+        self.serial_number = Some(serial_number);
+        self
+    }
+
+    /// Sets the domain of the issuing home server, e.g. `polyphony.chat`.
+    pub fn home_server_domain(mut self, domain: &str) -> Self {
+        self.home_server_domain = Some(domain.to_string());
+        self
+    }
+
+    /// Sets the UNIX timestamp from which on the certificate is valid.
+    pub fn not_before(mut self, not_before: u64) -> Self {
+        self.not_before = Some(not_before);
+        self
+    }
+
+    /// Sets the duration for which the certificate is valid, starting at `not_before`.
+    pub fn valid_for(mut self, duration: Duration) -> Self {
+        self.valid_for = Some(duration);
+        self
+    }
+
+    /// Sets the [ValidationPolicy] the certificate must meet, e.g. to enforce a maximum
+    /// certificate lifetime.
+    pub fn policy(mut self, policy: ValidationPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Builds an unsigned actor [IdCertTbs], to be signed by the private key belonging to
+    /// `issuer_public_key`. See [IdCertTbs::from_actor_csr()] for the guarantees given by the
+    /// result.
+    pub fn build_actor_tbs(
+        self,
+        issuer_public_key: &P,
+        signature_algorithm: AlgorithmIdentifierOwned,
+    ) -> Result<IdCertTbs<S, P>, BuilderError> {
+        let policy = self.policy.clone();
+        let (id_csr, serial_number, issuer, validity) = self.into_parts()?;
+        let id_cert_tbs = IdCertTbs::from_actor_csr(
+            id_csr,
+            issuer_public_key,
+            serial_number,
+            signature_algorithm,
+            issuer,
+            validity,
+        )?;
+        id_cert_tbs.validate_with_policy(Some(Target::Actor), &policy)?;
+        Ok(id_cert_tbs)
+    }
+
+    /// Builds an unsigned home server [IdCertTbs], to be signed by the private key belonging to
+    /// `issuer_public_key`. See [IdCertTbs::from_ca_csr()] for the guarantees given by the result.
+    pub fn build_home_server_tbs(
+        self,
+        issuer_public_key: &P,
+        signature_algorithm: AlgorithmIdentifierOwned,
+    ) -> Result<IdCertTbs<S, P>, BuilderError> {
+        let policy = self.policy.clone();
+        let (id_csr, serial_number, issuer, validity) = self.into_parts()?;
+        let id_cert_tbs = IdCertTbs::from_ca_csr(
+            id_csr,
+            issuer_public_key,
+            serial_number,
+            signature_algorithm,
+            issuer,
+            validity,
+        )?;
+        id_cert_tbs.validate_with_policy(Some(Target::HomeServer), &policy)?;
+        Ok(id_cert_tbs)
+    }
+
+    /// Builds an actor [IdCert], signed by the home server's `signing_key`. The certificate is
+    /// only signed after all inputs have been validated.
+    pub fn build_actor(
+        self,
+        signing_key: &impl PrivateKey<S, PublicKey = P>,
+    ) -> Result<IdCert<S, P>, BuilderError> {
+        let id_cert_tbs = self.build_actor_tbs(
+            signing_key.pubkey(),
+            signing_algorithm(signing_key.algorithm_identifier())?,
+        )?;
+        sign(id_cert_tbs, signing_key)
+    }
+
+    /// Builds a home server [IdCert], signed by the `signing_key` of the issuing home server. The
+    /// certificate is only signed after all inputs have been validated.
+    pub fn build_home_server(
+        self,
+        signing_key: &impl PrivateKey<S, PublicKey = P>,
+    ) -> Result<IdCert<S, P>, BuilderError> {
+        let id_cert_tbs = self.build_home_server_tbs(
+            signing_key.pubkey(),
+            signing_algorithm(signing_key.algorithm_identifier())?,
+        )?;
+        sign(id_cert_tbs, signing_key)
+    }
+
+    fn into_parts(self) -> Result<(IdCsr<S, P>, Uint, Name, Validity), BuilderError> {
+        let serial_number = self.serial_number.ok_or(BuilderError::MissingField {
+            field: "serial_number",
+        })?;
+        let domain = self.home_server_domain.ok_or(BuilderError::MissingField {
+            field: "home_server_domain",
+        })?;
+        let not_before = self.not_before.ok_or(BuilderError::MissingField {
+            field: "not_before",
+        })?;
+        let valid_for = self
+            .valid_for
+            .ok_or(BuilderError::MissingField { field: "valid_for" })?;
+        let issuer = home_server_name(&domain)?.into();
+        let not_after = not_before
+            .checked_add(valid_for.as_secs())
+            .ok_or(BuilderError::InvalidValidity)?;
+        let validity = Validity {
+            not_before: unix_time(not_before)?,
+            not_after: unix_time(not_after)?,
+        };
+        Ok((self.id_csr, serial_number, issuer, validity))
+    }
+}
+
+fn sign<S: Signature, P: PublicKey<S>>(
+    id_cert_tbs: IdCertTbs<S, P>,
+    signing_key: &impl PrivateKey<S, PublicKey = P>,
+) -> Result<IdCert<S, P>, BuilderError> {
+    let signature = signing_key.sign(&id_cert_tbs.clone().to_der()?)?;
+    Ok(IdCert::new(id_cert_tbs, signature))
+}
+
+fn unix_time(secs: u64) -> Result<Time, BuilderError> {
+    let system_time = UNIX_EPOCH
+        .checked_add(Duration::from_secs(secs))
+        .ok_or(BuilderError::InvalidValidity)?;
+    Time::try_from(system_time).map_err(|_| BuilderError::InvalidValidity)
+}
+
+/// Creates the [HomeServerName] for `domain`, reporting invalid domains as
+/// [BuilderError::InvalidHomeServerDomain].
+fn home_server_name(domain: &str) -> Result<HomeServerName, BuilderError> {
+    HomeServerName::new(domain)
+        .map_err(|_| BuilderError::InvalidHomeServerDomain(domain.to_string()))
+}
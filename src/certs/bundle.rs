@@ -0,0 +1,294 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use der::pem::LineEnding;
+use der::Decode;
+use spki::SubjectPublicKeyInfoOwned;
+
+use crate::errors::{ConversionError, PemBundleError};
+use crate::key::PublicKey;
+use crate::signature::Signature;
+
+use super::idcert::IdCert;
+use super::idcsr::IdCsr;
+use super::PublicKeyInfo;
+
+/// PEM label of an [IdCert]
+pub const PEM_LABEL_CERTIFICATE: &str = "CERTIFICATE";
+/// PEM label of an [IdCsr]
+pub const PEM_LABEL_CERTIFICATE_REQUEST: &str = "CERTIFICATE REQUEST";
+/// Legacy PEM label of an [IdCsr], as emitted by some older tools. Accepted when parsing, but never
+/// written.
+pub const PEM_LABEL_NEW_CERTIFICATE_REQUEST: &str = "NEW CERTIFICATE REQUEST";
+/// PEM label of a [PublicKeyInfo]
+pub const PEM_LABEL_PUBLIC_KEY: &str = "PUBLIC KEY";
+
+const PEM_BEGIN: &str = "-----BEGIN ";
+const PEM_END: &str = "-----END ";
+const PEM_BOUNDARY_SUFFIX: &str = "-----";
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+/// A single object contained in a [PemBundle].
+pub enum PemObject<S: Signature, P: PublicKey<S>> {
+    /// A certificate, labelled `CERTIFICATE`
+    IdCert(IdCert<S, P>),
+    /// A certificate signing request, labelled `CERTIFICATE REQUEST` or
+    /// `NEW CERTIFICATE REQUEST`
+    IdCsr(IdCsr<S, P>),
+    /// A public key, labelled `PUBLIC KEY`
+    PublicKeyInfo(PublicKeyInfo),
+}
+
+impl<S: Signature, P: PublicKey<S>> PemObject<S, P> {
+    /// The PEM label under which this object is written.
+    pub fn label(&self) -> &'static str {
+        match self {
+            PemObject::IdCert(_) => PEM_LABEL_CERTIFICATE,
+            PemObject::IdCsr(_) => PEM_LABEL_CERTIFICATE_REQUEST,
+            PemObject::PublicKeyInfo(_) => PEM_LABEL_PUBLIC_KEY,
+        }
+    }
+
+    /// Decodes the DER encoded `der` as the type denoted by the PEM `label`.
+    fn from_der(label: &str, der: &[u8]) -> Result<Option<Self>, ConversionError> {
+        Ok(Some(match label {
+            PEM_LABEL_CERTIFICATE => PemObject::IdCert(IdCert::from_der_unchecked(der)?),
+            PEM_LABEL_CERTIFICATE_REQUEST | PEM_LABEL_NEW_CERTIFICATE_REQUEST => {
+                PemObject::IdCsr(IdCsr::from_der_unchecked(der)?)
+            }
+            PEM_LABEL_PUBLIC_KEY => {
+                PemObject::PublicKeyInfo(SubjectPublicKeyInfoOwned::from_der(der)?.into())
+            }
+            _ => return Ok(None),
+        }))
+    }
+
+    /// Encode this object as DER, returning a byte vector.
+    fn into_der(self) -> Result<Vec<u8>, ConversionError> {
+        match self {
+            PemObject::IdCert(cert) => cert.to_der(),
+            PemObject::IdCsr(csr) => csr.to_der(),
+            PemObject::PublicKeyInfo(public_key_info) => public_key_info.to_der(),
+        }
+    }
+}
+
+impl<S: Signature, P: PublicKey<S>> From<IdCert<S, P>> for PemObject<S, P> {
+    fn from(value: IdCert<S, P>) -> Self {
+        PemObject::IdCert(value)
+    }
+}
+
+impl<S: Signature, P: PublicKey<S>> From<IdCsr<S, P>> for PemObject<S, P> {
+    fn from(value: IdCsr<S, P>) -> Self {
+        PemObject::IdCsr(value)
+    }
+}
+
+impl<S: Signature, P: PublicKey<S>> From<PublicKeyInfo> for PemObject<S, P> {
+    fn from(value: PublicKeyInfo) -> Self {
+        PemObject::PublicKeyInfo(value)
+    }
+}
+
+/// A sequence of PEM encoded [IdCert]s, [IdCsr]s and [PublicKeyInfo]s, stored in a single string.
+/// Bundles are commonly used to transfer certificate chains, or to store multiple certificates in
+/// one file.
+///
+/// When parsing a bundle, any text outside of the PEM blocks is ignored. The order of the objects
+/// is retained.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PemBundle<S: Signature, P: PublicKey<S>> {
+    /// The objects contained in this bundle, in order of appearance
+    pub objects: Vec<PemObject<S, P>>,
+}
+
+impl<S: Signature, P: PublicKey<S>> PemBundle<S, P> {
+    /// Creates a new [PemBundle] from the given objects.
+    pub fn new(objects: Vec<PemObject<S, P>>) -> Self {
+        Self { objects }
+    }
+
+    /// Create an unchecked [PemBundle] from a string containing any number of PEM blocks. The
+    /// caller is responsible for verifying the correctness of the contained objects, for example
+    /// using [verify_chain()](super::chain::verify_chain()) or the [Constrained](crate::Constrained)
+    /// trait, before using them.
+    ///
+    /// Fails on the first block which is not terminated, has an unsupported label or cannot be
+    /// decoded. The returned error carries the index of that block.
+    pub fn from_pem_unchecked(pem: &str) -> Result<Self, PemBundleError> {
+        let mut objects = Vec::new();
+        for (index, block) in split_pem_blocks(pem)?.into_iter().enumerate() {
+            let (label, der) = der::pem::decode_vec(block.as_bytes()).map_err(|e| {
+                PemBundleError::InvalidBlock {
+                    index,
+                    source: der::Error::from(e).into(),
+                }
+            })?;
+            match PemObject::from_der(label, &der) {
+                Ok(Some(object)) => objects.push(object),
+                Ok(None) => {
+                    return Err(PemBundleError::UnsupportedLabel {
+                        index,
+                        label: label.to_string(),
+                    })
+                }
+                Err(source) => return Err(PemBundleError::InvalidBlock { index, source }),
+            }
+        }
+        Ok(Self { objects })
+    }
+
+    /// Encode all objects of this bundle as PEM, returning a string with one block per object.
+    pub fn to_pem(self, line_ending: LineEnding) -> Result<String, PemBundleError> {
+        let mut pem = String::new();
+        for (index, object) in self.objects.into_iter().enumerate() {
+            let label = object.label();
+            let block = object
+                .into_der()
+                .and_then(|der| {
+                    Ok(der::pem::encode_string(label, line_ending, &der)
+                        .map_err(der::Error::from)?)
+                })
+                .map_err(|source| PemBundleError::InvalidBlock { index, source })?;
+            pem.push_str(&block);
+        }
+        Ok(pem)
+    }
+
+    /// Returns an iterator over all [IdCert]s in this bundle.
+    pub fn id_certs(&self) -> impl Iterator<Item = &IdCert<S, P>> {
+        self.objects.iter().filter_map(|object| match object {
+            PemObject::IdCert(cert) => Some(cert),
+            _ => None,
+        })
+    }
+
+    /// Returns an iterator over all [IdCsr]s in this bundle.
+    pub fn id_csrs(&self) -> impl Iterator<Item = &IdCsr<S, P>> {
+        self.objects.iter().filter_map(|object| match object {
+            PemObject::IdCsr(csr) => Some(csr),
+            _ => None,
+        })
+    }
+
+    /// Returns an iterator over all [PublicKeyInfo]s in this bundle.
+    pub fn public_key_infos(&self) -> impl Iterator<Item = &PublicKeyInfo> {
+        self.objects.iter().filter_map(|object| match object {
+            PemObject::PublicKeyInfo(public_key_info) => Some(public_key_info),
+            _ => None,
+        })
+    }
+
+    /// Converts this bundle into the [IdCert]s it contains, retaining their order. Fails with
+    /// [PemBundleError::UnexpectedLabel], if the bundle contains any other objects.
+    pub fn into_id_certs(self) -> Result<Vec<IdCert<S, P>>, PemBundleError> {
+        self.objects
+            .into_iter()
+            .enumerate()
+            .map(|(index, object)| match object {
+                PemObject::IdCert(cert) => Ok(cert),
+                other => Err(PemBundleError::UnexpectedLabel {
+                    index,
+                    expected: PEM_LABEL_CERTIFICATE,
+                    found: other.label(),
+                }),
+            })
+            .collect()
+    }
+}
+
+impl<S: Signature, P: PublicKey<S>> From<Vec<IdCert<S, P>>> for PemBundle<S, P> {
+    fn from(value: Vec<IdCert<S, P>>) -> Self {
+        Self::new(value.into_iter().map(PemObject::IdCert).collect())
+    }
+}
+
+/// Splits `pem` into its PEM blocks, each spanning from its "BEGIN" line to the matching "END"
+/// line. Text outside of the blocks is skipped.
+fn split_pem_blocks(pem: &str) -> Result<Vec<&str>, PemBundleError> {
+    let mut blocks = Vec::new();
+    // Start offset and label of the block which is currently open
+    let mut open_block: Option<(usize, &str)> = None;
+    let mut offset = 0;
+    for line in pem.split_inclusive('\n') {
+        let trimmed = line.trim();
+        match open_block {
+            None => {
+                if let Some(label) = boundary_label(trimmed, PEM_BEGIN) {
+                    let indentation = line.len() - line.trim_start().len();
+                    open_block = Some((offset + indentation, label));
+                }
+            }
+            Some((start, label)) => {
+                if let Some(end_label) = boundary_label(trimmed, PEM_END) {
+                    if end_label != label {
+                        return Err(PemBundleError::Unterminated {
+                            index: blocks.len(),
+                            label: label.to_string(),
+                        });
+                    }
+                    let end = offset + line.trim_end().len();
+                    blocks.push(&pem[start..end]);
+                    open_block = None;
+                } else if boundary_label(trimmed, PEM_BEGIN).is_some() {
+                    return Err(PemBundleError::Unterminated {
+                        index: blocks.len(),
+                        label: label.to_string(),
+                    });
+                }
+            }
+        }
+        offset += line.len();
+    }
+    match open_block {
+        Some((_, label)) => Err(PemBundleError::Unterminated {
+            index: blocks.len(),
+            label: label.to_string(),
+        }),
+        None => Ok(blocks),
+    }
+}
+
+/// Returns the label of `line`, if it is an encapsulation boundary starting with `prefix`.
+fn boundary_label<'a>(line: &'a str, prefix: &str) -> Option<&'a str> {
+    line.strip_prefix(prefix)?.strip_suffix(PEM_BOUNDARY_SUFFIX)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn split_blocks() {
+        let pem = "subject=CN=first\n-----BEGIN A-----\nAAAA\n-----END A-----\n\n  -----BEGIN B-----\r\nBBBB\r\n-----END B-----\r\ntrailing text";
+        assert_eq!(
+            split_pem_blocks(pem).unwrap(),
+            vec![
+                "-----BEGIN A-----\nAAAA\n-----END A-----",
+                "-----BEGIN B-----\r\nBBBB\r\n-----END B-----"
+            ]
+        );
+        assert!(split_pem_blocks("no blocks here").unwrap().is_empty());
+        assert_eq!(
+            split_pem_blocks("-----BEGIN A-----\nAAAA\n-----END A-----\n-----BEGIN B-----\nBBBB\n")
+                .err()
+                .unwrap(),
+            PemBundleError::Unterminated {
+                index: 1,
+                label: "B".to_string()
+            }
+        );
+        assert_eq!(
+            split_pem_blocks("-----BEGIN A-----\nAAAA\n-----END B-----\n")
+                .err()
+                .unwrap(),
+            PemBundleError::Unterminated {
+                index: 0,
+                label: "A".to_string()
+            }
+        );
+    }
+}
@@ -0,0 +1,236 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::str::FromStr;
+
+use der::asn1::{OctetString, SetOfVec};
+use der::{Any, Decode, Encode};
+use spki::ObjectIdentifier;
+use x509_cert::attr::Attribute;
+use x509_cert::ext::Extension;
+
+use crate::errors::{ConversionError, InvalidInput};
+
+use super::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// A purpose for which the public key contained in a certificate may be used, in addition to or in
+/// place of the basic purposes indicated by [KeyUsage]. See
+/// <https://datatracker.ietf.org/doc/html/rfc5280#section-4.2.1.12>
+pub enum ExtendedKeyUsage {
+    /// TLS WWW server authentication.
+    ServerAuth,
+    /// TLS WWW client authentication.
+    ClientAuth,
+    /// Signing of downloadable executable code.
+    CodeSigning,
+    /// Email protection.
+    EmailProtection,
+    /// Binding the hash of an object to a time.
+    TimeStamping,
+    /// Signing OCSP responses.
+    OcspSigning,
+    /// Any purpose not covered by the other variants, identified by its OID.
+    Other(ObjectIdentifier),
+}
+
+impl From<ExtendedKeyUsage> for ObjectIdentifier {
+    fn from(value: ExtendedKeyUsage) -> Self {
+        let oid = match value {
+            ExtendedKeyUsage::ServerAuth => OID_EXTENDED_KEY_USAGE_SERVER_AUTH,
+            ExtendedKeyUsage::ClientAuth => OID_EXTENDED_KEY_USAGE_CLIENT_AUTH,
+            ExtendedKeyUsage::CodeSigning => OID_EXTENDED_KEY_USAGE_CODE_SIGNING,
+            ExtendedKeyUsage::EmailProtection => OID_EXTENDED_KEY_USAGE_EMAIL_PROTECTION,
+            ExtendedKeyUsage::TimeStamping => OID_EXTENDED_KEY_USAGE_TIME_STAMPING,
+            ExtendedKeyUsage::OcspSigning => OID_EXTENDED_KEY_USAGE_OCSP_SIGNING,
+            ExtendedKeyUsage::Other(oid) => return oid,
+        };
+        ObjectIdentifier::from_str(oid).expect("Error occurred when converting ExtendedKeyUsage to ObjectIdentifier. Please report this crash at https://github.com/polyphony-chat/polyproto")
+    }
+}
+
+impl From<ObjectIdentifier> for ExtendedKeyUsage {
+    fn from(value: ObjectIdentifier) -> Self {
+        match value.to_string().as_str() {
+            OID_EXTENDED_KEY_USAGE_SERVER_AUTH => ExtendedKeyUsage::ServerAuth,
+            OID_EXTENDED_KEY_USAGE_CLIENT_AUTH => ExtendedKeyUsage::ClientAuth,
+            OID_EXTENDED_KEY_USAGE_CODE_SIGNING => ExtendedKeyUsage::CodeSigning,
+            OID_EXTENDED_KEY_USAGE_EMAIL_PROTECTION => ExtendedKeyUsage::EmailProtection,
+            OID_EXTENDED_KEY_USAGE_TIME_STAMPING => ExtendedKeyUsage::TimeStamping,
+            OID_EXTENDED_KEY_USAGE_OCSP_SIGNING => ExtendedKeyUsage::OcspSigning,
+            _ => ExtendedKeyUsage::Other(value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+/// The ExtendedKeyUsages struct is a collection of [ExtendedKeyUsage] variants. An empty
+/// collection means that the extended key usage extension is absent, and that the purposes of
+/// the key are not restricted beyond its [KeyUsages].
+pub struct ExtendedKeyUsages {
+    /// Vector of ExtendedKeyUsage variants.
+    pub extended_key_usages: Vec<ExtendedKeyUsage>,
+    /// Whether the extended key usage extension is marked as critical. Decoded extensions keep
+    /// their flag, so that it is encoded unchanged. Defaults to `false`, so that relying parties
+    /// which do not support extended key usages can still use the certificate.
+    pub critical: bool,
+}
+
+impl ExtendedKeyUsages {
+    /// Creates a new, non-critical ExtendedKeyUsages struct from a slice of ExtendedKeyUsage
+    /// variants.
+    pub fn new(extended_key_usages: &[ExtendedKeyUsage]) -> Self {
+        ExtendedKeyUsages {
+            extended_key_usages: extended_key_usages.to_vec(),
+            critical: false,
+        }
+    }
+
+    /// Whether the extended key usage extension is absent.
+    pub fn is_empty(&self) -> bool {
+        self.extended_key_usages.is_empty()
+    }
+
+    /// Checks, if the key may be used for the given purpose. This is the case, if the extended key
+    /// usage extension is absent, or if it contains `purpose`.
+    pub fn permits(&self, purpose: ExtendedKeyUsage) -> bool {
+        self.is_empty() || self.extended_key_usages.contains(&purpose)
+    }
+
+    /// Converts the ExtendedKeyUsages into DER, as specified in RFC 5280:
+    ///
+    /// ```text
+    /// ExtKeyUsageSyntax ::= SEQUENCE SIZE (1..MAX) OF KeyPurposeId
+    ///
+    /// KeyPurposeId ::= OBJECT IDENTIFIER
+    /// ```
+    pub fn to_der(&self) -> Result<Vec<u8>, ConversionError> {
+        let oids: Vec<ObjectIdentifier> = self
+            .extended_key_usages
+            .iter()
+            .map(|usage| ObjectIdentifier::from(*usage))
+            .collect();
+        Ok(oids.to_der()?)
+    }
+
+    /// Creates ExtendedKeyUsages from a DER encoded `ExtKeyUsageSyntax`.
+    pub fn from_der(bytes: &[u8]) -> Result<Self, ConversionError> {
+        let oids = Vec::<ObjectIdentifier>::from_der(bytes)?;
+        if oids.is_empty() {
+            return Err(ConversionError::InvalidInput(InvalidInput::Length {
+                min_length: 1,
+                max_length: usize::MAX,
+                actual_length: "0".to_string(),
+            }));
+        }
+        Ok(ExtendedKeyUsages {
+            extended_key_usages: oids.into_iter().map(ExtendedKeyUsage::from).collect(),
+            critical: false,
+        })
+    }
+}
+
+impl TryFrom<Attribute> for ExtendedKeyUsages {
+    type Error = ConversionError;
+
+    fn try_from(value: Attribute) -> Result<Self, Self::Error> {
+        if value.oid.to_string().as_str() != OID_EXTENDED_KEY_USAGE {
+            return Err(ConversionError::InvalidInput(InvalidInput::Malformed(
+                format!(
+                    "Expected OID {} for ExtendedKeyUsages, found OID {}",
+                    OID_EXTENDED_KEY_USAGE, value.oid
+                ),
+            )));
+        }
+        match value.values.len() {
+            0 => return Ok(ExtendedKeyUsages::default()),
+            1 => (),
+            _ => {
+                return Err(ConversionError::InvalidInput(InvalidInput::Length {
+                    min_length: 0,
+                    max_length: 1,
+                    actual_length: value.values.len().to_string(),
+                }));
+            }
+        };
+        let inner_value = value.values.get(0).expect("Illegal state. Please report this error to https://github.com/polyphony-chat/polyproto");
+        ExtendedKeyUsages::from_der(&inner_value.to_der()?)
+    }
+}
+
+impl TryFrom<Extension> for ExtendedKeyUsages {
+    type Error = ConversionError;
+
+    fn try_from(value: Extension) -> Result<Self, Self::Error> {
+        if value.extn_id.to_string().as_str() != OID_EXTENDED_KEY_USAGE {
+            return Err(ConversionError::InvalidInput(InvalidInput::Malformed(
+                format!(
+                    "Expected OID {} for ExtendedKeyUsages, found OID {}",
+                    OID_EXTENDED_KEY_USAGE, value.extn_id
+                ),
+            )));
+        }
+        Ok(ExtendedKeyUsages {
+            critical: value.critical,
+            ..ExtendedKeyUsages::from_der(value.extn_value.as_bytes())?
+        })
+    }
+}
+
+impl TryFrom<ExtendedKeyUsages> for Attribute {
+    type Error = ConversionError;
+
+    fn try_from(value: ExtendedKeyUsages) -> Result<Self, Self::Error> {
+        let mut sov = SetOfVec::new();
+        sov.insert(Any::from_der(&value.to_der()?)?)?;
+        Ok(Attribute {
+            oid: ObjectIdentifier::from_str(OID_EXTENDED_KEY_USAGE)?,
+            values: sov,
+        })
+    }
+}
+
+impl TryFrom<ExtendedKeyUsages> for Extension {
+    type Error = ConversionError;
+
+    /// Performs the conversion. The resulting [Extension] is marked as critical, if
+    /// [ExtendedKeyUsages::critical] is set.
+    fn try_from(value: ExtendedKeyUsages) -> Result<Self, Self::Error> {
+        Ok(Extension {
+            extn_id: ObjectIdentifier::from_str(OID_EXTENDED_KEY_USAGE)?,
+            critical: value.critical,
+            extn_value: OctetString::new(value.to_der()?)?,
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn oid_round_trip() {
+        let other = ObjectIdentifier::from_str("1.3.6.1.4.1.99999.1").unwrap();
+        for usage in [
+            ExtendedKeyUsage::ServerAuth,
+            ExtendedKeyUsage::ClientAuth,
+            ExtendedKeyUsage::CodeSigning,
+            ExtendedKeyUsage::EmailProtection,
+            ExtendedKeyUsage::TimeStamping,
+            ExtendedKeyUsage::OcspSigning,
+            ExtendedKeyUsage::Other(other),
+        ] {
+            assert_eq!(ExtendedKeyUsage::from(ObjectIdentifier::from(usage)), usage);
+        }
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn empty_sequence_is_rejected() {
+        let der = Vec::<ObjectIdentifier>::new().to_der().unwrap();
+        assert!(ExtendedKeyUsages::from_der(&der).is_err());
+    }
+}
@@ -4,10 +4,13 @@
 
 /// "basicConstraints" IdCert/Csr capabilities
 pub mod basic_constraints;
+/// "extKeyUsage" IdCert/Csr capabilities
+pub mod extended_key_usage;
 /// "keyUsage" IdCert/Csr capabilities
 pub mod key_usage;
 
 pub use basic_constraints::*;
+pub use extended_key_usage::*;
 pub use key_usage::*;
 
 use der::asn1::SetOfVec;
@@ -21,39 +24,88 @@ use crate::{
 };
 
 /// Object Identifier for the KeyUsage::DigitalSignature variant.
+#[deprecated(
+    note = "This is the OID of an extended key usage purpose, not of a key usage. Use OID_EXTENDED_KEY_USAGE_CODE_SIGNING instead."
+)]
 pub const OID_KEY_USAGE_DIGITAL_SIGNATURE: &str = "1.3.6.1.5.5.7.3.3";
 /// Object Identifier for the KeyUsage::CrlSign variant.
+#[deprecated(
+    note = "This is the OID of an extended key usage purpose, not of a key usage. Use OID_EXTENDED_KEY_USAGE_CLIENT_AUTH instead."
+)]
 pub const OID_KEY_USAGE_CRL_SIGN: &str = "1.3.6.1.5.5.7.3.2";
 /// Object Identifier for the KeyUsage::ContentCommitment variant.
+#[deprecated(
+    note = "This is the OID of an extended key usage purpose, not of a key usage. Use OID_EXTENDED_KEY_USAGE_TIME_STAMPING instead."
+)]
 pub const OID_KEY_USAGE_CONTENT_COMMITMENT: &str = "1.3.6.1.5.5.7.3.8";
 /// Object Identifier for the KeyUsage::KeyEncipherment variant.
+#[deprecated(
+    note = "This is the OID of an extended key usage purpose, not of a key usage. Use OID_EXTENDED_KEY_USAGE_SERVER_AUTH instead."
+)]
 pub const OID_KEY_USAGE_KEY_ENCIPHERMENT: &str = "1.3.6.1.5.5.7.3.1";
 /// Object Identifier for the KeyUsage::DataEncipherment variant.
+#[deprecated(
+    note = "This is the OID of an extended key usage purpose, not of a key usage. Use OID_EXTENDED_KEY_USAGE_EMAIL_PROTECTION instead."
+)]
 pub const OID_KEY_USAGE_DATA_ENCIPHERMENT: &str = "1.3.6.1.5.5.7.3.4";
 /// Object Identifier for the KeyUsage::KeyAgreement variant.
+#[deprecated(
+    note = "This is the OID of an extended key usage purpose, not of a key usage. Use OID_EXTENDED_KEY_USAGE_OCSP_SIGNING instead."
+)]
 pub const OID_KEY_USAGE_KEY_AGREEMENT: &str = "1.3.6.1.5.5.7.3.9";
 /// Object Identifier for the KeyUsage::KeyCertSign variant.
+#[deprecated(
+    note = "This is the OID of an extended key usage purpose, not of a key usage. Use OID_EXTENDED_KEY_USAGE_CODE_SIGNING instead."
+)]
 pub const OID_KEY_USAGE_KEY_CERT_SIGN: &str = "1.3.6.1.5.5.7.3.3";
 /// Object Identifier for the KeyUsage::EncipherOnly variant.
+#[deprecated(
+    note = "This is the OID of an extended key usage purpose, not of a key usage. Use ExtendedKeyUsage::Other instead."
+)]
 pub const OID_KEY_USAGE_ENCIPHER_ONLY: &str = "1.3.6.1.5.5.7.3.7";
 /// Object Identifier for the KeyUsage::DecipherOnly variant.
+#[deprecated(
+    note = "This is the OID of an extended key usage purpose, not of a key usage. Use ExtendedKeyUsage::Other instead."
+)]
 pub const OID_KEY_USAGE_DECIPHER_ONLY: &str = "1.3.6.1.5.5.7.3.6";
 /// Object Identifier for the BasicConstraints variant.
 pub const OID_BASIC_CONSTRAINTS: &str = "2.5.29.19";
 /// Object Identifier for the KeyUsage flag.
 pub const OID_KEY_USAGE: &str = "2.5.29.15";
-
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Object Identifier for the ExtendedKeyUsage extension.
+pub const OID_EXTENDED_KEY_USAGE: &str = "2.5.29.37";
+/// Object Identifier for the ExtendedKeyUsage::ServerAuth variant.
+pub const OID_EXTENDED_KEY_USAGE_SERVER_AUTH: &str = "1.3.6.1.5.5.7.3.1";
+/// Object Identifier for the ExtendedKeyUsage::ClientAuth variant.
+pub const OID_EXTENDED_KEY_USAGE_CLIENT_AUTH: &str = "1.3.6.1.5.5.7.3.2";
+/// Object Identifier for the ExtendedKeyUsage::CodeSigning variant.
+pub const OID_EXTENDED_KEY_USAGE_CODE_SIGNING: &str = "1.3.6.1.5.5.7.3.3";
+/// Object Identifier for the ExtendedKeyUsage::EmailProtection variant.
+pub const OID_EXTENDED_KEY_USAGE_EMAIL_PROTECTION: &str = "1.3.6.1.5.5.7.3.4";
+/// Object Identifier for the ExtendedKeyUsage::TimeStamping variant.
+pub const OID_EXTENDED_KEY_USAGE_TIME_STAMPING: &str = "1.3.6.1.5.5.7.3.8";
+/// Object Identifier for the ExtendedKeyUsage::OcspSigning variant.
+pub const OID_EXTENDED_KEY_USAGE_OCSP_SIGNING: &str = "1.3.6.1.5.5.7.3.9";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 /// An abstraction over X.509 Extensions and PKCS#10 Attributes, representing the capabilities
 /// of a certificate. Capabilities can be converted from and to both [Attributes] and [Extensions].
 ///
-/// This struct only covers the Attributes/Extensions currently relevant to polyproto.
+/// This struct only interprets the Attributes/Extensions currently relevant to polyproto. Other
+/// non-critical [Extensions] are retained verbatim in `unknown_extensions`, so that they survive
+/// a round trip through this type. Unknown critical extensions cannot be represented.
 pub struct Capabilities {
     /// The key usage extension defines the purpose of the key contained in the certificate.
     pub key_usage: KeyUsages,
     /// Extension type that defines whether a given certificate is allowed
     /// to sign additional certificates and what path length restrictions may exist.
     pub basic_constraints: BasicConstraints,
+    /// The extended key usage extension further restricts the purposes for which the key
+    /// contained in the certificate may be used. Empty, if the extension is absent.
+    pub extended_key_usage: ExtendedKeyUsages,
+    /// Non-critical extensions which are not interpreted by polyproto, in the order they were
+    /// encountered in. These are re-encoded exactly as they were parsed.
+    pub unknown_extensions: Vec<Extension>,
 }
 
 impl Default for Capabilities {
@@ -64,10 +116,57 @@ impl Default for Capabilities {
                 ca: false,
                 path_length: None,
             },
+            extended_key_usage: Default::default(),
+            unknown_extensions: Vec::new(),
         }
     }
 }
 
+impl Capabilities {
+    /// Returns a sortable and hashable representation of `unknown_extensions`, as [Extension]
+    /// implements neither [Ord] nor [Hash].
+    fn unknown_extensions_key(&self) -> Vec<(&[u8], bool, &[u8])> {
+        self.unknown_extensions
+            .iter()
+            .map(|extension| {
+                (
+                    extension.extn_id.as_bytes(),
+                    extension.critical,
+                    extension.extn_value.as_bytes(),
+                )
+            })
+            .collect()
+    }
+}
+
+impl PartialOrd for Capabilities {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Capabilities {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key_usage
+            .cmp(&other.key_usage)
+            .then_with(|| self.basic_constraints.cmp(&other.basic_constraints))
+            .then_with(|| self.extended_key_usage.cmp(&other.extended_key_usage))
+            .then_with(|| {
+                self.unknown_extensions_key()
+                    .cmp(&other.unknown_extensions_key())
+            })
+    }
+}
+
+impl std::hash::Hash for Capabilities {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.key_usage.hash(state);
+        self.basic_constraints.hash(state);
+        self.extended_key_usage.hash(state);
+        self.unknown_extensions_key().hash(state);
+    }
+}
+
 impl Capabilities {
     /// Sane default for actor [IdCsr]/[IdCert] [Capabilities]. Uses the DigitalSignature flag,
     /// not the ContentCommitment flag.
@@ -80,12 +179,14 @@ impl Capabilities {
         Self {
             key_usage,
             basic_constraints,
+            extended_key_usage: ExtendedKeyUsages::default(),
+            unknown_extensions: Vec::new(),
         }
     }
 
     /// Sane default for home server [IdCsr]/[IdCert] [Capabilities].
     pub fn default_home_server() -> Self {
-        let key_usage = KeyUsages::new(&[KeyUsage::KeyCertSign]);
+        let key_usage = KeyUsages::new(&[KeyUsage::KeyCertSign, KeyUsage::CrlSign]);
         let basic_constraints = BasicConstraints {
             ca: true,
             path_length: Some(1),
@@ -93,6 +194,8 @@ impl Capabilities {
         Self {
             key_usage,
             basic_constraints,
+            extended_key_usage: ExtendedKeyUsages::default(),
+            unknown_extensions: Vec::new(),
         }
     }
 }
@@ -109,6 +212,7 @@ impl TryFrom<Attributes> for Capabilities {
     fn try_from(value: Attributes) -> Result<Self, Self::Error> {
         let mut key_usages = KeyUsages::new(&[]);
         let mut basic_constraints = BasicConstraints::default();
+        let mut extended_key_usage = ExtendedKeyUsages::default();
         let mut num_basic_constraints = 0u8;
         for item in value.iter() {
             match item.oid.to_string().as_str() {
@@ -124,12 +228,17 @@ impl TryFrom<Attributes> for Capabilities {
                         basic_constraints = BasicConstraints::try_from(item.clone())?;
                     }
                 }
+                OID_EXTENDED_KEY_USAGE => {
+                    extended_key_usage = ExtendedKeyUsages::try_from(item.clone())?;
+                }
                 _ => (),
             }
         }
         Ok(Capabilities {
             key_usage: key_usages,
             basic_constraints,
+            extended_key_usage,
+            unknown_extensions: Vec::new(),
         })
     }
 }
@@ -151,20 +260,32 @@ impl TryFrom<Capabilities> for Attributes {
         if insertion.is_err() {
             return Err(ConversionError::InvalidInput(InvalidInput::Malformed("Tried inserting non-unique element into SetOfVec. You likely have a duplicate value in your Capabilities".to_string())));
         }
+        if !value.extended_key_usage.is_empty() {
+            let insertion = sov.insert(Attribute::try_from(value.extended_key_usage)?);
+            if insertion.is_err() {
+                return Err(ConversionError::InvalidInput(InvalidInput::Malformed("Tried inserting non-unique element into SetOfVec. You likely have a duplicate value in your Capabilities".to_string())));
+            }
+        }
         Ok(sov)
     }
 }
 
 impl TryFrom<Capabilities> for Extensions {
     type Error = ConversionError;
-    /// Performs the conversion.
+    /// Performs the conversion. The `unknown_extensions` of the [Capabilities] are appended to
+    /// the resulting [Extensions] unchanged.
     ///
     /// try_from does **not** check whether the resulting [Extensions] are well-formed.
     fn try_from(value: Capabilities) -> Result<Self, Self::Error> {
-        Ok(vec![
+        let mut extensions = vec![
             Extension::try_from(value.basic_constraints)?,
             Extension::try_from(value.key_usage)?,
-        ])
+        ];
+        if !value.extended_key_usage.is_empty() {
+            extensions.push(Extension::try_from(value.extended_key_usage)?);
+        }
+        extensions.extend(value.unknown_extensions);
+        Ok(extensions)
     }
 }
 
@@ -173,27 +294,39 @@ impl TryFrom<Extensions> for Capabilities {
 
     /// Performs the conversion.
     ///
+    /// Extensions other than [BasicConstraints], [KeyUsages] and [ExtendedKeyUsages] are retained
+    /// in `unknown_extensions`, if they are not marked as critical. Fails with
+    /// [ConversionError::UnknownCriticalExtension], if an unknown extension is marked as critical.
+    ///
     /// try_from does **not** check whether the resulting [Capabilities] are well-formed. If
     /// this property is critical, use the [Constrained] trait to verify the well-formedness of
     /// these resulting [Capabilities].
     fn try_from(value: Extensions) -> Result<Self, Self::Error> {
         let mut basic_constraints: BasicConstraints = BasicConstraints::default();
         let mut key_usage: KeyUsages = KeyUsages::default();
-        for item in value.iter() {
+        let mut extended_key_usage = ExtendedKeyUsages::default();
+        let mut unknown_extensions = Vec::new();
+        for item in value.into_iter() {
             #[allow(unreachable_patterns)] // cargo thinks that we have an unreachable pattern here
             match item.extn_id.to_string().as_str() {
-                OID_BASIC_CONSTRAINTS => {
-                    basic_constraints = BasicConstraints::try_from(item.clone())?
+                OID_BASIC_CONSTRAINTS => basic_constraints = BasicConstraints::try_from(item)?,
+                OID_KEY_USAGE => key_usage = KeyUsages::try_from(item)?,
+                OID_EXTENDED_KEY_USAGE => extended_key_usage = ExtendedKeyUsages::try_from(item)?,
+                _ if item.critical => {
+                    log::warn!("[Capabilities::try_from(Extensions)] Encountered unknown critical extension {}", item.extn_id);
+                    return Err(ConversionError::UnknownCriticalExtension { oid: item.extn_id });
+                }
+                _ => {
+                    log::trace!("[Capabilities::try_from(Extensions)] Retaining unknown non-critical extension {}", item.extn_id);
+                    unknown_extensions.push(item)
                 }
-                OID_KEY_USAGE => {
-                    key_usage = KeyUsages::try_from(item.clone())?
-                },
-                _ => return Err(ConversionError::InvalidInput(InvalidInput::Malformed(format!("Invalid OID found for converting this set of Extensions to Capabilities: {} is not a valid OID for BasicConstraints or KeyUsages", item.extn_id))))
             };
         }
         Ok(Capabilities {
             key_usage,
             basic_constraints,
+            extended_key_usage,
+            unknown_extensions,
         })
     }
 }
@@ -246,7 +379,7 @@ mod test {
 }
 
 #[cfg(test)]
-#[allow(clippy::unwrap_used)]
+#[allow(clippy::unwrap_used, deprecated)]
 mod test_key_usage_from_attribute {
     use std::str::FromStr;
 
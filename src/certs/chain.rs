@@ -0,0 +1,196 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::clock::{Clock, FixedClock};
+use crate::errors::{ConstraintError, InvalidCert, InvalidChain, ERR_CERTIFICATE_TO_DER_ERROR};
+use crate::key::PublicKey;
+use crate::signature::Signature;
+use crate::Constrained;
+
+use super::capabilities::KeyUsage;
+use super::idcert::IdCert;
+use super::Target;
+
+/// A chain of [IdCert]s, consisting of a leaf (actor) certificate and the home server
+/// certificates which issued it.
+///
+/// The issuer certificates are ordered from the leaf towards the root: `issuers[0]` is the
+/// certificate which issued `leaf`, `issuers[1]` is the certificate which issued `issuers[0]`, and
+/// so on. The last certificate in `issuers` is expected to be a self-signed home server
+/// certificate.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CertChain<S: Signature, P: PublicKey<S>> {
+    /// The actor certificate at the end of the chain
+    pub leaf: IdCert<S, P>,
+    /// The home server certificates, ordered from the issuer of `leaf` to the root certificate
+    pub issuers: Vec<IdCert<S, P>>,
+}
+
+impl<S: Signature, P: PublicKey<S>> CertChain<S, P> {
+    /// Creates a new [CertChain] from an actor certificate and the home server certificates which
+    /// issued it. Does not perform any verification; use [CertChain::verify()] for that.
+    pub fn new(leaf: IdCert<S, P>, issuers: Vec<IdCert<S, P>>) -> Self {
+        Self { leaf, issuers }
+    }
+
+    /// Performs full path validation of this chain at the given UNIX `time`. See [verify_chain()]
+    /// for a list of the properties checked.
+    pub fn verify(&self, time: u64) -> Result<(), InvalidChain> {
+        verify_chain(&self.leaf, &self.issuers, time)
+    }
+
+    /// Performs full path validation of this chain at the current time of the given [Clock]. See
+    /// [verify_chain_with_clock()].
+    pub fn verify_with_clock(&self, clock: &dyn Clock) -> Result<(), InvalidChain> {
+        verify_chain_with_clock(&self.leaf, &self.issuers, clock)
+    }
+}
+
+/// Performs full path validation of an actor [IdCert] against the home server [IdCert]s which
+/// issued it, at the given UNIX `time`. `home_server_certs` must be ordered from the direct issuer
+/// of `actor_cert` to the self-signed root certificate.
+///
+/// For every link of the chain, the following properties are checked:
+///
+/// - The certificate is well-formed and up to polyproto specification for its [Target]
+/// - The certificate is valid at the given `time`
+/// - The issuer [Name](x509_cert::name::Name) of the certificate equals the subject of the next
+///   certificate in the chain
+/// - If the certificate carries an AuthorityKeyIdentifier and its issuer carries a
+///   SubjectKeyIdentifier, both key identifiers are equal
+/// - The validity period of the certificate lies within the validity period of its issuer
+/// - The issuer is a CA, has the [KeyUsage::KeyCertSign] key usage, and its path length
+///   constraint allows for the number of intermediate certificates below it
+/// - The signature of the certificate is valid under the public key of its issuer
+///
+/// Additionally, the last certificate of `home_server_certs` must pass
+/// [IdCert::full_verify_home_server()].
+///
+/// On failure, the returned [InvalidChain] contains the index of the offending certificate, where
+/// index `0` refers to `actor_cert` and index `n` refers to `home_server_certs[n - 1]`.
+pub fn verify_chain<S: Signature, P: PublicKey<S>>(
+    actor_cert: &IdCert<S, P>,
+    home_server_certs: &[IdCert<S, P>],
+    time: u64,
+) -> Result<(), InvalidChain> {
+    verify_chain_with_clock(actor_cert, home_server_certs, &FixedClock::new(time))
+}
+
+/// Performs the same path validation as [verify_chain()], checking the validity periods of the
+/// certificates against the current time of the given [Clock] and its skew tolerance.
+pub fn verify_chain_with_clock<S: Signature, P: PublicKey<S>>(
+    actor_cert: &IdCert<S, P>,
+    home_server_certs: &[IdCert<S, P>],
+    clock: &dyn Clock,
+) -> Result<(), InvalidChain> {
+    if home_server_certs.is_empty() {
+        return Err(InvalidChain::MissingIssuer);
+    }
+    let mut chain = Vec::with_capacity(home_server_certs.len() + 1);
+    chain.push(actor_cert);
+    chain.extend(home_server_certs.iter());
+
+    for (index, pair) in chain.windows(2).enumerate() {
+        let (cert, issuer) = (pair[0], pair[1]);
+        let target = match index {
+            0 => Target::Actor,
+            _ => Target::HomeServer,
+        };
+        log::trace!(
+            "[verify_chain_with_clock()] verifying link {} of the chain with target {:?}",
+            index,
+            target
+        );
+        cert.validate(Some(target))
+            .map_err(|e| InvalidChain::InvalidCert {
+                index,
+                source: e.into(),
+            })?;
+        if !cert.valid_now(clock) {
+            return Err(InvalidChain::InvalidCert {
+                index,
+                source: InvalidCert::InvalidValidity,
+            });
+        }
+        if cert.id_cert_tbs().issuer != issuer.id_cert_tbs().subject {
+            log::warn!(
+                "[verify_chain_with_clock()] Issuer \"{}\" of certificate {} does not match subject \"{}\"",
+                cert.id_cert_tbs().issuer,
+                index,
+                issuer.id_cert_tbs().subject
+            );
+            return Err(InvalidChain::IssuerMismatch { index });
+        }
+        if !cert.is_issued_by(issuer) {
+            log::warn!(
+                "[verify_chain_with_clock()] AuthorityKeyIdentifier of certificate {} does not match the SubjectKeyIdentifier of its issuer",
+                index
+            );
+            return Err(InvalidChain::KeyIdentifierMismatch { index });
+        }
+        let validity = &cert.id_cert_tbs().validity;
+        let issuer_validity = &issuer.id_cert_tbs().validity;
+        if validity.not_before.to_unix_duration() < issuer_validity.not_before.to_unix_duration()
+            || validity.not_after.to_unix_duration() > issuer_validity.not_after.to_unix_duration()
+        {
+            return Err(InvalidChain::ValidityNotNested { index });
+        }
+        let issuer_capabilities = &issuer.id_cert_tbs().capabilities;
+        if !issuer_capabilities.basic_constraints.ca {
+            return Err(InvalidChain::NotCa { index: index + 1 });
+        }
+        if !issuer_capabilities
+            .key_usage
+            .key_usages
+            .contains(&KeyUsage::KeyCertSign)
+        {
+            return Err(InvalidChain::MissingKeyCertSign { index: index + 1 });
+        }
+        // The number of intermediate CA certificates between the issuer and the leaf certificate
+        let intermediates = index as u64;
+        if let Some(max) = issuer_capabilities.basic_constraints.path_length {
+            if intermediates > max {
+                return Err(InvalidChain::PathLengthExceeded {
+                    index: index + 1,
+                    max,
+                    actual: intermediates,
+                });
+            }
+        }
+        let signature_data = cert
+            .signature_data()
+            .map_err(|_| InvalidChain::InvalidCert {
+                index,
+                source: InvalidCert::InvalidProperties(ConstraintError::Malformed(Some(
+                    ERR_CERTIFICATE_TO_DER_ERROR.to_string(),
+                ))),
+            })?;
+        issuer
+            .id_cert_tbs()
+            .subject_public_key
+            .verify_signature(&cert.signature, &signature_data)
+            .map_err(|e| InvalidChain::InvalidCert {
+                index,
+                source: e.into(),
+            })?;
+    }
+
+    let root_index = chain.len() - 1;
+    log::trace!(
+        "[verify_chain_with_clock()] verifying root certificate {}",
+        root_index
+    );
+    let root = chain[root_index];
+    root.validate(Some(Target::HomeServer))
+        .map_err(|e| InvalidChain::InvalidCert {
+            index: root_index,
+            source: e.into(),
+        })?;
+    root.full_verify_home_server_with_clock(clock)
+        .map_err(|e| InvalidChain::InvalidCert {
+            index: root_index,
+            source: e,
+        })?;
+    Ok(())
+}
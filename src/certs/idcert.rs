@@ -3,20 +3,33 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use der::asn1::Uint;
-use der::pem::LineEnding;
-use der::{Decode, DecodePem, Encode, EncodePem};
+use der::pem::{LineEnding, PemLabel};
+use der::{Decode, Encode};
+use spki::AlgorithmIdentifierOwned;
 use x509_cert::name::Name;
 use x509_cert::time::Validity;
-use x509_cert::Certificate;
+use x509_cert::{Certificate, TbsCertificate};
 
-use crate::errors::{ConstraintError, ConversionError, InvalidCert, ERR_CERTIFICATE_TO_DER_ERROR};
-use crate::key::{PrivateKey, PublicKey};
+use crate::clock::{Clock, FixedClock};
+use crate::digest::{Digest, Fingerprint};
+use crate::errors::{
+    BuilderError, ConstraintError, ConversionError, InvalidCert, ERR_CERTIFICATE_TO_DER_ERROR,
+    ERR_MSG_CERT_ISSUER_MISMATCH,
+};
+use crate::key::{AsyncPrivateKey, PrivateKey, PublicKey};
 use crate::signature::Signature;
+use crate::types::FederationId;
 use crate::Constrained;
 
+use super::builder::IdCsrBuilder;
 use super::idcerttbs::IdCertTbs;
+use super::idcrl::IdCrl;
 use super::idcsr::IdCsr;
-use super::Target;
+use super::policy::ValidationPolicy;
+use super::{
+    decode_pem, decode_signature, encode_signed, ensure_algorithm, signed_data_der,
+    signing_algorithm, OriginalDer, SessionId, Target,
+};
 
 /// A signed polyproto ID-Cert, consisting of the actual certificate, the CA-generated signature and
 /// metadata about that signature.
@@ -38,18 +51,62 @@ use super::Target;
 ///
 /// If you only need to check
 /// if the certificate is valid at a given time, you can use the [valid_at()] method.
+///
+/// ## Modifying an ID-Cert
+///
+/// The [IdCertTbs] of a decoded `IdCert` keeps the exact encoding it has been decoded from, so
+/// that its signature remains verifiable. Modifying it through [IdCert::id_cert_tbs_mut()]
+/// discards that encoding.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct IdCert<S: Signature, P: PublicKey<S>> {
     /// Inner TBS (To be signed) certificate
-    pub id_cert_tbs: IdCertTbs<S, P>,
+    id_cert_tbs: IdCertTbs<S, P>,
     /// Signature for the TBS certificate
     pub signature: S,
+    /// The DER encoding `id_cert_tbs` has been decoded from, if any.
+    original_der: OriginalDer,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Represents an [IdCert] with an additional field `invalidated` which indicates whether the
+/// certificate has been invalidated. This type is used in the API as a response to the
+/// `GET /.p2/core/v1/idcert/actor/:fid`
+/// route, and by [IdCertStore](super::store::IdCertStore)s.
+pub struct IdCertExt<S: Signature, P: PublicKey<S>> {
+    /// The [IdCert] itself
+    pub id_cert: IdCert<S, P>,
+    /// Whether the certificate has been marked as invalidated
+    pub invalidated: bool,
 }
 
 impl<S: Signature, P: PublicKey<S>> IdCert<S, P> {
+    /// Creates a new [IdCert] from an [IdCertTbs] and the [Signature] over its DER encoding. Does
+    /// not verify the signature or validate the certificate.
+    pub fn new(id_cert_tbs: IdCertTbs<S, P>, signature: S) -> Self {
+        Self {
+            id_cert_tbs,
+            signature,
+            original_der: OriginalDer::default(),
+        }
+    }
+
+    /// The inner TBS (To be signed) certificate.
+    pub fn id_cert_tbs(&self) -> &IdCertTbs<S, P> {
+        &self.id_cert_tbs
+    }
+
+    /// Mutable access to the inner TBS (To be signed) certificate. Discards the encoding the
+    /// certificate has been decoded from, so that [IdCert::signature_data()] and
+    /// [IdCert::to_der()] re-encode the, possibly modified, contents.
+    pub fn id_cert_tbs_mut(&mut self) -> &mut IdCertTbs<S, P> {
+        self.original_der = OriginalDer::default();
+        &mut self.id_cert_tbs
+    }
+
     /// Create a new [IdCert] by passing an [IdCsr] and other supplementary information. Returns
     /// an error, if the provided IdCsr or issuer [Name] do not pass [Constrained] verification,
-    /// i.e. if they are not up to polyproto specification.
+    /// i.e. if they are not up to polyproto specification, or if the signature of the IdCsr does
+    /// not verify under its own subject public key (see [IdCsr::verify_signature()]).
     ///
     /// See [IdCert::from_actor_csr()] when trying to create a new actor certificate.
     ///
@@ -67,6 +124,9 @@ impl<S: Signature, P: PublicKey<S>> IdCert<S, P> {
     /// - `serial_number`: The serial number that should be assigned to the new certificate.
     /// - `issuer`: The [Name] of the issuer of the resulting certificate.
     /// - `validity`: The [Validity] period of the resulting certificate.
+    ///
+    /// The SubjectKeyIdentifier and AuthorityKeyIdentifier extensions are derived from the public
+    /// keys of `id_csr` and `signing_key` respectively, see [PublicKey::key_identifier()].
     pub fn from_ca_csr(
         id_csr: IdCsr<S, P>,
         signing_key: &impl PrivateKey<S, PublicKey = P>,
@@ -74,29 +134,90 @@ impl<S: Signature, P: PublicKey<S>> IdCert<S, P> {
         issuer: Name,
         validity: Validity,
     ) -> Result<Self, ConversionError> {
-        let signature_algorithm = signing_key.algorithm_identifier();
-        let id_cert_tbs = IdCertTbs::<S, P> {
+        let id_cert_tbs = IdCert::tbs_from_csr(
+            id_csr,
+            signing_algorithm(PrivateKey::algorithm_identifier(signing_key))?,
+            PrivateKey::pubkey(signing_key),
             serial_number,
-            signature_algorithm,
             issuer,
             validity,
-            subject: id_csr.inner_csr.subject,
-            subject_public_key: id_csr.inner_csr.subject_public_key,
-            capabilities: id_csr.inner_csr.capabilities,
-            s: std::marker::PhantomData,
-        };
-        let signature = signing_key.sign(&id_cert_tbs.clone().to_der()?);
-        let cert = IdCert {
-            id_cert_tbs,
-            signature,
-        };
-        cert.validate(Some(Target::HomeServer))?;
+        )?;
+        let signature = PrivateKey::sign(signing_key, &id_cert_tbs.clone().to_der()?)?;
+        IdCert::from_signed_tbs(id_cert_tbs, signature, Target::HomeServer)
+    }
+
+    /// Create a new [IdCert] by passing an [IdCsr] and other supplementary information, like
+    /// [IdCert::from_ca_csr()]. The certificate is signed using an [AsyncPrivateKey], which may be
+    /// held by a remote signer. Fails with [ConversionError::SigningError], if the signer fails to
+    /// create the signature.
+    ///
+    /// The resulting `IdCert` has the same guarantees as one created using
+    /// [IdCert::from_ca_csr()].
+    pub async fn from_ca_csr_async(
+        id_csr: IdCsr<S, P>,
+        signing_key: &impl AsyncPrivateKey<S, PublicKey = P>,
+        serial_number: Uint,
+        issuer: Name,
+        validity: Validity,
+    ) -> Result<Self, ConversionError> {
+        let id_cert_tbs = IdCert::tbs_from_csr(
+            id_csr,
+            signing_algorithm(AsyncPrivateKey::algorithm_identifier(signing_key))?,
+            AsyncPrivateKey::pubkey(signing_key),
+            serial_number,
+            issuer,
+            validity,
+        )?;
+        let signature_data = id_cert_tbs.clone().to_der()?;
+        let signature = AsyncPrivateKey::sign(signing_key, &signature_data).await?;
+        IdCert::from_signed_tbs(id_cert_tbs, signature, Target::HomeServer)
+    }
+
+    /// Create a new self-signed root [IdCert] for the home server with the given `domain`, e.g.
+    /// `polyphony.chat`. The subject and issuer of the certificate are both derived from `domain`,
+    /// and [Capabilities::default_home_server()](super::capabilities::Capabilities::default_home_server())
+    /// are applied.
+    ///
+    /// ## Safety guarantees
+    ///
+    /// The resulting `IdCert` is guaranteed to be well-formed and up to polyproto specification,
+    /// for the usage context of a home server certificate. It is also guaranteed to pass
+    /// [IdCert::full_verify_home_server()] at any point in time within its `validity` period.
+    ///
+    /// ## Parameters
+    ///
+    /// - `signing_key`: The home server's private key, used to sign the new certificate. Its public
+    ///   key becomes the subject public key of the certificate.
+    /// - `domain`: The domain of the home server.
+    /// - `validity`: The [Validity] period of the resulting certificate.
+    /// - `serial_number`: The serial number that should be assigned to the new certificate.
+    pub fn new_self_signed_home_server(
+        signing_key: &impl PrivateKey<S, PublicKey = P>,
+        domain: &str,
+        validity: Validity,
+        serial_number: Uint,
+    ) -> Result<Self, BuilderError> {
+        let not_before = validity.not_before.to_unix_duration().as_secs();
+        if not_before > validity.not_after.to_unix_duration().as_secs() {
+            return Err(BuilderError::InvalidValidity);
+        }
+        let id_csr = IdCsrBuilder::new()
+            .home_server_domain(domain)
+            .build_home_server(signing_key)?;
+        let subject = id_csr.inner_csr().subject.clone();
+        log::trace!(
+            "[IdCert::new_self_signed_home_server()] creating self-signed certificate for {}",
+            subject
+        );
+        let cert = IdCert::from_ca_csr(id_csr, signing_key, serial_number, subject, validity)?;
+        cert.full_verify_home_server(not_before)?;
         Ok(cert)
     }
 
     /// Create a new [IdCert] by passing an [IdCsr] and other supplementary information. Returns
     /// an error, if the provided IdCsr or issuer [Name] do not pass [Constrained] verification,
-    /// i.e. if they are not up to polyproto specification.
+    /// i.e. if they are not up to polyproto specification, or if the signature of the IdCsr does
+    /// not verify under its own subject public key (see [IdCsr::verify_signature()]).
     ///
     /// See [IdCert::from_ca_csr()] when trying to create a new ca certificate.
     ///
@@ -114,6 +235,9 @@ impl<S: Signature, P: PublicKey<S>> IdCert<S, P> {
     /// - `serial_number`: The serial number that should be assigned to the new certificate.
     /// - `issuer`: The [Name] of the issuer of the resulting certificate.
     /// - `validity`: The [Validity] period of the resulting certificate.
+    ///
+    /// The SubjectKeyIdentifier and AuthorityKeyIdentifier extensions are derived from the public
+    /// keys of `id_csr` and `signing_key` respectively, see [PublicKey::key_identifier()].
     pub fn from_actor_csr(
         id_csr: IdCsr<S, P>,
         signing_key: &impl PrivateKey<S, PublicKey = P>,
@@ -122,34 +246,91 @@ impl<S: Signature, P: PublicKey<S>> IdCert<S, P> {
         validity: Validity,
     ) -> Result<Self, ConversionError> {
         log::trace!("[IdCert::from_actor_csr()] creating actor certificate");
-        let signature_algorithm = signing_key.algorithm_identifier();
-        log::trace!("[IdCert::from_actor_csr()] creating IdCertTbs");
-        log::trace!("[IdCert::from_actor_csr()] Issuer: {}", issuer.to_string());
+        let id_cert_tbs = IdCert::tbs_from_csr(
+            id_csr,
+            signing_algorithm(PrivateKey::algorithm_identifier(signing_key))?,
+            PrivateKey::pubkey(signing_key),
+            serial_number,
+            issuer,
+            validity,
+        )?;
+        log::trace!("[IdCert::from_actor_csr()] creating Signature");
+        let signature = PrivateKey::sign(signing_key, &id_cert_tbs.clone().to_der()?)?;
+        IdCert::from_signed_tbs(id_cert_tbs, signature, Target::Actor)
+    }
+
+    /// Create a new [IdCert] by passing an [IdCsr] and other supplementary information, like
+    /// [IdCert::from_actor_csr()]. The certificate is signed using an [AsyncPrivateKey], which may
+    /// be held by a remote signer. Fails with [ConversionError::SigningError], if the signer fails
+    /// to create the signature.
+    ///
+    /// The resulting `IdCert` has the same guarantees as one created using
+    /// [IdCert::from_actor_csr()].
+    pub async fn from_actor_csr_async(
+        id_csr: IdCsr<S, P>,
+        signing_key: &impl AsyncPrivateKey<S, PublicKey = P>,
+        serial_number: Uint,
+        issuer: Name,
+        validity: Validity,
+    ) -> Result<Self, ConversionError> {
+        log::trace!("[IdCert::from_actor_csr_async()] creating actor certificate");
+        let id_cert_tbs = IdCert::tbs_from_csr(
+            id_csr,
+            signing_algorithm(AsyncPrivateKey::algorithm_identifier(signing_key))?,
+            AsyncPrivateKey::pubkey(signing_key),
+            serial_number,
+            issuer,
+            validity,
+        )?;
+        log::trace!("[IdCert::from_actor_csr_async()] requesting Signature");
+        let signature_data = id_cert_tbs.clone().to_der()?;
+        let signature = AsyncPrivateKey::sign(signing_key, &signature_data).await?;
+        IdCert::from_signed_tbs(id_cert_tbs, signature, Target::Actor)
+    }
+
+    /// Verifies the proof of possession of `id_csr` and creates the [IdCertTbs] of a certificate
+    /// for it, which is to be signed by the private key belonging to `issuer_public_key`.
+    fn tbs_from_csr(
+        id_csr: IdCsr<S, P>,
+        signature_algorithm: AlgorithmIdentifierOwned,
+        issuer_public_key: &P,
+        serial_number: Uint,
+        issuer: Name,
+        validity: Validity,
+    ) -> Result<IdCertTbs<S, P>, ConversionError> {
+        id_csr.verify_signature()?;
+        log::trace!("[IdCert::tbs_from_csr()] creating IdCertTbs");
+        log::trace!("[IdCert::tbs_from_csr()] Issuer: {}", issuer);
         log::trace!(
-            "[IdCert::from_actor_csr()] Subject: {}",
-            id_csr.inner_csr.subject.to_string()
+            "[IdCert::tbs_from_csr()] Subject: {}",
+            id_csr.inner_csr().subject
         );
-        let id_cert_tbs = IdCertTbs::<S, P> {
+        Ok(IdCertTbs {
             serial_number,
             signature_algorithm,
             issuer,
             validity,
-            subject: id_csr.inner_csr.subject,
-            subject_public_key: id_csr.inner_csr.subject_public_key,
-            capabilities: id_csr.inner_csr.capabilities,
+            subject: id_csr.inner_csr().subject.clone(),
+            subject_key_identifier: id_csr.inner_csr().subject_public_key.key_identifier(),
+            authority_key_identifier: issuer_public_key.key_identifier(),
+            subject_public_key: id_csr.inner_csr().subject_public_key.clone(),
+            capabilities: id_csr.inner_csr().capabilities.clone(),
             s: std::marker::PhantomData,
-        };
-        log::trace!("[IdCert::from_actor_csr()] creating Signature");
-        let signature = signing_key.sign(&id_cert_tbs.clone().to_der()?);
-        let cert = IdCert {
-            id_cert_tbs,
-            signature,
-        };
+        })
+    }
+
+    /// Assembles an [IdCert] from its signed `id_cert_tbs` and validates it against `target`.
+    fn from_signed_tbs(
+        id_cert_tbs: IdCertTbs<S, P>,
+        signature: S,
+        target: Target,
+    ) -> Result<Self, ConversionError> {
+        let cert = IdCert::new(id_cert_tbs, signature);
         log::trace!(
-            "[IdCert::from_actor_csr()] validating certificate with target {:?}",
-            Some(Target::Actor)
+            "[IdCert::from_signed_tbs()] validating certificate with target {:?}",
+            Some(target)
         );
-        cert.validate(Some(Target::Actor))?;
+        cert.validate(Some(target))?;
         Ok(cert)
     }
 
@@ -185,12 +366,24 @@ impl<S: Signature, P: PublicKey<S>> IdCert<S, P> {
     /// The caller is responsible for verifying the correctness of this `IdCert` using
     /// the [Constrained] trait before using it.
     pub fn from_der_unchecked(value: &[u8]) -> Result<Self, ConversionError> {
-        let cert = IdCert::try_from(Certificate::from_der(value)?)?;
+        let mut cert = IdCert::try_from(Certificate::from_der(value)?)?;
+        cert.original_der = OriginalDer(Some(signed_data_der(value)?));
         Ok(cert)
     }
 
     /// Encode this type as DER, returning a byte vector.
+    ///
+    /// If this `IdCert` has been decoded and [IdCert::id_cert_tbs_mut()] has not been called
+    /// since, the original encoding of its [IdCertTbs] is kept unchanged, so that the signature
+    /// over it remains verifiable.
     pub fn to_der(self) -> Result<Vec<u8>, ConversionError> {
+        if let Some(original_der) = &self.original_der.0 {
+            return encode_signed(
+                original_der,
+                &self.id_cert_tbs.signature_algorithm,
+                &self.signature.to_bitstring()?,
+            );
+        }
         Ok(Certificate::try_from(self)?.to_der()?)
     }
 
@@ -226,23 +419,36 @@ impl<S: Signature, P: PublicKey<S>> IdCert<S, P> {
     /// The caller is responsible for verifying the correctness of this `IdCert` using
     /// either [IdCert::full_verify_actor()] or [IdCert::full_verify_home_server()] before using it.
     pub fn from_pem_unchecked(pem: &str) -> Result<Self, ConversionError> {
-        let cert = IdCert::try_from(Certificate::from_pem(pem)?)?;
-        Ok(cert)
+        IdCert::from_der_unchecked(&decode_pem::<Certificate>(pem)?)
     }
 
     /// Encode this type as PEM, returning a string.
     pub fn to_pem(self, line_ending: LineEnding) -> Result<String, ConversionError> {
-        Ok(Certificate::try_from(self)?.to_pem(line_ending)?)
+        Ok(
+            der::pem::encode_string(Certificate::PEM_LABEL, line_ending, &self.to_der()?)
+                .map_err(der::Error::from)?,
+        )
+    }
+
+    /// Computes the [Fingerprint] of this certificate, which is the digest of its DER encoding.
+    /// The hash function is determined by `D`.
+    pub fn fingerprint<D: Digest>(&self) -> Result<Fingerprint, ConversionError> {
+        Ok(Fingerprint::new::<D>(&self.clone().to_der()?))
     }
 
     /// Returns a byte vector containing the DER encoded IdCertTbs. This data is encoded
     /// in the signature field of the certificate, and can be used to verify the signature.
     ///
-    /// This is a shorthand for `self.id_cert_tbs.clone().to_der()`, since intuitively, one might
+    /// This is a shorthand for `self.id_cert_tbs().clone().to_der()`, since intuitively, one might
     /// try to verify the signature of the certificate by using `self.to_der()`, which will result
-    /// in an error.
+    /// in an error. For a certificate decoded using one of the `from_der` or `from_pem` methods,
+    /// these are the exact bytes of the `tbsCertificate` found in the input, as long as
+    /// [IdCert::id_cert_tbs_mut()] has not been called since.
     pub fn signature_data(&self) -> Result<Vec<u8>, ConversionError> {
-        self.id_cert_tbs.clone().to_der()
+        match &self.original_der.0 {
+            Some(original_der) => Ok(original_der.clone()),
+            None => self.id_cert_tbs.clone().to_der(),
+        }
     }
 
     /// Checks, if the certificate is valid at a given time. Does not check if the certificate is
@@ -253,9 +459,83 @@ impl<S: Signature, P: PublicKey<S>> IdCert<S, P> {
         self.id_cert_tbs.valid_at(time)
     }
 
+    /// Checks, if the certificate is valid at the current time of the given [Clock], taking its
+    /// skew tolerance into account. Like [IdCert::valid_at()], this does not check if the
+    /// certificate is well-formed, up to polyproto specification or if the signature is correct.
+    pub fn valid_now(&self, clock: &dyn Clock) -> bool {
+        self.id_cert_tbs.valid_now(clock)
+    }
+
+    /// Checks, if `candidate` could have issued this certificate, judging by names and key
+    /// identifiers only: The subject of `candidate` must equal the issuer of this certificate, and
+    /// if both the AuthorityKeyIdentifier of this certificate and the SubjectKeyIdentifier of
+    /// `candidate` are present, they must be equal. Does not verify any signatures.
+    pub fn is_issued_by(&self, candidate: &IdCert<S, P>) -> bool {
+        if self.id_cert_tbs.issuer != candidate.id_cert_tbs.subject {
+            return false;
+        }
+        match (
+            &self.id_cert_tbs.authority_key_identifier,
+            &candidate.id_cert_tbs.subject_key_identifier,
+        ) {
+            (Some(authority_key_id), Some(subject_key_id)) => authority_key_id == subject_key_id,
+            _ => true,
+        }
+    }
+
+    /// Selects the certificate which issued this certificate from a list of `candidates`, for
+    /// example when a home server has rotated its key and multiple home server certificates
+    /// with the same subject exist.
+    ///
+    /// Candidates passing [IdCert::is_issued_by()] are considered in order. If this certificate
+    /// carries an AuthorityKeyIdentifier, the first candidate with a matching
+    /// SubjectKeyIdentifier is returned. Otherwise, the first candidate whose public key verifies
+    /// the signature of this certificate is returned. Returns `None`, if no candidate matches.
+    pub fn find_issuer<'a>(&self, candidates: &'a [IdCert<S, P>]) -> Option<&'a IdCert<S, P>> {
+        let mut candidates = candidates
+            .iter()
+            .filter(|candidate| self.is_issued_by(candidate));
+        if let Some(authority_key_id) = &self.id_cert_tbs.authority_key_identifier {
+            if let Some(candidate) = candidates.clone().find(|candidate| {
+                candidate.id_cert_tbs.subject_key_identifier.as_ref() == Some(authority_key_id)
+            }) {
+                return Some(candidate);
+            }
+        }
+        let signature_data = self.signature_data().ok()?;
+        candidates.find(|candidate| {
+            candidate
+                .id_cert_tbs
+                .subject_public_key
+                .verify_signature(&self.signature, &signature_data)
+                .is_ok()
+        })
+    }
+
+    /// Returns the [FederationId] of the subject. See [IdCertTbs::federation_id()].
+    pub fn federation_id(&self) -> Result<FederationId, ConstraintError> {
+        self.id_cert_tbs.federation_id()
+    }
+
+    /// Returns the [SessionId] of the subject. See [IdCertTbs::session_id()].
+    pub fn session_id(&self) -> Result<SessionId, ConstraintError> {
+        self.id_cert_tbs.session_id()
+    }
+
+    /// Returns the home server domain of the subject. See [IdCertTbs::home_server_domain()].
+    pub fn home_server_domain(&self) -> Result<String, ConstraintError> {
+        self.id_cert_tbs.home_server_domain()
+    }
+
+    /// Returns the common name of the subject. See [IdCertTbs::common_name()].
+    pub fn common_name(&self) -> Result<String, ConstraintError> {
+        self.id_cert_tbs.common_name()
+    }
+
     /// Performs verification of the certificate, checking for the following properties:
     ///
     /// - The certificate is valid at the given `time`
+    /// - The signature algorithm of the certificate is the algorithm of `S`
     /// - The signature of the certificate is correct
     /// - The certificate is well-formed and up to polyproto specification
     /// - All parts that make up the certificate are well-formed and up to polyproto specification
@@ -264,15 +544,32 @@ impl<S: Signature, P: PublicKey<S>> IdCert<S, P> {
         time: u64,
         home_server_public_key: &P,
     ) -> Result<(), InvalidCert> {
-        if !self.valid_at(time) {
+        self.full_verify_actor_with_clock(&FixedClock::new(time), home_server_public_key)
+    }
+
+    /// Performs the same verification as [IdCert::full_verify_actor()], checking the validity
+    /// period of the certificate against the current time of the given [Clock] and its skew
+    /// tolerance.
+    pub fn full_verify_actor_with_clock(
+        &self,
+        clock: &dyn Clock,
+        home_server_public_key: &P,
+    ) -> Result<(), InvalidCert> {
+        if !self.valid_now(clock) {
             return Err(InvalidCert::InvalidValidity);
         }
-        log::trace!("[IdCert::full_verify_actor(&self)] verifying signature (actor certificate)");
-        let der = match self.id_cert_tbs.clone().to_der() {
+        ensure_algorithm(
+            &self.signature.signature_algorithm(),
+            &self.id_cert_tbs.signature_algorithm,
+        )?;
+        log::trace!(
+            "[IdCert::full_verify_actor_with_clock(&self)] verifying signature (actor certificate)"
+        );
+        let der = match self.signature_data() {
             Ok(der) => der,
             Err(_) => {
                 log::warn!(
-                    "[IdCert::full_verify_actor(&self)] {}",
+                    "[IdCert::full_verify_actor_with_clock(&self)] {}",
                     ERR_CERTIFICATE_TO_DER_ERROR
                 );
                 return Err(InvalidCert::InvalidProperties(ConstraintError::Malformed(
@@ -283,21 +580,68 @@ impl<S: Signature, P: PublicKey<S>> IdCert<S, P> {
         Ok(home_server_public_key.verify_signature(&self.signature, &der)?)
     }
 
+    /// Performs the same verification as [IdCert::full_verify_actor()], additionally consulting the
+    /// given [IdCrl] of the issuing home server. Checks for the following properties:
+    ///
+    /// - The [IdCrl] is valid at the given `time` and correctly signed by `home_server_cert`
+    /// - The issuer of the certificate equals the subject of `home_server_cert`
+    /// - All properties checked by [IdCert::full_verify_actor()], using the public key of
+    ///   `home_server_cert`
+    /// - The serial number of the certificate is not listed as revoked in the [IdCrl]
+    pub fn full_verify_actor_with_crl(
+        &self,
+        time: u64,
+        home_server_cert: &IdCert<S, P>,
+        crl: &IdCrl<S, P>,
+    ) -> Result<(), InvalidCert> {
+        crl.verify(home_server_cert, time)?;
+        if self.id_cert_tbs.issuer != home_server_cert.id_cert_tbs.subject {
+            log::warn!(
+                "[IdCert::full_verify_actor_with_crl(&self)] {}",
+                ERR_MSG_CERT_ISSUER_MISMATCH
+            );
+            return Err(InvalidCert::InvalidProperties(ConstraintError::Malformed(
+                Some(ERR_MSG_CERT_ISSUER_MISMATCH.to_string()),
+            )));
+        }
+        self.full_verify_actor(time, &home_server_cert.id_cert_tbs.subject_public_key)?;
+        if crl.is_revoked(&self.id_cert_tbs.serial_number, time) {
+            log::warn!(
+                "[IdCert::full_verify_actor_with_crl(&self)] certificate with serial number {:?} has been revoked",
+                self.id_cert_tbs.serial_number
+            );
+            return Err(InvalidCert::Revoked);
+        }
+        Ok(())
+    }
+
     /// Performs verification of the certificate, checking for the following properties:
     ///
     /// - The certificate is valid at the given `time`
+    /// - The signature algorithm of the certificate is the algorithm of `S`
     /// - The signature of the certificate is correct
     /// - The certificate is well-formed and up to polyproto specification
     /// - All parts that make up the certificate are well-formed and up to polyproto specification
     pub fn full_verify_home_server(&self, time: u64) -> Result<(), InvalidCert> {
-        if !self.valid_at(time) {
+        self.full_verify_home_server_with_clock(&FixedClock::new(time))
+    }
+
+    /// Performs the same verification as [IdCert::full_verify_home_server()], checking the
+    /// validity period of the certificate against the current time of the given [Clock] and its
+    /// skew tolerance.
+    pub fn full_verify_home_server_with_clock(&self, clock: &dyn Clock) -> Result<(), InvalidCert> {
+        if !self.valid_now(clock) {
             return Err(InvalidCert::InvalidValidity);
         }
-        let der = match self.id_cert_tbs.clone().to_der() {
+        ensure_algorithm(
+            &self.signature.signature_algorithm(),
+            &self.id_cert_tbs.signature_algorithm,
+        )?;
+        let der = match self.signature_data() {
             Ok(data) => data,
             Err(_) => {
                 log::warn!(
-                    "[IdCert::full_verify_home_server(&self)] {}",
+                    "[IdCert::full_verify_home_server_with_clock(&self)] {}",
                     ERR_CERTIFICATE_TO_DER_ERROR
                 );
                 return Err(InvalidCert::InvalidProperties(ConstraintError::Malformed(
@@ -306,20 +650,57 @@ impl<S: Signature, P: PublicKey<S>> IdCert<S, P> {
             }
         };
         log::trace!(
-            "[IdCert::full_verify_home_server(&self)] verifying signature (self-signed IdCert)"
+            "[IdCert::full_verify_home_server_with_clock(&self)] verifying signature (self-signed IdCert)"
         );
         Ok(self
             .id_cert_tbs
             .subject_public_key
             .verify_signature(&self.signature, &der)?)
     }
+
+    /// Performs the same verification as [IdCert::full_verify_actor()], additionally checking
+    /// that the certificate is well-formed for [Target::Actor] and meets the rules of the given
+    /// [ValidationPolicy].
+    pub fn full_verify_actor_with_policy(
+        &self,
+        time: u64,
+        home_server_public_key: &P,
+        policy: &ValidationPolicy,
+    ) -> Result<(), InvalidCert> {
+        self.full_verify_actor(time, home_server_public_key)?;
+        log::trace!(
+            "[IdCert::full_verify_actor_with_policy(&self)] validating certificate against {:?}",
+            policy
+        );
+        Ok(self.validate_with_policy(Some(Target::Actor), policy)?)
+    }
+
+    /// Performs the same verification as [IdCert::full_verify_home_server()], additionally
+    /// checking that the certificate is well-formed for [Target::HomeServer] and meets the rules
+    /// of the given [ValidationPolicy].
+    pub fn full_verify_home_server_with_policy(
+        &self,
+        time: u64,
+        policy: &ValidationPolicy,
+    ) -> Result<(), InvalidCert> {
+        self.full_verify_home_server(time)?;
+        log::trace!(
+            "[IdCert::full_verify_home_server_with_policy(&self)] validating certificate against {:?}",
+            policy
+        );
+        Ok(self.validate_with_policy(Some(Target::HomeServer), policy)?)
+    }
 }
 
 impl<S: Signature, P: PublicKey<S>> TryFrom<IdCert<S, P>> for Certificate {
     type Error = ConversionError;
     fn try_from(value: IdCert<S, P>) -> Result<Self, Self::Error> {
+        let tbs_certificate = match &value.original_der.0 {
+            Some(original_der) => TbsCertificate::from_der(original_der)?,
+            None => value.id_cert_tbs.clone().try_into()?,
+        };
         Ok(Self {
-            tbs_certificate: value.id_cert_tbs.clone().try_into()?,
+            tbs_certificate,
             signature_algorithm: value.id_cert_tbs.signature_algorithm,
             signature: value.signature.to_bitstring()?,
         })
@@ -333,12 +714,18 @@ impl<S: Signature, P: PublicKey<S>> TryFrom<Certificate> for IdCert<S, P> {
     /// manually, the caller is responsible for verifying the correctness of this `IdCert` using
     /// the [Constrained] trait.
     fn try_from(value: Certificate) -> Result<Self, Self::Error> {
+        // RFC 5280, Section 4.1.1.2: The signatureAlgorithm field MUST contain the same algorithm
+        // identifier as the signature field in the sequence tbsCertificate.
+        ensure_algorithm(&value.tbs_certificate.signature, &value.signature_algorithm)?;
+        // Converting the extensions may reorder them, so the encoding of the TBS certificate is
+        // kept. Decoding methods taking bytes replace it with the encoding found in their input.
+        let original_der = OriginalDer(value.tbs_certificate.to_der().ok());
         let id_cert_tbs = value.tbs_certificate.try_into()?;
-        let signature = S::from_bytes(value.signature.raw_bytes());
-        let cert = IdCert {
+        let signature = decode_signature(&value.signature_algorithm, &value.signature)?;
+        Ok(IdCert {
             id_cert_tbs,
             signature,
-        };
-        Ok(cert)
+            original_der,
+        })
     }
 }
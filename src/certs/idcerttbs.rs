@@ -2,24 +2,30 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use der::asn1::Uint;
+use der::asn1::{OctetString, Uint};
+use der::oid::AssociatedOid;
 use der::{Decode, Encode};
 use spki::AlgorithmIdentifierOwned;
 use x509_cert::certificate::{Profile, TbsCertificateInner};
-use x509_cert::ext::Extensions;
+use x509_cert::ext::pkix::{AuthorityKeyIdentifier, SubjectKeyIdentifier};
+use x509_cert::ext::{Extension, Extensions};
 use x509_cert::name::Name;
 use x509_cert::serial_number::SerialNumber;
 use x509_cert::time::Validity;
 use x509_cert::TbsCertificate;
 
-use crate::errors::ConversionError;
+use crate::clock::{Clock, FixedClock};
+use crate::errors::{ConstraintError, ConversionError};
 use crate::key::PublicKey;
 use crate::signature::Signature;
+use crate::types::FederationId;
 use crate::Constrained;
 
 use super::capabilities::Capabilities;
 use super::idcsr::IdCsr;
-use super::{PublicKeyInfo, Target};
+use super::name::{ActorName, HomeServerName};
+use super::serial_number::decode_serial_number;
+use super::{decode_public_key, NameComponents, PublicKeyInfo, SessionId, Target};
 
 /// An unsigned polyproto ID-Cert.
 ///
@@ -57,6 +63,12 @@ pub struct IdCertTbs<S: Signature, P: PublicKey<S>> {
     pub subject_public_key: P,
     /// Capabilities assigned to the subject of the certificate.
     pub capabilities: Capabilities,
+    /// Key identifier of the subjects' public key, encoded in the SubjectKeyIdentifier extension.
+    /// See [PublicKey::key_identifier()].
+    pub subject_key_identifier: Option<Vec<u8>>,
+    /// Key identifier of the issuers' public key, encoded in the AuthorityKeyIdentifier
+    /// extension. Identifies which key of the issuer has signed this certificate.
+    pub authority_key_identifier: Option<Vec<u8>>,
     /// PhantomData
     pub(crate) s: std::marker::PhantomData<S>,
 }
@@ -64,29 +76,38 @@ pub struct IdCertTbs<S: Signature, P: PublicKey<S>> {
 impl<S: Signature, P: PublicKey<S>> IdCertTbs<S, P> {
     /// Create a new [IdCertTbs] by passing an [IdCsr] and other supplementary information. Returns
     /// an error, if the provided IdCsr or issuer [Name] do not pass [Constrained] verification,
-    /// i.e. if they are not up to polyproto specification. Also fails if the provided IdCsr has
+    /// i.e. if they are not up to polyproto specification, or if the signature of the IdCsr does
+    /// not verify under its own subject public key. Also fails if the provided IdCsr has
     /// the [BasicConstraints] "ca" flag set to `true`.
     ///
     /// See [IdCertTbs::from_ca_csr()] when trying to create a new CA certificate for home servers.
     ///
+    /// The SubjectKeyIdentifier and AuthorityKeyIdentifier are derived from the public key of the
+    /// [IdCsr] and `issuer_public_key` respectively, see [PublicKey::key_identifier()].
+    ///
     /// The resulting `IdCertTbs` is guaranteed to be well-formed and up to polyproto specification,
     /// for the usage context of an actor certificate.
     pub fn from_actor_csr(
         id_csr: IdCsr<S, P>,
+        issuer_public_key: &P,
         serial_number: Uint,
         signature_algorithm: AlgorithmIdentifierOwned,
         issuer: Name,
         validity: Validity,
     ) -> Result<Self, ConversionError> {
+        id_csr.verify_signature()?;
         id_csr.validate(Some(Target::Actor))?;
+        let subject_key_identifier = id_csr.inner_csr().subject_public_key.key_identifier();
         let cert_tbs = IdCertTbs {
             serial_number,
             signature_algorithm,
             issuer,
             validity,
-            subject: id_csr.inner_csr.subject,
-            subject_public_key: id_csr.inner_csr.subject_public_key,
-            capabilities: id_csr.inner_csr.capabilities,
+            subject: id_csr.inner_csr().subject.clone(),
+            subject_public_key: id_csr.inner_csr().subject_public_key.clone(),
+            capabilities: id_csr.inner_csr().capabilities.clone(),
+            subject_key_identifier,
+            authority_key_identifier: issuer_public_key.key_identifier(),
             s: std::marker::PhantomData,
         };
         cert_tbs.validate(Some(Target::Actor))?;
@@ -95,29 +116,38 @@ impl<S: Signature, P: PublicKey<S>> IdCertTbs<S, P> {
 
     /// Create a new [IdCertTbs] by passing an [IdCsr] and other supplementary information. Returns
     /// an error, if the provided IdCsr or issuer [Name] do not pass [Constrained] verification,
-    /// i.e. if they are not up to polyproto specification. Also fails if the provided IdCsr has
+    /// i.e. if they are not up to polyproto specification, or if the signature of the IdCsr does
+    /// not verify under its own subject public key. Also fails if the provided IdCsr has
     /// the [BasicConstraints] "ca" flag set to `false`.
     ///
     /// See [IdCertTbs::from_actor_csr()] when trying to create a new actor certificate.
     ///
+    /// The SubjectKeyIdentifier and AuthorityKeyIdentifier are derived from the public key of the
+    /// [IdCsr] and `issuer_public_key` respectively, see [PublicKey::key_identifier()].
+    ///
     /// The resulting `IdCertTbs` is guaranteed to be well-formed and up to polyproto specification,
     /// for the usage context of a home server certificate.
     pub fn from_ca_csr(
         id_csr: IdCsr<S, P>,
+        issuer_public_key: &P,
         serial_number: Uint,
         signature_algorithm: AlgorithmIdentifierOwned,
         issuer: Name,
         validity: Validity,
     ) -> Result<Self, ConversionError> {
+        id_csr.verify_signature()?;
         id_csr.validate(Some(Target::HomeServer))?;
+        let subject_key_identifier = id_csr.inner_csr().subject_public_key.key_identifier();
         let cert_tbs = IdCertTbs {
             serial_number,
             signature_algorithm,
             issuer,
             validity,
-            subject: id_csr.inner_csr.subject,
-            subject_public_key: id_csr.inner_csr.subject_public_key,
-            capabilities: id_csr.inner_csr.capabilities,
+            subject: id_csr.inner_csr().subject.clone(),
+            subject_public_key: id_csr.inner_csr().subject_public_key.clone(),
+            capabilities: id_csr.inner_csr().capabilities.clone(),
+            subject_key_identifier,
+            authority_key_identifier: issuer_public_key.key_identifier(),
             s: std::marker::PhantomData,
         };
         cert_tbs.validate(Some(Target::HomeServer))?;
@@ -125,6 +155,9 @@ impl<S: Signature, P: PublicKey<S>> IdCertTbs<S, P> {
     }
 
     /// Encode this type as DER, returning a byte vector.
+    ///
+    /// The contents are always re-encoded. To keep the original encoding of a decoded
+    /// certificate, use [IdCert::signature_data()](super::idcert::IdCert::signature_data()).
     pub fn to_der(self) -> Result<Vec<u8>, ConversionError> {
         Ok(TbsCertificate::try_from(self)?.to_der()?)
     }
@@ -142,15 +175,67 @@ impl<S: Signature, P: PublicKey<S>> IdCertTbs<S, P> {
     /// responsible for verifying the correctness of this `IdCertTbs` using
     /// the [Constrained] trait before using it.
     pub fn from_der_unchecked(bytes: &[u8]) -> Result<Self, ConversionError> {
-        let cert = IdCertTbs::try_from(TbsCertificate::from_der(bytes)?)?;
-        Ok(cert)
+        IdCertTbs::try_from(TbsCertificate::from_der(bytes)?)
     }
 
     /// Checks if the IdCertTbs was valid at a given UNIX time. Does not validate the certificate
     /// against the polyproto specification.
     pub(crate) fn valid_at(&self, time: u64) -> bool {
-        time >= self.validity.not_before.to_unix_duration().as_secs()
-            && time <= self.validity.not_after.to_unix_duration().as_secs()
+        self.valid_now(&FixedClock::new(time))
+    }
+
+    /// Checks if the IdCertTbs is valid at the current time of the given [Clock], taking its skew
+    /// tolerance into account. Does not validate the certificate against the polyproto
+    /// specification.
+    pub(crate) fn valid_now(&self, clock: &dyn Clock) -> bool {
+        clock.is_within(
+            self.validity.not_before.to_unix_duration().as_secs(),
+            self.validity.not_after.to_unix_duration().as_secs(),
+        )
+    }
+
+    /// Returns the [FederationId] of the subject, taken from the "UID" attribute of the subject
+    /// [Name]. Fails, if there is not exactly one UID attribute, or if it is not a valid
+    /// `FederationId`. Home server certificates do not carry a federation ID.
+    pub fn federation_id(&self) -> Result<FederationId, ConstraintError> {
+        NameComponents::parse(&self.subject).federation_id()
+    }
+
+    /// Returns the [SessionId] of the subject, taken from the "uniqueIdentifier" attribute of the
+    /// subject [Name]. Fails, if there is not exactly one uniqueIdentifier attribute, or if it is
+    /// not a valid `SessionId`. Home server certificates do not carry a session ID.
+    pub fn session_id(&self) -> Result<SessionId, ConstraintError> {
+        NameComponents::parse(&self.subject).session_id()
+    }
+
+    /// Returns the home server domain of the subject, e.g. `polyphony.chat`, by joining the
+    /// "domain component" attributes of the subject [Name]. Fails, if there are none.
+    pub fn home_server_domain(&self) -> Result<String, ConstraintError> {
+        NameComponents::parse(&self.subject).home_server_domain()
+    }
+
+    /// Returns the "common name" attribute of the subject [Name]. Fails, if there is not exactly
+    /// one common name attribute.
+    pub fn common_name(&self) -> Result<String, ConstraintError> {
+        NameComponents::parse(&self.subject).common_name()
+    }
+
+    /// Returns the subject [Name] as an [ActorName]. Fails, if the subject is not a valid actor
+    /// name.
+    pub fn actor_name(&self) -> Result<ActorName, ConstraintError> {
+        ActorName::try_from(self.subject.clone())
+    }
+
+    /// Returns the subject [Name] as a [HomeServerName]. Fails, if the subject is not a valid home
+    /// server name.
+    pub fn home_server_name(&self) -> Result<HomeServerName, ConstraintError> {
+        HomeServerName::try_from(self.subject.clone())
+    }
+
+    /// Returns the issuer [Name] as a [HomeServerName]. Fails, if the issuer is not a valid home
+    /// server name.
+    pub fn issuer_name(&self) -> Result<HomeServerName, ConstraintError> {
+        HomeServerName::try_from(self.issuer.clone())
     }
 }
 
@@ -164,10 +249,9 @@ impl<P: Profile, S: Signature, Q: PublicKey<S>> TryFrom<TbsCertificateInner<P>>
     /// for verifying the `IdCertTbs` using the [Constrained] trait.
     fn try_from(value: TbsCertificateInner<P>) -> Result<Self, Self::Error> {
         value.subject.validate(None)?;
-
-        let capabilities =
+        let extensions =
             match value.extensions {
-                Some(ext) => Capabilities::try_from(ext)?,
+                Some(ext) => ext,
                 None => return Err(ConversionError::InvalidInput(
                     crate::errors::base::InvalidInput::Malformed(
                         "field 'extensions' was None. Expected: Some(x509_cert::ext::Extensions)"
@@ -175,11 +259,26 @@ impl<P: Profile, S: Signature, Q: PublicKey<S>> TryFrom<TbsCertificateInner<P>>
                     ),
                 )),
             };
-        let subject_public_key_info = PublicKey::try_from_public_key_info(PublicKeyInfo::from(
-            value.subject_public_key_info,
-        ))?;
+        let mut subject_key_identifier = None;
+        let mut authority_key_identifier = None;
+        let mut capability_extensions = Vec::new();
+        for extension in extensions.into_iter() {
+            if extension.extn_id == SubjectKeyIdentifier::OID {
+                let ski = SubjectKeyIdentifier::from_der(extension.extn_value.as_bytes())?;
+                subject_key_identifier = Some(ski.0.into_bytes());
+            } else if extension.extn_id == AuthorityKeyIdentifier::OID {
+                let aki = AuthorityKeyIdentifier::from_der(extension.extn_value.as_bytes())?;
+                authority_key_identifier = aki.key_identifier.map(OctetString::into_bytes);
+            } else {
+                capability_extensions.push(extension);
+            }
+        }
+        let capabilities = Capabilities::try_from(capability_extensions)?;
+        S::check_algorithm(&value.signature)?;
+        let subject_public_key_info =
+            decode_public_key(PublicKeyInfo::from(value.subject_public_key_info))?;
 
-        let serial_number = Uint::new(value.serial_number.as_bytes())?;
+        let serial_number = decode_serial_number(&value.serial_number)?;
 
         Ok(Self {
             serial_number,
@@ -189,6 +288,8 @@ impl<P: Profile, S: Signature, Q: PublicKey<S>> TryFrom<TbsCertificateInner<P>>
             subject: value.subject,
             subject_public_key: subject_public_key_info,
             capabilities,
+            subject_key_identifier,
+            authority_key_identifier,
             s: std::marker::PhantomData,
         })
     }
@@ -217,6 +318,37 @@ impl<P: Profile, S: Signature, Q: PublicKey<S>> TryFrom<IdCertTbs<S, Q>>
             parameters: value.signature_algorithm.parameters,
         };
 
+        // Key identifiers are placed between the known capabilities and any retained unknown
+        // extensions, mirroring the order in which they are emitted by this crate.
+        let unknown_extensions = value.capabilities.unknown_extensions.len();
+        let mut extensions = Extensions::try_from(value.capabilities)?;
+        let mut key_identifiers = Vec::new();
+        if let Some(key_identifier) = value.subject_key_identifier {
+            key_identifiers.push(Extension {
+                extn_id: SubjectKeyIdentifier::OID,
+                critical: false,
+                extn_value: OctetString::new(
+                    SubjectKeyIdentifier(OctetString::new(key_identifier)?).to_der()?,
+                )?,
+            });
+        }
+        if let Some(key_identifier) = value.authority_key_identifier {
+            key_identifiers.push(Extension {
+                extn_id: AuthorityKeyIdentifier::OID,
+                critical: false,
+                extn_value: OctetString::new(
+                    AuthorityKeyIdentifier {
+                        key_identifier: Some(OctetString::new(key_identifier)?),
+                        authority_cert_issuer: None,
+                        authority_cert_serial_number: None,
+                    }
+                    .to_der()?,
+                )?,
+            });
+        }
+        let position = extensions.len() - unknown_extensions;
+        extensions.splice(position..position, key_identifiers);
+
         Ok(TbsCertificateInner {
             version: x509_cert::Version::V3,
             serial_number,
@@ -227,7 +359,7 @@ impl<P: Profile, S: Signature, Q: PublicKey<S>> TryFrom<IdCertTbs<S, Q>>
             subject_public_key_info: value.subject_public_key.public_key_info().into(),
             issuer_unique_id: None,
             subject_unique_id: None,
-            extensions: Some(Extensions::try_from(value.capabilities)?),
+            extensions: Some(extensions),
         })
     }
 }
@@ -0,0 +1,594 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::marker::PhantomData;
+
+use der::asn1::{OctetString, Uint};
+use der::oid::AssociatedOid;
+use der::pem::LineEnding;
+use der::{Decode, Encode};
+use spki::AlgorithmIdentifierOwned;
+use x509_cert::crl::{CertificateList, RevokedCert, TbsCertList};
+use x509_cert::ext::pkix::crl::CrlReason;
+use x509_cert::ext::pkix::{AuthorityKeyIdentifier, CrlNumber};
+use x509_cert::ext::Extension;
+use x509_cert::name::Name;
+use x509_cert::serial_number::SerialNumber;
+use x509_cert::time::Time;
+use x509_cert::Version;
+
+use crate::clock::{Clock, FixedClock};
+use crate::errors::{
+    ConstraintError, ConversionError, InvalidCert, InvalidInput,
+    ERR_MSG_CRL_AUTHORITY_KEY_ID_MISMATCH, ERR_MSG_CRL_ISSUER_MISMATCH,
+    ERR_MSG_HOME_SERVER_MISSING_CRL_SIGN,
+};
+use crate::key::{PrivateKey, PublicKey};
+use crate::signature::Signature;
+use crate::Constrained;
+
+use super::capabilities::KeyUsage;
+use super::idcert::IdCert;
+use super::serial_number::{decode_serial_number, validate_serial_number};
+use super::{
+    decode_signature, encode_signed, ensure_algorithm, signed_data_der, signing_algorithm,
+    OriginalDer, Target,
+};
+
+/// The PEM label of a DER encoded X.509 `CertificateList`, as defined in RFC 7468.
+pub const PEM_LABEL_CRL: &str = "X509 CRL";
+
+/// A single entry of an [IdCrl], identifying a revoked [IdCert] by its serial number.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RevokedIdCert {
+    /// The serial number of the revoked certificate.
+    pub serial_number: Uint,
+    /// The point in time at which the certificate has been revoked.
+    pub revocation_date: Time,
+    /// The reason for the revocation, if any was given.
+    pub reason: Option<CrlReason>,
+    /// Further non-critical entry extensions, which are not interpreted by this crate. They are
+    /// retained unchanged when decoding and encoding the entry.
+    pub extensions: Vec<Extension>,
+}
+
+impl RevokedIdCert {
+    /// Creates a new [RevokedIdCert].
+    pub fn new(serial_number: Uint, revocation_date: Time, reason: Option<CrlReason>) -> Self {
+        Self {
+            serial_number,
+            revocation_date,
+            reason,
+            extensions: Vec::new(),
+        }
+    }
+}
+
+/// The unsigned contents of an [IdCrl]. In the context of X.509, this is a `TBSCertList`:
+///
+/// ```md
+/// TBSCertList  ::=  SEQUENCE  {
+///      version                 Version OPTIONAL, -- if present, MUST be v2
+///      signature               AlgorithmIdentifier,
+///      issuer                  Name,
+///      thisUpdate              Time,
+///      nextUpdate              Time OPTIONAL,
+///      revokedCertificates     SEQUENCE OF SEQUENCE  { ... }  OPTIONAL,
+///      crlExtensions           [0]  EXPLICIT Extensions OPTIONAL
+/// }
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct IdCrlTbs {
+    /// The version of the CRL. CRLs issued by this crate are v2 CRLs, as they carry extensions.
+    pub version: Version,
+    /// The signature algorithm used by the home server to sign this CRL.
+    pub signature_algorithm: AlgorithmIdentifierOwned,
+    /// X.501 name, identifying the home server which issued this CRL.
+    pub issuer: Name,
+    /// The point in time at which this CRL has been issued.
+    pub this_update: Time,
+    /// The point in time at which the next CRL will be issued, if known.
+    pub next_update: Option<Time>,
+    /// The revoked certificates.
+    pub revoked_certificates: Vec<RevokedIdCert>,
+    /// The CRL number, a monotonically increasing sequence number of the CRLs issued by the home
+    /// server, as defined in RFC 5280, Section 5.2.3.
+    pub crl_number: Option<Uint>,
+    /// The key identifier of the public key of the home server which issued this CRL, taken from
+    /// the AuthorityKeyIdentifier extension, as defined in RFC 5280, Section 5.2.1.
+    pub authority_key_identifier: Option<Vec<u8>>,
+    /// Further non-critical CRL extensions, which are not interpreted by this crate. They are
+    /// retained unchanged when decoding and encoding the CRL.
+    pub extensions: Vec<Extension>,
+}
+
+impl IdCrlTbs {
+    /// Encode this type as DER, returning a byte vector.
+    ///
+    /// The contents are always re-encoded. To keep the original encoding of a decoded CRL, use
+    /// [IdCrl::signature_data()].
+    pub fn to_der(self) -> Result<Vec<u8>, ConversionError> {
+        Ok(TbsCertList::try_from(self)?.to_der()?)
+    }
+}
+
+/// A certificate revocation list (CRL), issued and signed by a polyproto home server. Lists the
+/// serial numbers of [IdCert]s issued by that home server, which must no longer be trusted.
+///
+/// ## Generic Parameters
+///
+/// - **S**: The [Signature] this CRL was signed with.
+/// - **P**: A [PublicKey] type P which can be used to verify [Signature]s of type S.
+///
+/// ## Verifying an IdCrl
+///
+/// An `IdCrl` must be verified against the [IdCert] of the home server which issued it using
+/// [IdCrl::verify()], before its contents can be trusted.
+///
+/// ## Modifying an IdCrl
+///
+/// The [IdCrlTbs] of a decoded `IdCrl` keeps the exact encoding it has been decoded from, so that
+/// its signature remains verifiable. Modifying it through [IdCrl::id_crl_tbs_mut()] discards that
+/// encoding.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct IdCrl<S: Signature, P: PublicKey<S>> {
+    /// Inner TBS (To be signed) CRL
+    id_crl_tbs: IdCrlTbs,
+    /// Signature for the TBS CRL
+    pub signature: S,
+    /// The DER encoding `id_crl_tbs` has been decoded from, if any.
+    original_der: OriginalDer,
+    phantom_data: PhantomData<P>,
+}
+
+impl<S: Signature, P: PublicKey<S>> IdCrl<S, P> {
+    /// Create and sign a new [IdCrl]. Returns an error, if the provided issuer [Name] does not pass
+    /// [Constrained] verification for the [Target] of a home server.
+    ///
+    /// ## Parameters
+    ///
+    /// - `issuer`: The [Name] of the home server issuing this CRL. Must be equal to the subject of
+    ///   the home server's [IdCert].
+    /// - `crl_number`: The CRL number of this CRL, which must be greater than the CRL numbers of
+    ///   all CRLs previously issued by the home server. Like certificate serial numbers, CRL
+    ///   numbers must pass [validate_serial_number()].
+    /// - `revoked_certificates`: The certificates to revoke.
+    /// - `this_update`: The point in time at which this CRL is issued.
+    /// - `next_update`: The point in time at which the next CRL will be issued, if known.
+    /// - `signing_key`: The home server's private key, used to sign the CRL.
+    ///
+    /// As required by RFC 5280, Section 5.2, the CRL carries the CRL Number extension and, if the
+    /// public key of `signing_key` has a [PublicKey::key_identifier()], the
+    /// AuthorityKeyIdentifier extension.
+    pub fn new(
+        issuer: Name,
+        crl_number: Uint,
+        revoked_certificates: Vec<RevokedIdCert>,
+        this_update: Time,
+        next_update: Option<Time>,
+        signing_key: &impl PrivateKey<S, PublicKey = P>,
+    ) -> Result<Self, ConversionError> {
+        issuer.validate(Some(Target::HomeServer))?;
+        validate_serial_number(&crl_number)?;
+        let id_crl_tbs = IdCrlTbs {
+            version: Version::V2,
+            signature_algorithm: signing_algorithm(signing_key.algorithm_identifier())?,
+            issuer,
+            this_update,
+            next_update,
+            revoked_certificates,
+            crl_number: Some(crl_number),
+            authority_key_identifier: signing_key.pubkey().key_identifier(),
+            extensions: Vec::new(),
+        };
+        let signature = signing_key.sign(&id_crl_tbs.clone().to_der()?)?;
+        Ok(Self {
+            id_crl_tbs,
+            signature,
+            original_der: OriginalDer::default(),
+            phantom_data: PhantomData,
+        })
+    }
+
+    /// The inner TBS (To be signed) CRL.
+    pub fn id_crl_tbs(&self) -> &IdCrlTbs {
+        &self.id_crl_tbs
+    }
+
+    /// Mutable access to the inner TBS (To be signed) CRL. Discards the encoding the CRL has been
+    /// decoded from, so that [IdCrl::signature_data()] and [IdCrl::to_der()] re-encode the,
+    /// possibly modified, contents.
+    pub fn id_crl_tbs_mut(&mut self) -> &mut IdCrlTbs {
+        self.original_der = OriginalDer::default();
+        &mut self.id_crl_tbs
+    }
+
+    /// Create an [IdCrl] from a byte slice containing a DER encoded X.509 `CertificateList`, and
+    /// verify it against the [IdCert] of the issuing home server at the given UNIX `time`. See
+    /// [IdCrl::verify()] for the checks performed.
+    pub fn from_der(
+        value: &[u8],
+        home_server_cert: &IdCert<S, P>,
+        time: u64,
+    ) -> Result<Self, InvalidCert> {
+        let crl = match IdCrl::from_der_unchecked(value) {
+            Ok(crl) => crl,
+            Err(e) => {
+                return Err(InvalidCert::InvalidProperties(ConstraintError::Malformed(
+                    Some(e.to_string()),
+                )))
+            }
+        };
+        crl.verify(home_server_cert, time)?;
+        Ok(crl)
+    }
+
+    /// Create an unchecked [IdCrl] from a byte slice containing a DER encoded X.509
+    /// `CertificateList`. The caller is responsible for verifying the correctness of this `IdCrl`
+    /// using [IdCrl::verify()] before using it.
+    pub fn from_der_unchecked(value: &[u8]) -> Result<Self, ConversionError> {
+        let mut crl = IdCrl::try_from(CertificateList::from_der(value)?)?;
+        crl.original_der = OriginalDer(Some(signed_data_der(value)?));
+        Ok(crl)
+    }
+
+    /// Encode this type as DER, returning a byte vector.
+    ///
+    /// If this `IdCrl` has been decoded and [IdCrl::id_crl_tbs_mut()] has not been called since,
+    /// the original encoding of its [IdCrlTbs] is kept unchanged, so that the signature over it
+    /// remains verifiable.
+    pub fn to_der(self) -> Result<Vec<u8>, ConversionError> {
+        if let Some(original_der) = &self.original_der.0 {
+            return encode_signed(
+                original_der,
+                &self.id_crl_tbs.signature_algorithm,
+                &self.signature.to_bitstring()?,
+            );
+        }
+        Ok(CertificateList::try_from(self)?.to_der()?)
+    }
+
+    /// Create an [IdCrl] from a string containing a PEM encoded X.509 `CertificateList`, and
+    /// verify it against the [IdCert] of the issuing home server at the given UNIX `time`. See
+    /// [IdCrl::verify()] for the checks performed.
+    pub fn from_pem(
+        pem: &str,
+        home_server_cert: &IdCert<S, P>,
+        time: u64,
+    ) -> Result<Self, InvalidCert> {
+        let crl = match IdCrl::from_pem_unchecked(pem) {
+            Ok(crl) => crl,
+            Err(e) => {
+                return Err(InvalidCert::InvalidProperties(ConstraintError::Malformed(
+                    Some(e.to_string()),
+                )))
+            }
+        };
+        crl.verify(home_server_cert, time)?;
+        Ok(crl)
+    }
+
+    /// Create an unchecked [IdCrl] from a string containing a PEM encoded X.509
+    /// `CertificateList`. The caller is responsible for verifying the correctness of this `IdCrl`
+    /// using [IdCrl::verify()] before using it.
+    pub fn from_pem_unchecked(pem: &str) -> Result<Self, ConversionError> {
+        let (label, der) = der::pem::decode_vec(pem.as_bytes()).map_err(der::Error::from)?;
+        if label != PEM_LABEL_CRL {
+            return Err(ConversionError::InvalidInput(InvalidInput::Malformed(
+                format!(
+                    "Expected PEM label \"{}\", found \"{}\"",
+                    PEM_LABEL_CRL, label
+                ),
+            )));
+        }
+        IdCrl::from_der_unchecked(&der)
+    }
+
+    /// Encode this type as PEM, returning a string.
+    pub fn to_pem(self, line_ending: LineEnding) -> Result<String, ConversionError> {
+        let der = self.to_der()?;
+        Ok(der::pem::encode_string(PEM_LABEL_CRL, line_ending, &der).map_err(der::Error::from)?)
+    }
+
+    /// Returns a byte vector containing the DER encoded [IdCrlTbs]. This data is encoded
+    /// in the signature field of the CRL, and can be used to verify the signature. For a CRL
+    /// decoded using one of the `from_der` or `from_pem` methods, these are the exact bytes of the
+    /// `tbsCertList` found in the input, as long as [IdCrl::id_crl_tbs_mut()] has not been called
+    /// since.
+    pub fn signature_data(&self) -> Result<Vec<u8>, ConversionError> {
+        match &self.original_der.0 {
+            Some(original_der) => Ok(original_der.clone()),
+            None => self.id_crl_tbs.clone().to_der(),
+        }
+    }
+
+    /// Returns the [RevokedIdCert] entry for the given serial number, if the certificate with this
+    /// serial number has been revoked at or before the given UNIX `time`.
+    pub fn revocation(&self, serial_number: &Uint, time: u64) -> Option<&RevokedIdCert> {
+        self.id_crl_tbs.revoked_certificates.iter().find(|entry| {
+            entry.serial_number == *serial_number
+                && entry.revocation_date.to_unix_duration().as_secs() <= time
+        })
+    }
+
+    /// Checks, if the certificate with the given serial number has been revoked at or before the
+    /// given UNIX `time`. Does not verify the CRL itself; use [IdCrl::verify()] for that.
+    pub fn is_revoked(&self, serial_number: &Uint, time: u64) -> bool {
+        self.revocation(serial_number, time).is_some()
+    }
+
+    /// Verifies this CRL against the [IdCert] of the home server which issued it, checking for
+    /// the following properties:
+    ///
+    /// - The issuer of the CRL equals the subject of `home_server_cert`
+    /// - The AuthorityKeyIdentifier of the CRL equals the SubjectKeyIdentifier of
+    ///   `home_server_cert`, if both are present
+    /// - `home_server_cert` carries the [KeyUsage::CrlSign] key usage
+    /// - The CRL has been issued at or before `time`, and `time` is not past its `next_update`
+    /// - The signature algorithm of the CRL is the algorithm of `S`
+    /// - The signature of the CRL is valid under the public key of `home_server_cert`
+    pub fn verify(&self, home_server_cert: &IdCert<S, P>, time: u64) -> Result<(), InvalidCert> {
+        self.verify_with_clock(home_server_cert, &FixedClock::new(time))
+    }
+
+    /// Performs the same verification as [IdCrl::verify()], checking the update period of the
+    /// CRL against the current time of the given [Clock] and its skew tolerance.
+    pub fn verify_with_clock(
+        &self,
+        home_server_cert: &IdCert<S, P>,
+        clock: &dyn Clock,
+    ) -> Result<(), InvalidCert> {
+        if self.id_crl_tbs.issuer != home_server_cert.id_cert_tbs().subject {
+            log::warn!(
+                "[IdCrl::verify_with_clock()] {}",
+                ERR_MSG_CRL_ISSUER_MISMATCH
+            );
+            return Err(InvalidCert::InvalidProperties(ConstraintError::Malformed(
+                Some(ERR_MSG_CRL_ISSUER_MISMATCH.to_string()),
+            )));
+        }
+        if let (Some(authority_key_id), Some(subject_key_id)) = (
+            &self.id_crl_tbs.authority_key_identifier,
+            &home_server_cert.id_cert_tbs().subject_key_identifier,
+        ) {
+            if authority_key_id != subject_key_id {
+                log::warn!(
+                    "[IdCrl::verify_with_clock()] {}",
+                    ERR_MSG_CRL_AUTHORITY_KEY_ID_MISMATCH
+                );
+                return Err(InvalidCert::InvalidProperties(ConstraintError::Malformed(
+                    Some(ERR_MSG_CRL_AUTHORITY_KEY_ID_MISMATCH.to_string()),
+                )));
+            }
+        }
+        if !home_server_cert
+            .id_cert_tbs()
+            .capabilities
+            .key_usage
+            .key_usages
+            .contains(&KeyUsage::CrlSign)
+        {
+            log::warn!(
+                "[IdCrl::verify_with_clock()] {}",
+                ERR_MSG_HOME_SERVER_MISSING_CRL_SIGN
+            );
+            return Err(InvalidCert::InvalidProperties(ConstraintError::Malformed(
+                Some(ERR_MSG_HOME_SERVER_MISSING_CRL_SIGN.to_string()),
+            )));
+        }
+        let next_update = match self.id_crl_tbs.next_update {
+            Some(next_update) => next_update.to_unix_duration().as_secs(),
+            None => u64::MAX,
+        };
+        if !clock.is_within(
+            self.id_crl_tbs.this_update.to_unix_duration().as_secs(),
+            next_update,
+        ) {
+            return Err(InvalidCert::InvalidValidity);
+        }
+        ensure_algorithm(
+            &self.signature.signature_algorithm(),
+            &self.id_crl_tbs.signature_algorithm,
+        )?;
+        let signature_data = self.signature_data().map_err(|e| {
+            InvalidCert::InvalidProperties(ConstraintError::Malformed(Some(e.to_string())))
+        })?;
+        log::trace!("[IdCrl::verify_with_clock()] verifying signature");
+        Ok(home_server_cert
+            .id_cert_tbs()
+            .subject_public_key
+            .verify_signature(&self.signature, &signature_data)?)
+    }
+}
+
+impl TryFrom<RevokedCert> for RevokedIdCert {
+    type Error = ConversionError;
+
+    fn try_from(value: RevokedCert) -> Result<Self, Self::Error> {
+        let mut reason = None;
+        let mut extensions = Vec::new();
+        for extension in value.crl_entry_extensions.unwrap_or_default().into_iter() {
+            if extension.extn_id == CrlReason::OID {
+                reason = Some(CrlReason::from_der(extension.extn_value.as_bytes())?);
+            } else if extension.critical {
+                log::warn!(
+                    "[RevokedIdCert::try_from()] Unknown critical extension: {}",
+                    extension.extn_id
+                );
+                return Err(ConversionError::UnknownCriticalExtension {
+                    oid: extension.extn_id,
+                });
+            } else {
+                extensions.push(extension);
+            }
+        }
+        Ok(Self {
+            serial_number: decode_serial_number(&value.serial_number)?,
+            revocation_date: value.revocation_date,
+            reason,
+            extensions,
+        })
+    }
+}
+
+impl TryFrom<RevokedIdCert> for RevokedCert {
+    type Error = ConversionError;
+
+    fn try_from(value: RevokedIdCert) -> Result<Self, Self::Error> {
+        let mut crl_entry_extensions = Vec::new();
+        if let Some(reason) = value.reason {
+            crl_entry_extensions.push(Extension {
+                extn_id: CrlReason::OID,
+                critical: false,
+                extn_value: OctetString::new(reason.to_der()?)?,
+            });
+        }
+        crl_entry_extensions.extend(value.extensions);
+        Ok(Self {
+            serial_number: SerialNumber::new(value.serial_number.as_bytes())?,
+            revocation_date: value.revocation_date,
+            crl_entry_extensions: match crl_entry_extensions.is_empty() {
+                true => None,
+                false => Some(crl_entry_extensions),
+            },
+        })
+    }
+}
+
+impl TryFrom<TbsCertList> for IdCrlTbs {
+    type Error = ConversionError;
+
+    /// Tries to convert a [TbsCertList] into an [IdCrlTbs]. The Ok() variant of this Result is an
+    /// unverified `IdCrlTbs`.
+    fn try_from(value: TbsCertList) -> Result<Self, Self::Error> {
+        let mut crl_number = None;
+        let mut authority_key_identifier = None;
+        let mut extensions = Vec::new();
+        for extension in value.crl_extensions.unwrap_or_default().into_iter() {
+            if extension.extn_id == CrlNumber::OID {
+                crl_number = Some(CrlNumber::from_der(extension.extn_value.as_bytes())?.0);
+            } else if extension.extn_id == AuthorityKeyIdentifier::OID {
+                let aki = AuthorityKeyIdentifier::from_der(extension.extn_value.as_bytes())?;
+                authority_key_identifier = aki.key_identifier.map(OctetString::into_bytes);
+            } else if extension.critical {
+                log::warn!(
+                    "[IdCrlTbs::try_from()] Unknown critical extension: {}",
+                    extension.extn_id
+                );
+                return Err(ConversionError::UnknownCriticalExtension {
+                    oid: extension.extn_id,
+                });
+            } else {
+                extensions.push(extension);
+            }
+        }
+        let mut revoked_certificates = Vec::new();
+        for revoked in value.revoked_certificates.unwrap_or_default().into_iter() {
+            revoked_certificates.push(RevokedIdCert::try_from(revoked)?);
+        }
+        Ok(Self {
+            version: value.version,
+            signature_algorithm: value.signature,
+            issuer: value.issuer,
+            this_update: value.this_update,
+            next_update: value.next_update,
+            revoked_certificates,
+            crl_number,
+            authority_key_identifier,
+            extensions,
+        })
+    }
+}
+
+impl TryFrom<IdCrlTbs> for TbsCertList {
+    type Error = ConversionError;
+
+    fn try_from(value: IdCrlTbs) -> Result<Self, Self::Error> {
+        let mut crl_extensions = Vec::new();
+        if let Some(key_identifier) = value.authority_key_identifier {
+            crl_extensions.push(Extension {
+                extn_id: AuthorityKeyIdentifier::OID,
+                critical: false,
+                extn_value: OctetString::new(
+                    AuthorityKeyIdentifier {
+                        key_identifier: Some(OctetString::new(key_identifier)?),
+                        authority_cert_issuer: None,
+                        authority_cert_serial_number: None,
+                    }
+                    .to_der()?,
+                )?,
+            });
+        }
+        if let Some(crl_number) = value.crl_number {
+            crl_extensions.push(Extension {
+                extn_id: CrlNumber::OID,
+                critical: false,
+                extn_value: OctetString::new(CrlNumber(crl_number).to_der()?)?,
+            });
+        }
+        crl_extensions.extend(value.extensions);
+        let mut revoked_certificates = Vec::new();
+        for revoked in value.revoked_certificates.into_iter() {
+            revoked_certificates.push(RevokedCert::try_from(revoked)?);
+        }
+        Ok(Self {
+            version: value.version,
+            signature: value.signature_algorithm,
+            issuer: value.issuer,
+            this_update: value.this_update,
+            next_update: value.next_update,
+            // RFC 5280: When there are no revoked certificates, the revoked certificates list MUST
+            // be absent.
+            revoked_certificates: match revoked_certificates.is_empty() {
+                true => None,
+                false => Some(revoked_certificates),
+            },
+            crl_extensions: match crl_extensions.is_empty() {
+                true => None,
+                false => Some(crl_extensions),
+            },
+        })
+    }
+}
+
+impl<S: Signature, P: PublicKey<S>> TryFrom<CertificateList> for IdCrl<S, P> {
+    type Error = ConversionError;
+
+    /// Tries to convert a [CertificateList] into an [IdCrl]. The Ok() variant of this method
+    /// contains the `IdCrl` if the conversion was successful. If this conversion is called
+    /// manually, the caller is responsible for verifying the correctness of this `IdCrl` using
+    /// [IdCrl::verify()].
+    fn try_from(value: CertificateList) -> Result<Self, Self::Error> {
+        // RFC 5280, Section 5.1.1.2: The signatureAlgorithm field MUST contain the same algorithm
+        // identifier as the signature field in the sequence tbsCertList.
+        ensure_algorithm(&value.tbs_cert_list.signature, &value.signature_algorithm)?;
+        S::check_algorithm(&value.signature_algorithm)?;
+        // Without access to the bytes `value` has been decoded from, its re-encoding is the
+        // closest available approximation of the original encoding. Decoding methods taking bytes
+        // replace it with the encoding found in their input.
+        let original_der = OriginalDer(value.tbs_cert_list.to_der().ok());
+        Ok(Self {
+            id_crl_tbs: IdCrlTbs::try_from(value.tbs_cert_list)?,
+            signature: decode_signature(&value.signature_algorithm, &value.signature)?,
+            original_der,
+            phantom_data: PhantomData,
+        })
+    }
+}
+
+impl<S: Signature, P: PublicKey<S>> TryFrom<IdCrl<S, P>> for CertificateList {
+    type Error = ConversionError;
+
+    fn try_from(value: IdCrl<S, P>) -> Result<Self, Self::Error> {
+        let tbs_cert_list = match value.original_der.0 {
+            Some(original_der) => TbsCertList::from_der(&original_der)?,
+            None => value.id_crl_tbs.clone().try_into()?,
+        };
+        Ok(Self {
+            signature_algorithm: value.id_crl_tbs.signature_algorithm,
+            tbs_cert_list,
+            signature: value.signature.to_bitstring()?,
+        })
+    }
+}
@@ -4,20 +4,26 @@
 
 use std::marker::PhantomData;
 
-use der::pem::LineEnding;
-use der::{Decode, DecodePem, Encode, EncodePem};
+use der::pem::{LineEnding, PemLabel};
+use der::{Decode, Encode};
 use spki::AlgorithmIdentifierOwned;
 use x509_cert::attr::Attributes;
 use x509_cert::name::Name;
 use x509_cert::request::{CertReq, CertReqInfo};
 
-use crate::errors::ConversionError;
-use crate::key::{PrivateKey, PublicKey};
+use crate::digest::{Digest, Fingerprint};
+use crate::errors::{ConstraintError, ConversionError, InvalidCert};
+use crate::key::{AsyncPrivateKey, PrivateKey, PublicKey};
 use crate::signature::Signature;
+use crate::types::FederationId;
 use crate::Constrained;
 
 use super::capabilities::Capabilities;
-use super::{PkcsVersion, PublicKeyInfo, Target};
+use super::name::{ActorName, HomeServerName};
+use super::{
+    decode_pem, decode_public_key, decode_signature, encode_signed, ensure_algorithm,
+    signed_data_der, NameComponents, OriginalDer, PkcsVersion, PublicKeyInfo, SessionId, Target,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 /// A polyproto Certificate Signing Request, compatible with [IETF RFC 2986 "PKCS #10"](https://datatracker.ietf.org/doc/html/rfc2986).
@@ -33,13 +39,19 @@ use super::{PkcsVersion, PublicKeyInfo, Target};
 ///     signature          BIT STRING
 /// }
 /// ```
+///
+/// The [IdCsrInner] of a decoded `IdCsr` keeps the exact encoding it has been decoded from, so
+/// that its signature remains verifiable. Modifying it through [IdCsr::inner_csr_mut()] discards
+/// that encoding.
 pub struct IdCsr<S: Signature, P: PublicKey<S>> {
     /// The CSRs main contents.
-    pub inner_csr: IdCsrInner<S, P>,
+    inner_csr: IdCsrInner<S, P>,
     /// The signature algorithm, with which the [Signature] was created.
     pub signature_algorithm: AlgorithmIdentifierOwned,
     /// [Signature] value for the `inner_csr`
     pub signature: S,
+    /// The DER encoding `inner_csr` has been decoded from, if any.
+    original_der: OriginalDer,
 }
 impl<S: Signature, P: PublicKey<S>> IdCsr<S, P> {
     /// Performs basic input validation and creates a new polyproto ID-Cert CSR, according to
@@ -50,17 +62,17 @@ impl<S: Signature, P: PublicKey<S>> IdCsr<S, P> {
     /// - **subject**: A [Name], comprised of:
     ///   - Common Name: The federation ID of the subject (actor)
     ///   - Domain Component: Actor home server subdomain, if applicable. May be repeated, depending
-    ///                       on how many subdomain levels there are.
+    ///     on how many subdomain levels there are.
     ///   - Domain Component: Actor home server domain.
     ///   - Domain Component: Actor home server TLD, if applicable.
     ///   - Session ID: [SessionId], an Ia5String, max 32 characters. You can use the [SessionId] struct
-    ///                 and its [SessionId::new_validated()] and [SessionId::to_rdn_sequence()] methods
-    ///                 to help you create a valid SessionId.
+    ///     and its [SessionId::new_validated()] and [SessionId::to_rdn_sequence()] methods
+    ///     to help you create a valid SessionId.
     /// - **signing_key**: Subject signing key. Will NOT be included in the certificate. Is used to
-    ///                    sign the CSR.
+    ///   sign the CSR.
     /// - **capabilities**: The capabilities requested by the subject.
     /// - **target**: The [Target] for which the CSR is intended. This is used to validate the CSR
-    ///               against the polyproto specification.
+    ///   against the polyproto specification.
     ///
     /// The resulting `IdCsr` is guaranteed to be well-formed and up to polyproto specification,
     /// if the correct [Target] for the CSRs intended usage context is provided.
@@ -70,25 +82,63 @@ impl<S: Signature, P: PublicKey<S>> IdCsr<S, P> {
         capabilities: &Capabilities,
         target: Option<Target>,
     ) -> Result<IdCsr<S, P>, ConversionError> {
-        let inner_csr = IdCsrInner::<S, P> {
-            version: PkcsVersion::V1,
-            subject: subject.clone(),
-            subject_public_key: signing_key.pubkey().clone(),
-            capabilities: capabilities.clone(),
-            phantom_data: PhantomData,
-        };
-        let signature = signing_key.sign(&inner_csr.clone().to_der()?);
-        let signature_algorithm = S::algorithm_identifier();
+        let inner_csr = IdCsrInner::unsigned(subject, signing_key.pubkey(), capabilities);
+        let signature = PrivateKey::sign(signing_key, &inner_csr.clone().to_der()?)?;
+        log::trace!("[IdCsr::new()] Validating self with Target: {:?}", target);
+        IdCsr::from_signed_inner(inner_csr, signature, target)
+    }
+
+    /// Performs basic input validation and creates a new polyproto ID-Cert CSR, like
+    /// [IdCsr::new()]. The CSR is signed using an [AsyncPrivateKey], which may be held by a remote
+    /// signer. Fails with [ConversionError::SigningError], if the signer fails to create the
+    /// signature.
+    ///
+    /// The resulting `IdCsr` has the same guarantees as one created using [IdCsr::new()].
+    pub async fn new_async(
+        subject: &Name,
+        signing_key: &impl AsyncPrivateKey<S, PublicKey = P>,
+        capabilities: &Capabilities,
+        target: Option<Target>,
+    ) -> Result<IdCsr<S, P>, ConversionError> {
+        let inner_csr = IdCsrInner::unsigned(subject, signing_key.pubkey(), capabilities);
+        let signature_data = inner_csr.clone().to_der()?;
+        let signature = AsyncPrivateKey::sign(signing_key, &signature_data).await?;
+        log::trace!(
+            "[IdCsr::new_async()] Validating self with Target: {:?}",
+            target
+        );
+        IdCsr::from_signed_inner(inner_csr, signature, target)
+    }
+
+    /// Assembles an [IdCsr] from its signed `inner_csr` and validates it against `target`.
+    fn from_signed_inner(
+        inner_csr: IdCsrInner<S, P>,
+        signature: S,
+        target: Option<Target>,
+    ) -> Result<Self, ConversionError> {
         let id_csr = IdCsr {
             inner_csr,
-            signature_algorithm,
+            signature_algorithm: signature.signature_algorithm(),
             signature,
+            original_der: OriginalDer::default(),
         };
-        log::trace!("[IdCsr::new()] Validating self with Target: {:?}", target);
         id_csr.validate(target)?;
         Ok(id_csr)
     }
 
+    /// The CSRs main contents.
+    pub fn inner_csr(&self) -> &IdCsrInner<S, P> {
+        &self.inner_csr
+    }
+
+    /// Mutable access to the CSRs main contents. Discards the encoding the CSR has been decoded
+    /// from, so that [IdCsr::signature_data()] and [IdCsr::to_der()] re-encode the, possibly
+    /// modified, contents.
+    pub fn inner_csr_mut(&mut self) -> &mut IdCsrInner<S, P> {
+        self.original_der = OriginalDer::default();
+        &mut self.inner_csr
+    }
+
     /// Create an [IdCsr] from a byte slice containing a DER encoded PKCS #10 CSR.
     /// The resulting `IdCsr` is guaranteed to be well-formed and up to polyproto specification,
     /// if the correct [Target] for the CSRs intended usage context is provided.
@@ -98,16 +148,45 @@ impl<S: Signature, P: PublicKey<S>> IdCsr<S, P> {
         Ok(csr)
     }
 
+    /// Create an [IdCsr] from a byte slice containing a DER encoded PKCS #10 CSR, verifying its
+    /// proof of possession using [IdCsr::verify_signature()] before validating it against the
+    /// polyproto specification. Use this method to parse CSRs received from other parties.
+    ///
+    /// The resulting `IdCsr` is guaranteed to be signed by the private key belonging to its
+    /// subject public key, and to be well-formed and up to polyproto specification, if the correct
+    /// [Target] for the CSRs intended usage context is provided.
+    pub fn from_der_verified(
+        bytes: &[u8],
+        target: Option<Target>,
+    ) -> Result<Self, ConversionError> {
+        let csr = IdCsr::from_der_unchecked(bytes)?;
+        csr.verify_signature()?;
+        csr.validate(target)?;
+        Ok(csr)
+    }
+
     /// Create an unchecked [IdCsr] from a byte slice containing a DER encoded PKCS #10 CSR.
     /// The caller is responsible for verifying the correctness of this `IdCsr` using
     /// the [Constrained] trait before using it.
     pub fn from_der_unchecked(bytes: &[u8]) -> Result<Self, ConversionError> {
-        let csr = IdCsr::try_from(CertReq::from_der(bytes)?)?;
+        let mut csr = IdCsr::try_from(CertReq::from_der(bytes)?)?;
+        csr.original_der = OriginalDer(Some(signed_data_der(bytes)?));
         Ok(csr)
     }
 
     /// Encode this type as DER, returning a byte vector.
+    ///
+    /// If this `IdCsr` has been decoded and [IdCsr::inner_csr_mut()] has not been called since,
+    /// the original encoding of its [IdCsrInner] is kept unchanged, so that the signature over it
+    /// remains verifiable.
     pub fn to_der(self) -> Result<Vec<u8>, ConversionError> {
+        if let Some(original_der) = &self.original_der.0 {
+            return encode_signed(
+                original_der,
+                &self.signature_algorithm,
+                &self.signature.to_bitstring()?,
+            );
+        }
         Ok(CertReq::try_from(self)?.to_der()?)
     }
 
@@ -120,27 +199,106 @@ impl<S: Signature, P: PublicKey<S>> IdCsr<S, P> {
         Ok(csr)
     }
 
+    /// Create an [IdCsr] from a string containing a PEM encoded PKCS #10 CSR, verifying its proof
+    /// of possession using [IdCsr::verify_signature()] before validating it against the polyproto
+    /// specification. Use this method to parse CSRs received from other parties.
+    ///
+    /// The resulting `IdCsr` is guaranteed to be signed by the private key belonging to its
+    /// subject public key, and to be well-formed and up to polyproto specification, if the correct
+    /// [Target] for the CSRs intended usage context is provided.
+    pub fn from_pem_verified(pem: &str, target: Option<Target>) -> Result<Self, ConversionError> {
+        let csr = IdCsr::from_pem_unchecked(pem)?;
+        csr.verify_signature()?;
+        csr.validate(target)?;
+        Ok(csr)
+    }
+
     /// Create an unchecked [IdCsr] from a string containing a PEM encoded PKCS #10 CSR.
     /// The caller is responsible for verifying the correctness of this `IdCsr` using
     /// the [Constrained] trait before using it.
     pub fn from_pem_unchecked(pem: &str) -> Result<Self, ConversionError> {
-        let csr = IdCsr::try_from(CertReq::from_pem(pem)?)?;
-        Ok(csr)
+        IdCsr::from_der_unchecked(&decode_pem::<CertReq>(pem)?)
     }
 
     /// Encode this type as PEM, returning a string.
     pub fn to_pem(self, line_ending: LineEnding) -> Result<String, ConversionError> {
-        Ok(CertReq::try_from(self)?.to_pem(line_ending)?)
+        Ok(
+            der::pem::encode_string(CertReq::PEM_LABEL, line_ending, &self.to_der()?)
+                .map_err(der::Error::from)?,
+        )
+    }
+
+    /// Computes the [Fingerprint] of this certificate signing request, which is the digest of its
+    /// DER encoding. The hash function is determined by `D`.
+    pub fn fingerprint<D: Digest>(&self) -> Result<Fingerprint, ConversionError> {
+        Ok(Fingerprint::new::<D>(&self.clone().to_der()?))
+    }
+
+    /// Verifies the proof of possession of this CSR: Checks, that the [Signature] of the CSR is
+    /// valid under the `subject_public_key` contained in the CSR itself, meaning that the requester
+    /// holds the private key belonging to the public key they want to have certified. The signature
+    /// algorithm of the CSR must be the algorithm of `S`.
+    ///
+    /// Does not check if the CSR is well-formed and up to polyproto specification; use
+    /// [Constrained::validate()] for that.
+    pub fn verify_signature(&self) -> Result<(), InvalidCert> {
+        let signature_data = self.signature_data().map_err(|e| {
+            log::warn!(
+                "[IdCsr::verify_signature()] DER conversion failure when converting inner IdCsr to DER: {}",
+                e
+            );
+            InvalidCert::InvalidProperties(ConstraintError::Malformed(Some(e.to_string())))
+        })?;
+        ensure_algorithm(
+            &self.signature.signature_algorithm(),
+            &self.signature_algorithm,
+        )?;
+        log::trace!("[IdCsr::verify_signature()] verifying proof of possession");
+        Ok(self
+            .inner_csr
+            .subject_public_key
+            .verify_signature(&self.signature, &signature_data)?)
     }
 
     /// Returns a byte vector containing the DER encoded [IdCsrInner]. This data is encoded
     /// in the signature field of the IdCSR, and can be used to verify the signature of the CSR.
     ///
-    /// This is a shorthand for `self.inner_csr.clone().to_der()`, since intuitively, one might
+    /// This is a shorthand for `self.inner_csr().clone().to_der()`, since intuitively, one might
     /// try to verify the signature of the CSR by using `self.to_der()`, which will result
-    /// in an error.
+    /// in an error. For a CSR decoded using one of the `from_der` or `from_pem` methods, these are
+    /// the exact bytes of the `CertificationRequestInfo` found in the input, as long as
+    /// [IdCsr::inner_csr_mut()] has not been called since.
     pub fn signature_data(&self) -> Result<Vec<u8>, ConversionError> {
-        self.inner_csr.clone().to_der()
+        match &self.original_der.0 {
+            Some(original_der) => Ok(original_der.clone()),
+            None => self.inner_csr.clone().to_der(),
+        }
+    }
+
+    /// Returns the [FederationId] of the subject, taken from the "UID" attribute of the subject
+    /// [Name]. Fails, if there is not exactly one UID attribute, or if it is not a valid
+    /// `FederationId`. Home server CSRs do not carry a federation ID.
+    pub fn federation_id(&self) -> Result<FederationId, ConstraintError> {
+        NameComponents::parse(&self.inner_csr.subject).federation_id()
+    }
+
+    /// Returns the [SessionId] of the subject, taken from the "uniqueIdentifier" attribute of the
+    /// subject [Name]. Fails, if there is not exactly one uniqueIdentifier attribute, or if it is
+    /// not a valid `SessionId`. Home server CSRs do not carry a session ID.
+    pub fn session_id(&self) -> Result<SessionId, ConstraintError> {
+        NameComponents::parse(&self.inner_csr.subject).session_id()
+    }
+
+    /// Returns the home server domain of the subject, e.g. `polyphony.chat`, by joining the
+    /// "domain component" attributes of the subject [Name]. Fails, if there are none.
+    pub fn home_server_domain(&self) -> Result<String, ConstraintError> {
+        NameComponents::parse(&self.inner_csr.subject).home_server_domain()
+    }
+
+    /// Returns the "common name" attribute of the subject [Name]. Fails, if there is not exactly
+    /// one common name attribute.
+    pub fn common_name(&self) -> Result<String, ConstraintError> {
+        NameComponents::parse(&self.inner_csr.subject).common_name()
     }
 }
 
@@ -183,24 +341,27 @@ impl<S: Signature, P: PublicKey<S>> IdCsrInner<S, P> {
         capabilities: &Capabilities,
         target: Option<Target>,
     ) -> Result<IdCsrInner<S, P>, ConversionError> {
-        let subject = subject.clone();
-        let subject_public_key_info = public_key.clone();
-        let id_csr_inner = IdCsrInner {
+        let id_csr_inner = IdCsrInner::unsigned(subject, public_key, capabilities);
+        id_csr_inner.validate(target)?;
+        Ok(id_csr_inner)
+    }
+
+    /// Creates a new, unvalidated [IdCsrInner], which is yet to be signed.
+    fn unsigned(subject: &Name, public_key: &P, capabilities: &Capabilities) -> Self {
+        IdCsrInner {
             version: PkcsVersion::V1,
-            subject,
-            subject_public_key: subject_public_key_info,
+            subject: subject.clone(),
+            subject_public_key: public_key.clone(),
             capabilities: capabilities.clone(),
             phantom_data: PhantomData,
-        };
-        id_csr_inner.validate(target)?;
-        Ok(id_csr_inner)
+        }
     }
 
     /// Create an [IdCsrInner] from a byte slice containing a DER encoded PKCS #10 CSR.
     /// The resulting `IdCsrInner` is guaranteed to be well-formed and up to polyproto specification,
     /// if the correct [Target] for the CSRs intended usage context is provided.
     pub fn from_der(bytes: &[u8], target: Option<Target>) -> Result<Self, ConversionError> {
-        let csr_inner = IdCsrInner::try_from(CertReqInfo::from_der(bytes)?)?;
+        let csr_inner = IdCsrInner::from_der_unchecked(bytes)?;
         csr_inner.validate(target)?;
         Ok(csr_inner)
     }
@@ -209,14 +370,28 @@ impl<S: Signature, P: PublicKey<S>> IdCsrInner<S, P> {
     /// The caller is responsible for verifying the correctness of this `IdCsrInner` using
     /// the [Constrained] trait before using it.
     pub fn from_der_unchecked(bytes: &[u8]) -> Result<Self, ConversionError> {
-        let csr_inner = IdCsrInner::try_from(CertReqInfo::from_der(bytes)?)?;
-        Ok(csr_inner)
+        IdCsrInner::try_from(CertReqInfo::from_der(bytes)?)
     }
 
     /// Encode this type as DER, returning a byte vector.
+    ///
+    /// The contents are always re-encoded. To keep the original encoding of a decoded CSR, use
+    /// [IdCsr::signature_data()].
     pub fn to_der(self) -> Result<Vec<u8>, ConversionError> {
         Ok(CertReqInfo::try_from(self)?.to_der()?)
     }
+
+    /// Returns the subject [Name] as an [ActorName]. Fails, if the subject is not a valid actor
+    /// name.
+    pub fn actor_name(&self) -> Result<ActorName, ConstraintError> {
+        ActorName::try_from(self.subject.clone())
+    }
+
+    /// Returns the subject [Name] as a [HomeServerName]. Fails, if the subject is not a valid home
+    /// server name.
+    pub fn home_server_name(&self) -> Result<HomeServerName, ConstraintError> {
+        HomeServerName::try_from(self.subject.clone())
+    }
 }
 
 impl<S: Signature, P: PublicKey<S>> TryFrom<CertReq> for IdCsr<S, P> {
@@ -226,10 +401,16 @@ impl<S: Signature, P: PublicKey<S>> TryFrom<CertReq> for IdCsr<S, P> {
     /// unverified `IdCsr`. If this conversion is called manually, the caller is responsible for
     /// verifying the `IdCsr` using the [Constrained] trait.
     fn try_from(value: CertReq) -> Result<Self, Self::Error> {
+        S::check_algorithm(&value.algorithm)?;
+        // Without access to the bytes `value` has been decoded from, its re-encoding is the
+        // closest available approximation of the original encoding. Decoding methods taking bytes
+        // replace it with the encoding found in their input.
+        let original_der = OriginalDer(value.info.to_der().ok());
         Ok(IdCsr {
             inner_csr: IdCsrInner::try_from(value.info)?,
+            signature: decode_signature(&value.algorithm, &value.signature)?,
             signature_algorithm: value.algorithm,
-            signature: S::from_bytes(value.signature.raw_bytes()),
+            original_der,
         })
     }
 }
@@ -250,7 +431,7 @@ impl<S: Signature, P: PublicKey<S>> TryFrom<CertReqInfo> for IdCsrInner<S, P> {
         Ok(IdCsrInner {
             version: PkcsVersion::V1,
             subject: rdn_sequence,
-            subject_public_key: PublicKey::try_from_public_key_info(public_key_info)?,
+            subject_public_key: decode_public_key(public_key_info)?,
             capabilities: Capabilities::try_from(value.attributes)?,
             phantom_data: PhantomData,
         })
@@ -261,8 +442,12 @@ impl<S: Signature, P: PublicKey<S>> TryFrom<IdCsr<S, P>> for CertReq {
     type Error = ConversionError;
 
     fn try_from(value: IdCsr<S, P>) -> Result<Self, Self::Error> {
+        let info = match value.original_der.0 {
+            Some(original_der) => CertReqInfo::from_der(&original_der)?,
+            None => value.inner_csr.try_into()?,
+        };
         Ok(CertReq {
-            info: value.inner_csr.try_into()?,
+            info,
             algorithm: value.signature_algorithm,
             signature: value.signature.to_bitstring()?,
         })
@@ -6,24 +6,53 @@ use std::ops::{Deref, DerefMut};
 use std::str::FromStr;
 
 use der::asn1::BitString;
-use der::pem::LineEnding;
-use der::{Decode, DecodePem, Encode, EncodePem};
+use der::pem::{LineEnding, PemLabel};
+use der::{Decode, DecodePem, Encode, EncodePem, Header, Length, Reader, SliceReader, Tag};
 use spki::{AlgorithmIdentifierOwned, SubjectPublicKeyInfoOwned};
 use x509_cert::name::{Name, RdnSequence};
 
-use crate::errors::ConversionError;
+use crate::digest::{Digest, Fingerprint};
+use crate::errors::{
+    ConversionError, InvalidCert, InvalidInput, ERR_MSG_SIGNING_KEY_WITHOUT_ALGORITHM,
+};
+use crate::key::PublicKey;
+use crate::signature::Signature;
 use crate::types::der::asn1::Ia5String;
-use crate::{Constrained, ConstraintError, OID_RDN_DOMAIN_COMPONENT};
+use crate::types::FederationId;
+use crate::{
+    Constrained, ConstraintError, OID_RDN_COMMON_NAME, OID_RDN_DOMAIN_COMPONENT, OID_RDN_UID,
+    OID_RDN_UNIQUE_IDENTIFIER,
+};
 
+/// Builders for [IdCsr](idcsr::IdCsr)s and [IdCert]s, deriving subject and issuer names from
+/// typed components
+pub mod builder;
+/// Parsing and writing of PEM bundles, containing multiple [IdCert]s, [IdCsr](idcsr::IdCsr)s and
+/// [PublicKeyInfo]s
+pub mod bundle;
 /// Additional capabilities ([x509_cert::ext::Extensions] or [x509_cert::attr::Attributes], depending
 /// on the context) of X.509 certificates.
 pub mod capabilities;
+/// Verification of [IdCert] chains, consisting of an actor certificate and its issuing home server
+/// certificates
+pub mod chain;
 /// Complete, signed [IdCert]
 pub mod idcert;
 /// [IdCertTbs] is an [IdCert] which has not yet been signed by
 pub mod idcerttbs;
+/// Certificate revocation lists, issued by home servers to revoke [IdCert]s
+pub mod idcrl;
 /// Certificate Signing Request for an [IdCert]/[IdCertTbs]
 pub mod idcsr;
+/// Strongly typed distinguished names of actors and home servers
+pub mod name;
+/// Local validation policies, which home servers can enforce in addition to the polyproto
+/// specification
+pub mod policy;
+/// Validation and allocation of certificate serial numbers
+pub mod serial_number;
+/// Storage of [IdCert]s, indexed for lookups by actor, session, serial number and issuer
+pub mod store;
 
 /// polyproto client Session ID. Must be unique for each client. Must be between 1 and =32
 /// characters in length. The session ID is used to uniquely identify a client in the context of
@@ -111,8 +140,8 @@ pub enum Target {
 #[repr(u8)]
 /// `PKCS#10` version. From the PKCS specification document (RFC 2986):
 /// > version is the version number, for compatibility with future
-/// revisions of this document.  It shall be 0 for this version of
-/// the standard.
+/// > revisions of this document.  It shall be 0 for this version of
+/// > the standard.
 ///
 /// The specification also says:
 /// > `version       INTEGER { v1(0) } (v1,...),`
@@ -159,6 +188,21 @@ impl PublicKeyInfo {
     pub fn to_pem(&self, line_ending: LineEnding) -> Result<String, ConversionError> {
         Ok(SubjectPublicKeyInfoOwned::from(self.clone()).to_pem(line_ending)?)
     }
+
+    /// Computes a key identifier for this public key, suitable for the SubjectKeyIdentifier and
+    /// AuthorityKeyIdentifier extensions. Following method (1) of
+    /// [RFC 5280, Section 4.2.1.2](https://datatracker.ietf.org/doc/html/rfc5280#section-4.2.1.2),
+    /// the key identifier is the digest of the value of the public key bit string, excluding the
+    /// tag, length and number of unused bits. The hash function is determined by `D`.
+    pub fn key_identifier<D: Digest>(&self) -> Vec<u8> {
+        D::digest(self.public_key_bitstring.raw_bytes())
+    }
+
+    /// Computes the [Fingerprint] of this public key, which is the digest of its DER encoded
+    /// `SubjectPublicKeyInfo` structure. The hash function is determined by `D`.
+    pub fn fingerprint<D: Digest>(&self) -> Result<Fingerprint, ConversionError> {
+        Ok(Fingerprint::new::<D>(&self.to_der()?))
+    }
 }
 
 impl From<SubjectPublicKeyInfoOwned> for PublicKeyInfo {
@@ -179,26 +223,209 @@ impl From<PublicKeyInfo> for SubjectPublicKeyInfoOwned {
     }
 }
 
+#[derive(Debug, Clone, Default)]
+/// The exact DER encoding the signed part of an [IdCert](idcert::IdCert), [IdCsr](idcsr::IdCsr)
+/// or [IdCrl](idcrl::IdCrl) has been decoded from, as it has been found in the input. It is
+/// discarded as soon as the signed part is borrowed mutably.
+///
+/// Signatures are computed over the original encoding, which may differ from the encoding this
+/// crate produces for the decoded contents, e.g. if a different string type has been used in a
+/// [Name], or if the elements of a `SET OF` have not been sorted. As [der] normalizes the latter
+/// while decoding, the original encoding must be taken from the input bytes; see
+/// [signed_data_der()]. `OriginalDer` is ignored when comparing structures, so that decoded and
+/// constructed structures with equal contents compare equal.
+pub(crate) struct OriginalDer(pub(crate) Option<Vec<u8>>);
+
+impl PartialEq for OriginalDer {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for OriginalDer {}
+
+/// Returns the first field of the DER encoded `SEQUENCE` in `der`, exactly as it is encoded in
+/// `der`. For a certificate, CSR or CRL, this is the signed part: the `tbsCertificate`,
+/// `certificationRequestInfo` or `tbsCertList` respectively.
+pub(crate) fn signed_data_der(der: &[u8]) -> Result<Vec<u8>, ConversionError> {
+    let mut reader = SliceReader::new(der)?;
+    let header = Header::decode(&mut reader)?;
+    header.tag.assert_eq(Tag::Sequence)?;
+    Ok(reader.tlv_bytes()?.to_vec())
+}
+
+/// Encodes a signed structure, i.e. a certificate, CSR or CRL, as a `SEQUENCE` of the already DER
+/// encoded `signed_data`, the signature `algorithm` and the `signature`. Unlike encoding the
+/// corresponding [x509_cert] type, this keeps `signed_data` byte for byte.
+pub(crate) fn encode_signed(
+    signed_data: &[u8],
+    algorithm: &AlgorithmIdentifierOwned,
+    signature: &BitString,
+) -> Result<Vec<u8>, ConversionError> {
+    let algorithm = algorithm.to_der()?;
+    let signature = signature.to_der()?;
+    let length = Length::try_from(signed_data.len() + algorithm.len() + signature.len())?;
+    let mut der = Header::new(Tag::Sequence, length)?.to_der()?;
+    der.extend_from_slice(signed_data);
+    der.extend_from_slice(&algorithm);
+    der.extend_from_slice(&signature);
+    Ok(der)
+}
+
+/// Decodes the PEM document `pem`, checking that its label is the PEM label of `T`.
+pub(crate) fn decode_pem<T: PemLabel>(pem: &str) -> Result<Vec<u8>, ConversionError> {
+    let (label, der) = der::pem::decode_vec(pem.as_bytes()).map_err(der::Error::from)?;
+    T::validate_pem_label(label).map_err(der::Error::from)?;
+    Ok(der)
+}
+
+/// Returns the signature `algorithm` declared by a signing key, see
+/// [PrivateKey::algorithm_identifier()](crate::key::PrivateKey::algorithm_identifier()). Fails, if
+/// the key does not declare the algorithm of its signatures, as certificates and CRLs cannot be
+/// issued without it.
+pub(crate) fn signing_algorithm(
+    algorithm: Option<AlgorithmIdentifierOwned>,
+) -> Result<AlgorithmIdentifierOwned, ConversionError> {
+    algorithm.ok_or_else(|| {
+        log::warn!(
+            "[signing_algorithm()] {}",
+            ERR_MSG_SIGNING_KEY_WITHOUT_ALGORITHM
+        );
+        InvalidInput::Malformed(ERR_MSG_SIGNING_KEY_WITHOUT_ALGORITHM.to_string()).into()
+    })
+}
+
+/// Checks, that the algorithm identifier `found` in a certificate, CSR or CRL equals the `expected`
+/// one. Differing algorithm identifiers would allow algorithm confusion attacks, where a signature
+/// or key is interpreted using a different algorithm than the one it has been created for.
+pub(crate) fn ensure_algorithm(
+    expected: &AlgorithmIdentifierOwned,
+    found: &AlgorithmIdentifierOwned,
+) -> Result<(), InvalidCert> {
+    if expected == found {
+        return Ok(());
+    }
+    log::warn!(
+        "[ensure_algorithm()] Expected algorithm {}, found algorithm {}",
+        expected.oid,
+        found.oid
+    );
+    Err(InvalidCert::AlgorithmMismatch {
+        expected: expected.oid,
+        found: found.oid,
+    })
+}
+
+/// Decodes a subject public key, ensuring that the decoded key uses the algorithm declared in
+/// `public_key_info`.
+pub(crate) fn decode_public_key<S: Signature, P: PublicKey<S>>(
+    public_key_info: PublicKeyInfo,
+) -> Result<P, ConversionError> {
+    let declared = public_key_info.algorithm.clone();
+    let public_key = P::try_from_public_key_info(public_key_info)?;
+    ensure_algorithm(&public_key.public_key_info().algorithm, &declared)?;
+    Ok(public_key)
+}
+
+/// Decodes a [Signature] created with `algorithm` from the BIT STRING it is encoded in. The BIT
+/// STRING must consist of whole octets, which must exactly encode a signature of type `S`.
+pub(crate) fn decode_signature<S: Signature>(
+    algorithm: &AlgorithmIdentifierOwned,
+    signature: &BitString,
+) -> Result<S, ConversionError> {
+    let bytes = signature.as_bytes().ok_or_else(|| {
+        InvalidInput::Malformed("The signature is not a whole number of octets".to_string())
+    })?;
+    S::try_from_algorithm_and_bytes(algorithm, bytes)
+}
+
 /// Checks, if the domain components of two [Name]s are equal and ordered in the same way. Returns
 /// `true`, if the domain components are equal, `false` otherwise.
 pub fn equal_domain_components(name_1: &Name, name_2: &Name) -> bool {
-    let mut domain_components_1 = Vec::new();
-    let mut domain_components_2 = Vec::new();
-    for rdn in name_1.0.iter() {
-        for ava in rdn.0.iter() {
-            if ava.oid.to_string().as_str() == OID_RDN_DOMAIN_COMPONENT {
-                domain_components_1.push(String::from_utf8_lossy(ava.value.value()));
+    NameComponents::parse(name_1).domain_components
+        == NameComponents::parse(name_2).domain_components
+}
+
+/// The polyproto-relevant attributes of a [Name], as found by walking its RDN sequence. This is
+/// the single place where the attributes of a `Name` are extracted; both the [Constrained]
+/// implementation for `Name` and the identity accessors of [IdCert], [IdCertTbs] and
+/// [IdCsr](idcsr::IdCsr) are built upon it.
+///
+/// All attributes are collected, even if they occur more often than allowed by the polyproto
+/// specification, so that validation can report them.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub(crate) struct NameComponents {
+    /// Values of all "common name" attributes
+    pub(crate) common_names: Vec<String>,
+    /// Values of all "domain component" attributes, ordered as in the string representation of
+    /// the `Name`, i.e. the most specific label first
+    pub(crate) domain_components: Vec<String>,
+    /// Values of all "UID" attributes
+    pub(crate) uids: Vec<String>,
+    /// Values of all "uniqueIdentifier" attributes
+    pub(crate) unique_identifiers: Vec<String>,
+}
+
+impl NameComponents {
+    /// Walks the RDN sequence of `name`, collecting all polyproto-relevant attributes.
+    pub(crate) fn parse(name: &Name) -> Self {
+        let mut components = NameComponents::default();
+        // The order of the RDNs in the [Name] object is reversed, compared to the order of the
+        // RDNs in its string representation.
+        for rdn in name.0.iter().rev() {
+            for item in rdn.0.iter() {
+                let value = String::from_utf8_lossy(item.value.value()).to_string();
+                match item.oid.to_string().as_str() {
+                    OID_RDN_COMMON_NAME => components.common_names.push(value),
+                    OID_RDN_DOMAIN_COMPONENT => components.domain_components.push(value),
+                    OID_RDN_UID => components.uids.push(value),
+                    OID_RDN_UNIQUE_IDENTIFIER => components.unique_identifiers.push(value),
+                    _ => log::trace!(
+                        "[NameComponents::parse()] Found unknown/non-validated component in RDN: {}",
+                        item
+                    ),
+                }
             }
         }
+        components
     }
-    for rdn in name_2.0.iter() {
-        for ava in rdn.0.iter() {
-            if ava.oid.to_string().as_str() == OID_RDN_DOMAIN_COMPONENT {
-                domain_components_2.push(String::from_utf8_lossy(ava.value.value()));
-            }
+
+    /// Returns the home server domain, consisting of the domain components joined by `.`.
+    pub(crate) fn home_server_domain(&self) -> Result<String, ConstraintError> {
+        if self.domain_components.is_empty() {
+            return Err(ConstraintError::Malformed(Some(
+                "Domain Component is missing in Name component".to_string(),
+            )));
         }
+        Ok(self.domain_components.join("."))
+    }
+
+    /// Returns the single "common name" attribute.
+    pub(crate) fn common_name(&self) -> Result<String, ConstraintError> {
+        exactly_one(&self.common_names, "Common Name")
+    }
+
+    /// Returns the single "UID" attribute as a [FederationId].
+    pub(crate) fn federation_id(&self) -> Result<FederationId, ConstraintError> {
+        FederationId::new(&exactly_one(&self.uids, "UID")?)
+    }
+
+    /// Returns the single "uniqueIdentifier" attribute as a [SessionId].
+    pub(crate) fn session_id(&self) -> Result<SessionId, ConstraintError> {
+        SessionId::new_validated(&exactly_one(&self.unique_identifiers, "uniqueIdentifier")?)
+    }
+}
+
+fn exactly_one(values: &[String], attribute: &str) -> Result<String, ConstraintError> {
+    match values {
+        [value] => Ok(value.clone()),
+        _ => Err(ConstraintError::OutOfBounds {
+            lower: 1,
+            upper: 1,
+            actual: values.len().to_string(),
+            reason: format!("Expected exactly one {} component", attribute),
+        }),
     }
-    domain_components_1 == domain_components_2
 }
 
 #[cfg(test)]
@@ -241,4 +468,24 @@ mod test {
         .unwrap();
         assert!(!equal_domain_components(&rdn_1, &rdn_2));
     }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn name_components_parse() {
+        #[allow(clippy::unwrap_used)]
+        let name = RdnSequence::from_str(
+            "CN=root,OU=programmer,DC=www,DC=polyphony,DC=chat,UID=root@www.polyphony.chat,uniqueIdentifier=root",
+        )
+        .unwrap();
+        let components = NameComponents::parse(&name);
+        assert_eq!(components.common_names, ["root"]);
+        assert_eq!(components.domain_components, ["www", "polyphony", "chat"]);
+        assert_eq!(components.uids, ["root@www.polyphony.chat"]);
+        assert_eq!(components.unique_identifiers, ["root"]);
+        assert_eq!(
+            components.home_server_domain().unwrap(),
+            "www.polyphony.chat"
+        );
+        assert_eq!(components.session_id().unwrap().to_string(), "root");
+    }
 }
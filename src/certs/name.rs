@@ -0,0 +1,222 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use der::asn1::SetOfVec;
+use der::{Any, Tag};
+use spki::ObjectIdentifier;
+use x509_cert::attr::AttributeTypeAndValue;
+use x509_cert::name::{Name, RdnSequence, RelativeDistinguishedName};
+
+use crate::errors::ConstraintError;
+use crate::types::FederationId;
+use crate::{
+    Constrained, OID_RDN_COMMON_NAME, OID_RDN_DOMAIN_COMPONENT, OID_RDN_UID,
+    OID_RDN_UNIQUE_IDENTIFIER,
+};
+
+use super::{NameComponents, SessionId, Target};
+
+/// The distinguished name of an actor, as found in the subject of actor [IdCsr](super::idcsr::IdCsr)s
+/// and [IdCert](super::idcert::IdCert)s. An `ActorName` always passes [Constrained] validation for
+/// [Target::Actor], and always carries exactly one [FederationId] and one [SessionId].
+///
+/// Converting a [Name] into an `ActorName` and back is lossless: attributes which are not relevant
+/// to polyproto, as well as the string types of all attributes, are retained.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActorName {
+    name: Name,
+    federation_id: FederationId,
+    session_id: SessionId,
+}
+
+impl ActorName {
+    /// Creates the [ActorName] of the given session of an actor. The "common name" attribute is the
+    /// local name of the [FederationId], and the "domain component" attributes are derived from
+    /// its domain, as required by the polyproto specification.
+    ///
+    /// Fails, if the domain of the federation ID is not a valid home server domain.
+    pub fn new(
+        federation_id: &FederationId,
+        session_id: &SessionId,
+    ) -> Result<Self, ConstraintError> {
+        // A valid FederationId always contains exactly one @
+        let (local_name, domain) = federation_id.split_once('@').ok_or_else(|| {
+            ConstraintError::Malformed(Some(format!(
+                "\"{}\" is not a valid federation ID",
+                federation_id
+            )))
+        })?;
+        let mut rdns = vec![rdn(OID_RDN_COMMON_NAME, Tag::Utf8String, local_name)?];
+        rdns.extend(domain_components(domain)?);
+        rdns.push(rdn(OID_RDN_UID, Tag::Utf8String, federation_id)?);
+        rdns.push(rdn(
+            OID_RDN_UNIQUE_IDENTIFIER,
+            Tag::Utf8String,
+            session_id.as_str(),
+        )?);
+        ActorName::try_from(to_rdn_sequence(rdns))
+    }
+
+    /// The [FederationId] of the actor, taken from the "UID" attribute.
+    pub fn federation_id(&self) -> &FederationId {
+        &self.federation_id
+    }
+
+    /// The [SessionId] of the actor, taken from the "uniqueIdentifier" attribute.
+    pub fn session_id(&self) -> &SessionId {
+        &self.session_id
+    }
+
+    /// The home server domain of the actor, e.g. `polyphony.chat`.
+    pub fn home_server_domain(&self) -> String {
+        NameComponents::parse(&self.name)
+            .domain_components
+            .join(".")
+    }
+
+    /// The underlying [Name].
+    pub fn as_name(&self) -> &Name {
+        &self.name
+    }
+}
+
+impl TryFrom<Name> for ActorName {
+    type Error = ConstraintError;
+
+    /// Performs the conversion. Fails, if `value` does not pass [Constrained] validation for
+    /// [Target::Actor], or does not contain exactly one "UID" and one "uniqueIdentifier"
+    /// attribute.
+    fn try_from(value: Name) -> Result<Self, Self::Error> {
+        value.validate(Some(Target::Actor))?;
+        let components = NameComponents::parse(&value);
+        Ok(Self {
+            federation_id: components.federation_id()?,
+            session_id: components.session_id()?,
+            name: value,
+        })
+    }
+}
+
+impl From<ActorName> for Name {
+    fn from(value: ActorName) -> Self {
+        value.name
+    }
+}
+
+impl AsRef<Name> for ActorName {
+    fn as_ref(&self) -> &Name {
+        &self.name
+    }
+}
+
+impl Display for ActorName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.name.fmt(f)
+    }
+}
+
+/// The distinguished name of a home server, as found in the subject of home server
+/// [IdCert](super::idcert::IdCert)s and in the issuer of all `IdCert`s. A `HomeServerName` always
+/// passes [Constrained] validation for [Target::HomeServer].
+///
+/// Converting a [Name] into a `HomeServerName` and back is lossless: attributes which are not
+/// relevant to polyproto, as well as the string types of all attributes, are retained.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HomeServerName {
+    name: Name,
+}
+
+impl HomeServerName {
+    /// Creates the [HomeServerName] of the home server with the given domain, e.g.
+    /// `polyphony.chat`. The name consists of one "domain component" attribute per label of the
+    /// domain.
+    ///
+    /// Fails, if `domain` is not a valid domain name consisting of lowercase labels.
+    pub fn new(domain: &str) -> Result<Self, ConstraintError> {
+        HomeServerName::try_from(to_rdn_sequence(domain_components(domain)?))
+    }
+
+    /// The domain of the home server, e.g. `polyphony.chat`.
+    pub fn domain(&self) -> String {
+        NameComponents::parse(&self.name)
+            .domain_components
+            .join(".")
+    }
+
+    /// The underlying [Name].
+    pub fn as_name(&self) -> &Name {
+        &self.name
+    }
+}
+
+impl TryFrom<Name> for HomeServerName {
+    type Error = ConstraintError;
+
+    /// Performs the conversion. Fails, if `value` does not pass [Constrained] validation for
+    /// [Target::HomeServer].
+    fn try_from(value: Name) -> Result<Self, Self::Error> {
+        value.validate(Some(Target::HomeServer))?;
+        Ok(Self { name: value })
+    }
+}
+
+impl From<HomeServerName> for Name {
+    fn from(value: HomeServerName) -> Self {
+        value.name
+    }
+}
+
+impl AsRef<Name> for HomeServerName {
+    fn as_ref(&self) -> &Name {
+        &self.name
+    }
+}
+
+impl Display for HomeServerName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.name.fmt(f)
+    }
+}
+
+/// Splits a home server domain into its domain component RDNs, most specific label first.
+fn domain_components(domain: &str) -> Result<Vec<RelativeDistinguishedName>, ConstraintError> {
+    let is_valid_label = |label: &str| {
+        !label.is_empty()
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    };
+    if !domain.split('.').all(is_valid_label) {
+        return Err(ConstraintError::Malformed(Some(format!(
+            "\"{}\" is not a valid home server domain",
+            domain
+        ))));
+    }
+    domain
+        .split('.')
+        .map(|label| rdn(OID_RDN_DOMAIN_COMPONENT, Tag::Ia5String, label))
+        .collect()
+}
+
+fn rdn(oid: &str, tag: Tag, value: &str) -> Result<RelativeDistinguishedName, ConstraintError> {
+    let malformed = |e: &dyn Display| ConstraintError::Malformed(Some(e.to_string()));
+    let attribute = AttributeTypeAndValue {
+        oid: ObjectIdentifier::from_str(oid).map_err(|e| malformed(&e))?,
+        value: Any::new(tag, value.as_bytes()).map_err(|e| malformed(&e))?,
+    };
+    let set = SetOfVec::try_from(vec![attribute]).map_err(|e| malformed(&e))?;
+    Ok(RelativeDistinguishedName(set))
+}
+
+/// Converts RDNs given in string representation order (most specific first) into an
+/// [RdnSequence], which stores them in reverse order.
+fn to_rdn_sequence(mut rdns: Vec<RelativeDistinguishedName>) -> Name {
+    rdns.reverse();
+    RdnSequence(rdns)
+}
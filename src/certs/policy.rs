@@ -0,0 +1,272 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::time::Duration;
+
+use spki::{AlgorithmIdentifierOwned, ObjectIdentifier};
+use x509_cert::name::Name;
+use x509_cert::time::Validity;
+
+use crate::errors::{ConstraintError, ConstraintViolation, ViolationCode};
+use crate::{
+    OID_RDN_COMMON_NAME, OID_RDN_DOMAIN_COMPONENT, OID_RDN_UID, OID_RDN_UNIQUE_IDENTIFIER,
+};
+
+use super::capabilities::{Capabilities, ExtendedKeyUsage, KeyUsage};
+use super::{NameComponents, Target};
+
+/// The OID for the `organizationalUnitName` RDN, which the polyproto specification permits in
+/// distinguished names
+const OID_RDN_ORGANIZATIONAL_UNIT: &str = "2.5.4.11";
+
+/// Maximum certificate lifetime of [ValidationPolicy::strict()]: 365 days
+const STRICT_MAX_LIFETIME: Duration = Duration::from_secs(60 * 60 * 24 * 365);
+/// Maximum number of domain components of [ValidationPolicy::strict()]
+const STRICT_MAX_DOMAIN_COMPONENTS: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Local rules which [IdCert](super::idcert::IdCert)s, [IdCsr](super::idcsr::IdCsr)s and their
+/// components must meet in addition to the polyproto specification. Home servers can use a
+/// `ValidationPolicy` to enforce their own issuance policy, by passing it to
+/// [Constrained::validate_with_policy()](crate::Constrained::validate_with_policy()),
+/// [IdCert::full_verify_actor_with_policy()](super::idcert::IdCert::full_verify_actor_with_policy())
+/// or [IdCertBuilder::policy()](super::builder::IdCertBuilder::policy()).
+///
+/// Violations of a policy are reported the same way as violations of the specification, see
+/// [Constrained::validate_all()](crate::Constrained::validate_all()).
+///
+/// ## Presets
+///
+/// - [ValidationPolicy::spec_default()], also returned by [Default::default()], adds no rules on
+///   top of the specification.
+/// - [ValidationPolicy::strict()] enables all rules, with conservative limits.
+///
+/// To create a custom policy, start from a preset and override single fields using struct update
+/// syntax, e.g. `ValidationPolicy { max_lifetime: None, ..ValidationPolicy::strict() }`.
+pub struct ValidationPolicy {
+    /// The maximum duration between the start and the end of the validity period of a
+    /// certificate. Applies to actor and home server certificates alike.
+    pub max_lifetime: Option<Duration>,
+    /// Whether non-CA [Capabilities] must contain the "ContentCommitment" key usage, which
+    /// excludes the "DigitalSignature" key usage.
+    pub require_content_commitment: bool,
+    /// The maximum number of "domain component" attributes of a distinguished name.
+    pub max_domain_components: Option<usize>,
+    /// Whether distinguished names must only contain the attributes described by the polyproto
+    /// specification: "common name", "domain component", "UID", "uniqueIdentifier" and
+    /// "organizational unit".
+    pub forbid_unknown_attributes: bool,
+    /// The OIDs of the signature and public key algorithms which are accepted. If set, the
+    /// signature algorithm of certificates and CSRs, as well as the algorithm of their subject
+    /// public key, must be contained in this list. For algorithms such as ECDSA, which use
+    /// different OIDs for signatures and public keys, both OIDs must be listed.
+    ///
+    /// Like all rules of a `ValidationPolicy`, the allowlist is only enforced by the methods
+    /// taking a policy, such as [Constrained::validate_with_policy()](crate::Constrained::validate_with_policy())
+    /// and [IdCert::full_verify_actor_with_policy()](super::idcert::IdCert::full_verify_actor_with_policy()),
+    /// and by [IdCertBuilder](super::builder::IdCertBuilder)s. Decoding and verifying
+    /// certificates and CSRs with [IdCert::from_der()](super::idcert::IdCert::from_der()) or
+    /// [IdCert::full_verify_actor()](super::idcert::IdCert::full_verify_actor()) accepts any
+    /// algorithm of the [Signature](crate::signature::Signature) type in use.
+    pub allowed_algorithms: Option<Vec<ObjectIdentifier>>,
+    /// The [ExtendedKeyUsage] identifying the purpose of actor certificates, which is signing
+    /// messages. Actor certificates restricting their purposes using the extended key usage
+    /// extension must contain it. If `None`, any extended key usages are accepted.
+    pub message_signing_purpose: Option<ExtendedKeyUsage>,
+    /// The [ExtendedKeyUsage] identifying the purpose of home server certificates, which is
+    /// authenticating the home server to actors and other home servers. Home server certificates
+    /// restricting their purposes using the extended key usage extension must contain it. If
+    /// `None`, any extended key usages are accepted.
+    pub server_auth_purpose: Option<ExtendedKeyUsage>,
+}
+
+impl Default for ValidationPolicy {
+    fn default() -> Self {
+        Self::spec_default()
+    }
+}
+
+impl ValidationPolicy {
+    /// A policy which adds no rules on top of the polyproto specification. As the specification
+    /// does not define extended key usage purposes, none are required.
+    pub const fn spec_default() -> Self {
+        Self {
+            max_lifetime: None,
+            require_content_commitment: false,
+            max_domain_components: None,
+            forbid_unknown_attributes: false,
+            allowed_algorithms: None,
+            message_signing_purpose: None,
+            server_auth_purpose: None,
+        }
+    }
+
+    /// A policy enabling all rules: Certificates must not be valid for longer than 365 days,
+    /// actors must use the "ContentCommitment" key usage, names must not have more than 5 domain
+    /// components and must not contain attributes unknown to the polyproto specification.
+    ///
+    /// Which algorithms are accepted depends on the signature algorithms supported by the
+    /// verifier, and which extended key usage purposes identify actors and home servers depends on
+    /// the deployment, so neither `allowed_algorithms` nor the purposes are set by this preset.
+    pub const fn strict() -> Self {
+        Self {
+            max_lifetime: Some(STRICT_MAX_LIFETIME),
+            require_content_commitment: true,
+            max_domain_components: Some(STRICT_MAX_DOMAIN_COMPONENTS),
+            forbid_unknown_attributes: true,
+            allowed_algorithms: None,
+            message_signing_purpose: None,
+            server_auth_purpose: None,
+        }
+    }
+
+    /// The [ExtendedKeyUsage] which certificates for the given [Target] must carry, if they
+    /// restrict their purposes using the extended key usage extension at all: the
+    /// `message_signing_purpose` for actors and the `server_auth_purpose` for home servers.
+    pub fn required_purpose(&self, target: Target) -> Option<ExtendedKeyUsage> {
+        match target {
+            Target::Actor => self.message_signing_purpose,
+            Target::HomeServer => self.server_auth_purpose,
+        }
+    }
+
+    /// Whether the algorithm with the given OID is accepted by this policy. All algorithms are
+    /// accepted, if `allowed_algorithms` is not set.
+    pub fn permits_algorithm(&self, oid: &ObjectIdentifier) -> bool {
+        match &self.allowed_algorithms {
+            Some(allowed_algorithms) => allowed_algorithms.contains(oid),
+            None => true,
+        }
+    }
+
+    /// Checks the signature or public key `algorithm` of the given `component` against the
+    /// algorithm allowlist of this policy.
+    pub(crate) fn algorithm_violations(
+        &self,
+        component: &str,
+        algorithm: &AlgorithmIdentifierOwned,
+    ) -> Vec<ConstraintViolation> {
+        if self.permits_algorithm(&algorithm.oid) {
+            return Vec::new();
+        }
+        log::debug!(
+            "[ValidationPolicy::algorithm_violations()] Algorithm {} of {} is not permitted",
+            algorithm.oid,
+            component
+        );
+        vec![ConstraintViolation::new(
+            ViolationCode::AlgorithmNotAllowed,
+            component,
+            ConstraintError::Malformed(Some(format!(
+                "Algorithm {} is not accepted",
+                algorithm.oid
+            ))),
+        )]
+    }
+
+    /// Checks the rules of this policy concerning distinguished names. Offending attributes
+    /// unknown to the specification are reported using their OID as component.
+    pub(crate) fn name_violations(&self, name: &Name) -> Vec<ConstraintViolation> {
+        let mut violations = Vec::new();
+        if let Some(max_domain_components) = self.max_domain_components {
+            let num_dc = NameComponents::parse(name).domain_components.len();
+            if num_dc > max_domain_components {
+                violations.push(ConstraintViolation::new(
+                    ViolationCode::TooManyDomainComponents,
+                    "dc",
+                    ConstraintError::OutOfBounds {
+                        lower: 1,
+                        upper: max_domain_components as i32,
+                        actual: num_dc.to_string(),
+                        reason: format!(
+                            "Names must not contain more than {} Domain Components",
+                            max_domain_components
+                        ),
+                    },
+                ));
+            }
+        }
+        if self.forbid_unknown_attributes {
+            let known = [
+                OID_RDN_COMMON_NAME,
+                OID_RDN_DOMAIN_COMPONENT,
+                OID_RDN_UID,
+                OID_RDN_UNIQUE_IDENTIFIER,
+                OID_RDN_ORGANIZATIONAL_UNIT,
+            ];
+            for attribute in name.0.iter().flat_map(|rdn| rdn.0.iter()) {
+                let oid = attribute.oid.to_string();
+                if !known.contains(&oid.as_str()) {
+                    log::debug!(
+                        "[ValidationPolicy::name_violations()] Attribute with OID {} is not permitted",
+                        oid
+                    );
+                    violations.push(ConstraintViolation::new(
+                        ViolationCode::UnexpectedAttribute,
+                        &oid,
+                        ConstraintError::Malformed(Some(format!(
+                            "Attribute with OID {} is not described by the polyproto specification",
+                            oid
+                        ))),
+                    ));
+                }
+            }
+        }
+        violations
+    }
+
+    /// Checks the rules of this policy concerning [Capabilities]. Offending components are named
+    /// like in [Constrained::validate_all()](crate::Constrained::validate_all()) for
+    /// [Capabilities].
+    pub(crate) fn capabilities_violations(
+        &self,
+        capabilities: &Capabilities,
+    ) -> Vec<ConstraintViolation> {
+        let mut violations = Vec::new();
+        if self.require_content_commitment
+            && !capabilities.basic_constraints.ca
+            && !capabilities
+                .key_usage
+                .key_usages
+                .contains(&KeyUsage::ContentCommitment)
+        {
+            violations.push(ConstraintViolation::new(
+                ViolationCode::ContentCommitmentRequired,
+                "key_usage",
+                ConstraintError::Malformed(Some(
+                    "Actors must use the \"ContentCommitment\" capability".to_string(),
+                )),
+            ));
+        }
+        violations
+    }
+
+    /// Checks the rules of this policy concerning validity periods, reporting offending periods
+    /// as `validity`.
+    pub(crate) fn validity_violations(&self, validity: &Validity) -> Vec<ConstraintViolation> {
+        let mut violations = Vec::new();
+        if let Some(max_lifetime) = self.max_lifetime {
+            let lifetime = validity
+                .not_after
+                .to_unix_duration()
+                .saturating_sub(validity.not_before.to_unix_duration());
+            if lifetime > max_lifetime {
+                violations.push(ConstraintViolation::new(
+                    ViolationCode::LifetimeExceeded,
+                    "validity",
+                    ConstraintError::OutOfBounds {
+                        lower: 0,
+                        upper: i32::try_from(max_lifetime.as_secs()).unwrap_or(i32::MAX),
+                        actual: lifetime.as_secs().to_string(),
+                        reason: format!(
+                            "Certificates must not be valid for longer than {} seconds",
+                            max_lifetime.as_secs()
+                        ),
+                    },
+                ));
+            }
+        }
+        violations
+    }
+}
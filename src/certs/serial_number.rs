@@ -0,0 +1,216 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use der::asn1::Uint;
+use der::EncodeValue;
+use x509_cert::certificate::Profile;
+use x509_cert::serial_number::SerialNumber;
+
+use crate::errors::{
+    ConstraintError, ConversionError, SerialNumberError, ERR_MSG_SERIAL_NUMBER_LENGTH,
+    ERR_MSG_SERIAL_NUMBER_NOT_POSITIVE,
+};
+
+/// The maximum length of the encoded value of a certificate serial number, in octets, as specified
+/// in [RFC 5280, Section 4.1.2.2](https://datatracker.ietf.org/doc/html/rfc5280#section-4.1.2.2).
+pub const MAX_SERIAL_NUMBER_LENGTH: usize = 20;
+
+/// Checks, if `serial_number` conforms to
+/// [RFC 5280, Section 4.1.2.2](https://datatracker.ietf.org/doc/html/rfc5280#section-4.1.2.2):
+/// The serial number must be a positive integer, and its DER encoded value must not be longer than
+/// [MAX_SERIAL_NUMBER_LENGTH] octets.
+pub fn validate_serial_number(serial_number: &Uint) -> Result<(), ConstraintError> {
+    if serial_number.as_bytes().iter().all(|byte| *byte == 0) {
+        return Err(ConstraintError::Malformed(Some(
+            ERR_MSG_SERIAL_NUMBER_NOT_POSITIVE.to_string(),
+        )));
+    }
+    let length = serial_number
+        .value_len()
+        .map(|length| u32::from(length) as usize)
+        .map_err(|e| ConstraintError::Malformed(Some(e.to_string())))?;
+    if length > MAX_SERIAL_NUMBER_LENGTH {
+        return Err(ConstraintError::OutOfBounds {
+            lower: 1,
+            upper: MAX_SERIAL_NUMBER_LENGTH as i32,
+            actual: length.to_string(),
+            reason: ERR_MSG_SERIAL_NUMBER_LENGTH.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Converts the decoded `serial_number` of a certificate or CRL entry to a [Uint]. Fails, if the
+/// serial number is negative, which would otherwise be reinterpreted as the positive integer of
+/// its two's complement octets.
+pub(crate) fn decode_serial_number<P: Profile>(
+    serial_number: &SerialNumber<P>,
+) -> Result<Uint, ConversionError> {
+    let bytes = serial_number.as_bytes();
+    if bytes.first().is_some_and(|byte| byte & 0x80 != 0) {
+        return Err(ConstraintError::Malformed(Some(
+            ERR_MSG_SERIAL_NUMBER_NOT_POSITIVE.to_string(),
+        ))
+        .into());
+    }
+    Ok(Uint::new(bytes)?)
+}
+
+/// A strategy for allocating serial numbers to newly issued [IdCert](super::idcert::IdCert)s.
+///
+/// Serial numbers must be unique for each certificate issued by a home server. Implementations
+/// must only return serial numbers which pass [validate_serial_number()].
+pub trait SerialNumberGenerator {
+    /// Allocates the serial number for the next certificate to be issued.
+    fn next_serial_number(&mut self) -> Result<Uint, SerialNumberError>;
+}
+
+#[cfg(feature = "getrandom")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// A [SerialNumberGenerator] producing random serial numbers with 127 bits of entropy, using the
+/// cryptographically secure random number generator of the operating system.
+///
+/// Serial numbers are limited to 16 octets, so that they can be represented as a [u128], as is
+/// required by [SerialNumber::try_as_u128()](crate::types::x509_cert::SerialNumber::try_as_u128()).
+pub struct RandomSerialNumberGenerator;
+
+#[cfg(feature = "getrandom")]
+impl RandomSerialNumberGenerator {
+    /// Creates a new [RandomSerialNumberGenerator].
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[cfg(feature = "getrandom")]
+impl SerialNumberGenerator for RandomSerialNumberGenerator {
+    fn next_serial_number(&mut self) -> Result<Uint, SerialNumberError> {
+        loop {
+            let mut bytes = [0u8; 16];
+            getrandom::getrandom(&mut bytes)
+                .map_err(|e| SerialNumberError::Randomness(e.to_string()))?;
+            // Clearing the most significant bit keeps the value within 16 octets, as DER would
+            // otherwise prepend a zero octet to mark the integer as positive.
+            bytes[0] &= 0x7f;
+            if bytes.iter().any(|byte| *byte != 0) {
+                return Ok(Uint::new(&bytes)?);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// A [SerialNumberGenerator] counting upwards from the last issued serial number. The state of the
+/// counter can be persisted using [MonotonicSerialNumberGenerator::last_issued()], and restored
+/// using [MonotonicSerialNumberGenerator::new()], so that no serial number is issued twice across
+/// restarts.
+pub struct MonotonicSerialNumberGenerator {
+    last_issued: u128,
+}
+
+impl MonotonicSerialNumberGenerator {
+    /// Creates a new [MonotonicSerialNumberGenerator], which continues counting after the serial
+    /// number `last_issued`. Pass `0` if no certificate has been issued yet; the first serial number
+    /// will then be `1`.
+    pub fn new(last_issued: u128) -> Self {
+        Self { last_issued }
+    }
+
+    /// The serial number which has been issued last, or `0` if none has been issued yet. Persist
+    /// this value after every call to [SerialNumberGenerator::next_serial_number()].
+    pub fn last_issued(&self) -> u128 {
+        self.last_issued
+    }
+}
+
+impl SerialNumberGenerator for MonotonicSerialNumberGenerator {
+    fn next_serial_number(&mut self) -> Result<Uint, SerialNumberError> {
+        let next = self
+            .last_issued
+            .checked_add(1)
+            .ok_or(SerialNumberError::Exhausted)?;
+        let serial_number = Uint::new(&next.to_be_bytes())?;
+        self.last_issued = next;
+        Ok(serial_number)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn serial_number_bounds() {
+        assert!(validate_serial_number(&Uint::new(&[0]).unwrap()).is_err());
+        assert!(validate_serial_number(&Uint::new(&[0, 0]).unwrap()).is_err());
+        validate_serial_number(&Uint::new(&[1]).unwrap()).unwrap();
+        validate_serial_number(&Uint::new(&[0x7f; 20]).unwrap()).unwrap();
+        // A set most significant bit requires a leading zero octet in DER
+        assert!(validate_serial_number(&Uint::new(&[0xff; 20]).unwrap()).is_err());
+        assert!(validate_serial_number(&Uint::new(&[0x01; 21]).unwrap()).is_err());
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn negative_serial_numbers_are_rejected() {
+        use der::Decode;
+
+        let positive = <SerialNumber>::from_der(&[2, 2, 0, 0xff]).unwrap();
+        assert_eq!(
+            decode_serial_number(&positive).unwrap(),
+            Uint::new(&[0xff]).unwrap()
+        );
+        let negative = <SerialNumber>::from_der(&[2, 1, 0xff]).unwrap();
+        assert!(decode_serial_number(&negative).is_err());
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn monotonic_generator() {
+        let mut generator = MonotonicSerialNumberGenerator::default();
+        assert_eq!(
+            generator.next_serial_number().unwrap(),
+            Uint::new(&[1]).unwrap()
+        );
+        assert_eq!(
+            generator.next_serial_number().unwrap(),
+            Uint::new(&[2]).unwrap()
+        );
+        assert_eq!(generator.last_issued(), 2);
+
+        let mut restored = MonotonicSerialNumberGenerator::new(generator.last_issued());
+        assert_eq!(
+            restored.next_serial_number().unwrap(),
+            Uint::new(&[3]).unwrap()
+        );
+
+        let mut exhausted = MonotonicSerialNumberGenerator::new(u128::MAX);
+        assert_eq!(
+            exhausted.next_serial_number().err().unwrap(),
+            SerialNumberError::Exhausted
+        );
+        assert_eq!(exhausted.last_issued(), u128::MAX);
+        validate_serial_number(
+            &MonotonicSerialNumberGenerator::new(u128::MAX - 1)
+                .next_serial_number()
+                .unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[cfg(feature = "getrandom")]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn random_generator() {
+        let mut generator = RandomSerialNumberGenerator::new();
+        let first = generator.next_serial_number().unwrap();
+        let second = generator.next_serial_number().unwrap();
+        assert_ne!(first, second);
+        for serial_number in [first, second] {
+            validate_serial_number(&serial_number).unwrap();
+            assert!(serial_number.as_bytes().len() <= 16);
+        }
+    }
+}
@@ -0,0 +1,256 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+
+use der::asn1::Uint;
+use x509_cert::name::Name;
+
+use crate::key::PublicKey;
+use crate::signature::Signature;
+use crate::types::FederationId;
+
+use super::idcert::IdCertExt;
+use super::SessionId;
+
+/// A storage backend for [IdCertExt]s, which are [IdCert](super::idcert::IdCert)s along with
+/// their `invalidated` state. Certificates are identified by their issuer and serial number, and
+/// can be looked up by the [FederationId] and [SessionId] of their subject.
+///
+/// This crate ships the [MemoryIdCertStore]. Implement this trait to persist certificates in a
+/// database or other storage of your choice.
+pub trait IdCertStore<S: Signature, P: PublicKey<S>> {
+    /// The error type returned by the storage backend.
+    type Error: std::error::Error;
+
+    /// Adds a certificate to the store. A certificate with the same issuer and serial number as an
+    /// already stored certificate replaces that certificate.
+    fn insert(&mut self, id_cert: IdCertExt<S, P>) -> Result<(), Self::Error>;
+
+    /// Marks the certificate with the given issuer and serial number as invalidated. Returns
+    /// `false`, if no such certificate is stored.
+    fn invalidate(&mut self, issuer: &Name, serial_number: &Uint) -> Result<bool, Self::Error>;
+
+    /// Returns the certificate with the given issuer and serial number, if it is stored.
+    fn get_by_serial_number(
+        &self,
+        issuer: &Name,
+        serial_number: &Uint,
+    ) -> Result<Option<IdCertExt<S, P>>, Self::Error>;
+
+    /// Returns all certificates issued to the actor with the given [FederationId], across all of
+    /// its sessions.
+    fn get_by_federation_id(
+        &self,
+        federation_id: &FederationId,
+    ) -> Result<Vec<IdCertExt<S, P>>, Self::Error>;
+
+    /// Returns all certificates issued to the given session of the actor with the given
+    /// [FederationId].
+    fn get_by_session_id(
+        &self,
+        federation_id: &FederationId,
+        session_id: &SessionId,
+    ) -> Result<Vec<IdCertExt<S, P>>, Self::Error>;
+
+    /// Returns all certificates issued by the home server with the given subject [Name].
+    fn get_by_issuer(&self, issuer: &Name) -> Result<Vec<IdCertExt<S, P>>, Self::Error>;
+
+    /// Returns the certificates of the actor with the given [FederationId] which were valid at the
+    /// given UNIX `time`, optionally restricted to a single session. Certificates are ordered by
+    /// the start of their validity period, latest first.
+    ///
+    /// Invalidated certificates are included; check the `invalidated` field of the returned
+    /// [IdCertExt]s to decide whether to trust a certificate which has been invalidated since.
+    fn valid_at(
+        &self,
+        federation_id: &FederationId,
+        session_id: Option<&SessionId>,
+        time: u64,
+    ) -> Result<Vec<IdCertExt<S, P>>, Self::Error> {
+        let mut id_certs = match session_id {
+            Some(session_id) => self.get_by_session_id(federation_id, session_id)?,
+            None => self.get_by_federation_id(federation_id)?,
+        };
+        id_certs.retain(|id_cert| id_cert.id_cert.valid_at(time));
+        id_certs.sort_by_key(|id_cert| {
+            std::cmp::Reverse(
+                id_cert
+                    .id_cert
+                    .id_cert_tbs()
+                    .validity
+                    .not_before
+                    .to_unix_duration(),
+            )
+        });
+        Ok(id_certs)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An [IdCertStore] keeping all certificates in memory. Lookups by [FederationId], [SessionId],
+/// serial number and issuer are served from indices, which are updated on every insertion.
+pub struct MemoryIdCertStore<S: Signature, P: PublicKey<S>> {
+    id_certs: Vec<IdCertExt<S, P>>,
+    /// Issuer, as a string representation of its [Name], and serial number of each certificate
+    by_serial_number: BTreeMap<(String, Uint), usize>,
+    by_federation_id: BTreeMap<FederationId, Vec<usize>>,
+    /// Federation ID and session ID of each actor certificate
+    by_session_id: BTreeMap<(FederationId, String), Vec<usize>>,
+    by_issuer: BTreeMap<String, Vec<usize>>,
+}
+
+impl<S: Signature, P: PublicKey<S>> Default for MemoryIdCertStore<S, P> {
+    fn default() -> Self {
+        Self {
+            id_certs: Vec::new(),
+            by_serial_number: BTreeMap::new(),
+            by_federation_id: BTreeMap::new(),
+            by_session_id: BTreeMap::new(),
+            by_issuer: BTreeMap::new(),
+        }
+    }
+}
+
+impl<S: Signature, P: PublicKey<S>> MemoryIdCertStore<S, P> {
+    /// Creates a new, empty [MemoryIdCertStore].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of stored certificates.
+    pub fn len(&self) -> usize {
+        self.id_certs.len()
+    }
+
+    /// Whether the store contains no certificates.
+    pub fn is_empty(&self) -> bool {
+        self.id_certs.is_empty()
+    }
+
+    /// Returns an iterator over all stored certificates, in order of their first insertion.
+    pub fn iter(&self) -> impl Iterator<Item = &IdCertExt<S, P>> {
+        self.id_certs.iter()
+    }
+
+    /// Adds the certificate at `position` of `self.id_certs` to the lookup indices.
+    fn index(&mut self, position: usize) {
+        let id_cert = &self.id_certs[position].id_cert;
+        let issuer = id_cert.id_cert_tbs().issuer.to_string();
+        self.by_serial_number.insert(
+            (issuer.clone(), id_cert.id_cert_tbs().serial_number.clone()),
+            position,
+        );
+        self.by_issuer.entry(issuer).or_default().push(position);
+        // Home server certificates carry neither a federation ID nor a session ID
+        if let Ok(federation_id) = id_cert.federation_id() {
+            if let Ok(session_id) = id_cert.session_id() {
+                self.by_session_id
+                    .entry((federation_id.clone(), session_id.to_string()))
+                    .or_default()
+                    .push(position);
+            }
+            self.by_federation_id
+                .entry(federation_id)
+                .or_default()
+                .push(position);
+        }
+    }
+
+    /// Rebuilds all lookup indices from scratch.
+    fn reindex(&mut self) {
+        self.by_serial_number.clear();
+        self.by_federation_id.clear();
+        self.by_session_id.clear();
+        self.by_issuer.clear();
+        for position in 0..self.id_certs.len() {
+            self.index(position);
+        }
+    }
+
+    /// Clones the certificates at the given positions of `self.id_certs`.
+    fn collect(&self, positions: Option<&Vec<usize>>) -> Vec<IdCertExt<S, P>> {
+        positions
+            .map(|positions| {
+                positions
+                    .iter()
+                    .map(|position| self.id_certs[*position].clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl<S: Signature, P: PublicKey<S>> IdCertStore<S, P> for MemoryIdCertStore<S, P> {
+    type Error = Infallible;
+
+    fn insert(&mut self, id_cert: IdCertExt<S, P>) -> Result<(), Self::Error> {
+        let key = (
+            id_cert.id_cert.id_cert_tbs().issuer.to_string(),
+            id_cert.id_cert.id_cert_tbs().serial_number.clone(),
+        );
+        match self.by_serial_number.get(&key) {
+            Some(position) => {
+                log::trace!(
+                    "[MemoryIdCertStore::insert()] Replacing certificate with serial number {:?}",
+                    key.1
+                );
+                self.id_certs[*position] = id_cert;
+                self.reindex();
+            }
+            None => {
+                self.id_certs.push(id_cert);
+                self.index(self.id_certs.len() - 1);
+            }
+        }
+        Ok(())
+    }
+
+    fn invalidate(&mut self, issuer: &Name, serial_number: &Uint) -> Result<bool, Self::Error> {
+        match self
+            .by_serial_number
+            .get(&(issuer.to_string(), serial_number.clone()))
+        {
+            Some(position) => {
+                self.id_certs[*position].invalidated = true;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn get_by_serial_number(
+        &self,
+        issuer: &Name,
+        serial_number: &Uint,
+    ) -> Result<Option<IdCertExt<S, P>>, Self::Error> {
+        Ok(self
+            .by_serial_number
+            .get(&(issuer.to_string(), serial_number.clone()))
+            .map(|position| self.id_certs[*position].clone()))
+    }
+
+    fn get_by_federation_id(
+        &self,
+        federation_id: &FederationId,
+    ) -> Result<Vec<IdCertExt<S, P>>, Self::Error> {
+        Ok(self.collect(self.by_federation_id.get(federation_id)))
+    }
+
+    fn get_by_session_id(
+        &self,
+        federation_id: &FederationId,
+        session_id: &SessionId,
+    ) -> Result<Vec<IdCertExt<S, P>>, Self::Error> {
+        Ok(self.collect(
+            self.by_session_id
+                .get(&(federation_id.clone(), session_id.to_string())),
+        ))
+    }
+
+    fn get_by_issuer(&self, issuer: &Name) -> Result<Vec<IdCertExt<S, P>>, Self::Error> {
+        Ok(self.collect(self.by_issuer.get(&issuer.to_string())))
+    }
+}
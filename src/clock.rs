@@ -0,0 +1,192 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+/// A source of the current time, used for all time-based checks in this crate, such as checking
+/// the validity period of an [IdCert](crate::certs::idcert::IdCert) or the expiry of a
+/// [ChallengeString](crate::types::ChallengeString).
+///
+/// Besides the current time, a `Clock` specifies a skew tolerance: the number of seconds by which
+/// the clock of the party which created a time-sensitive object may deviate from this clock.
+/// Checks against a point in time are relaxed by this amount in both directions.
+pub trait Clock: std::fmt::Debug {
+    /// The current time, in seconds since the UNIX epoch.
+    fn now(&self) -> u64;
+
+    /// The tolerated clock skew, in seconds. Defaults to `0`.
+    fn skew_tolerance(&self) -> u64 {
+        0
+    }
+
+    /// Checks, if the current time lies within the period from `not_before` to `not_after`
+    /// (inclusive), taking the [Clock::skew_tolerance()] into account.
+    fn is_within(&self, not_before: u64, not_after: u64) -> bool {
+        let now = self.now();
+        let skew = self.skew_tolerance();
+        now.saturating_add(skew) >= not_before && now.saturating_sub(skew) <= not_after
+    }
+
+    /// Checks, if the point in time `expires` lies in the past, taking the
+    /// [Clock::skew_tolerance()] into account.
+    fn is_expired(&self, expires: u64) -> bool {
+        self.now().saturating_sub(self.skew_tolerance()) > expires
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// A [Clock] reading the current time from the system clock. If the `wasm` feature is enabled on
+/// `wasm32` targets, the time is read from the JavaScript `Date` API instead.
+///
+/// ## Panics
+///
+/// The `wasm32-unknown-unknown` target has no system clock. On this target, [SystemClock::now()]
+/// panics, unless the `wasm` feature is enabled.
+pub struct SystemClock {
+    /// The tolerated clock skew, in seconds.
+    pub skew_tolerance: u64,
+}
+
+impl SystemClock {
+    /// Creates a new [SystemClock] with a skew tolerance of `0`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the tolerated clock skew, in seconds.
+    pub fn with_skew_tolerance(mut self, skew_tolerance: u64) -> Self {
+        self.skew_tolerance = skew_tolerance;
+        self
+    }
+}
+
+impl Clock for SystemClock {
+    #[cfg(not(all(target_arch = "wasm32", feature = "wasm")))]
+    fn now(&self) -> u64 {
+        match std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+            Ok(duration) => duration.as_secs(),
+            Err(_) => {
+                log::warn!(
+                    "[SystemClock::now()] System clock is set to a time before the UNIX epoch"
+                );
+                0
+            }
+        }
+    }
+
+    #[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+    fn now(&self) -> u64 {
+        (js_sys::Date::now() / 1000.0) as u64
+    }
+
+    fn skew_tolerance(&self) -> u64 {
+        self.skew_tolerance
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A [Clock] which always reports the same point in time. Useful for tests, and for verifying
+/// objects at a specific point in the past.
+pub struct FixedClock {
+    /// The time reported by this clock, in seconds since the UNIX epoch.
+    pub time: u64,
+    /// The tolerated clock skew, in seconds.
+    pub skew_tolerance: u64,
+}
+
+impl FixedClock {
+    /// Creates a new [FixedClock] reporting `time`, with a skew tolerance of `0`.
+    pub fn new(time: u64) -> Self {
+        Self {
+            time,
+            skew_tolerance: 0,
+        }
+    }
+
+    /// Sets the tolerated clock skew, in seconds.
+    pub fn with_skew_tolerance(mut self, skew_tolerance: u64) -> Self {
+        self.skew_tolerance = skew_tolerance;
+        self
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> u64 {
+        self.time
+    }
+
+    fn skew_tolerance(&self) -> u64 {
+        self.skew_tolerance
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A [Clock] which reports the time of an inner clock, shifted by a fixed amount of seconds. The
+/// skew tolerance of the inner clock is retained.
+pub struct OffsetClock<C: Clock> {
+    /// The inner clock
+    pub clock: C,
+    /// The offset in seconds, which is added to the time of the inner clock. May be negative.
+    pub offset: i64,
+}
+
+impl<C: Clock> OffsetClock<C> {
+    /// Creates a new [OffsetClock], shifting the time of `clock` by `offset` seconds.
+    pub fn new(clock: C, offset: i64) -> Self {
+        Self { clock, offset }
+    }
+}
+
+impl<C: Clock> Clock for OffsetClock<C> {
+    fn now(&self) -> u64 {
+        self.clock.now().saturating_add_signed(self.offset)
+    }
+
+    fn skew_tolerance(&self) -> u64 {
+        self.clock.skew_tolerance()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn skew_tolerance() {
+        let clock = FixedClock::new(100);
+        assert!(clock.is_within(100, 200));
+        assert!(!clock.is_within(101, 200));
+        assert!(!clock.is_within(0, 99));
+        assert!(!clock.is_expired(100));
+        assert!(clock.is_expired(99));
+
+        let clock = clock.with_skew_tolerance(5);
+        assert!(clock.is_within(105, 200));
+        assert!(!clock.is_within(106, 200));
+        assert!(clock.is_within(0, 95));
+        assert!(!clock.is_within(0, 94));
+        assert!(!clock.is_expired(95));
+        assert!(clock.is_expired(94));
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn offset_clock() {
+        let clock = FixedClock::new(100).with_skew_tolerance(5);
+        assert_eq!(OffsetClock::new(clock, 50).now(), 150);
+        assert_eq!(OffsetClock::new(clock, -50).now(), 50);
+        assert_eq!(OffsetClock::new(clock, -500).now(), 0);
+        assert_eq!(OffsetClock::new(clock, 50).skew_tolerance(), 5);
+        assert_eq!(
+            OffsetClock::new(FixedClock::new(u64::MAX), 1).now(),
+            u64::MAX
+        );
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn system_clock() {
+        // 2024-01-01T00:00:00Z
+        assert!(SystemClock::new().now() > 1_704_067_200);
+    }
+}
@@ -7,7 +7,63 @@ use crate::errors::{ERR_MSG_ACTOR_MISSING_SIGNING_CAPS, ERR_MSG_HOME_SERVER_MISS
 use super::*;
 
 impl Constrained for Capabilities {
-    fn validate(&self, _target: Option<Target>) -> Result<(), ConstraintError> {
+    /// [Capabilities] must meet the following criteria to be valid in the context of polyproto:
+    /// - Key usages and basic constraints must be consistent with each other, as described in
+    ///   RFC 5280.
+    /// - If a [Target] is given and the extended key usage extension is present, it must contain
+    ///   the purpose of that target, see [ValidationPolicy::required_purpose()].
+    ///   [ValidationPolicy::spec_default()], which is used unless validated with a different
+    ///   [ValidationPolicy], requires no purposes.
+    fn validate(&self, target: Option<Target>) -> Result<(), ConstraintError> {
+        first_violation(self.validate_all(target))
+    }
+
+    /// Offending components are named after the fields of [Capabilities]: `key_usage`,
+    /// `extended_key_usage` and `basic_constraints`.
+    fn validate_all(&self, target: Option<Target>) -> Vec<ConstraintViolation> {
+        self.violations(target, &ValidationPolicy::spec_default())
+    }
+
+    fn validate_all_with_policy(
+        &self,
+        target: Option<Target>,
+        policy: &ValidationPolicy,
+    ) -> Vec<ConstraintViolation> {
+        let mut violations = self.violations(target, policy);
+        violations.extend(policy.capabilities_violations(self));
+        violations
+    }
+}
+
+impl Capabilities {
+    /// Checks these [Capabilities] against the polyproto specification, requiring the extended
+    /// key usage purpose which `policy` defines for `target`.
+    pub(super) fn violations(
+        &self,
+        target: Option<Target>,
+        policy: &ValidationPolicy,
+    ) -> Vec<ConstraintViolation> {
+        let mut violations = Vec::new();
+        if let Some(target) = target {
+            if let Some(required) = policy.required_purpose(target) {
+                if !self.extended_key_usage.permits(required) {
+                    log::warn!(
+                        "[Capabilities::violations()] Extended key usages {:?} do not permit {:?}, which is required for target {:?}",
+                        self.extended_key_usage.extended_key_usages,
+                        required,
+                        target
+                    );
+                    violations.push(ConstraintViolation::new(
+                        ViolationCode::MissingExtendedKeyUsage,
+                        "extended_key_usage",
+                        ConstraintError::Malformed(Some(format!(
+                            "Extended key usage {:?} is required for {:?} certificates",
+                            required, target
+                        ))),
+                    ));
+                }
+            }
+        }
         let is_ca = self.basic_constraints.ca;
 
         // Define the flags to check
@@ -43,44 +99,59 @@ impl Constrained for Capabilities {
         // Non-CAs must be able to sign their messages. Whether with or without non-repudiation
         // does not matter.
         if !is_ca && !can_sign && !can_commit_content {
-            return Err(ConstraintError::Malformed(Some(
-                ERR_MSG_ACTOR_MISSING_SIGNING_CAPS.to_string(),
-            )));
+            violations.push(ConstraintViolation::new(
+                ViolationCode::MissingSigningKeyUsage,
+                "key_usage",
+                ConstraintError::Malformed(Some(ERR_MSG_ACTOR_MISSING_SIGNING_CAPS.to_string())),
+            ));
         }
 
         // Certificates cannot be both non-repudiating and repudiating
         if can_sign && can_commit_content {
-            return Err(ConstraintError::Malformed(Some(
-                "Cannot have both signing and non-repudiation signing capabilities".to_string(),
-            )));
+            violations.push(ConstraintViolation::new(
+                ViolationCode::ConflictingSigningKeyUsages,
+                "key_usage",
+                ConstraintError::Malformed(Some(
+                    "Cannot have both signing and non-repudiation signing capabilities".to_string(),
+                )),
+            ));
         }
 
         // If these Capabilities are for a CA, it also must have the KeyCertSign Capability set to
         // true. Also, non-CAs are not allowed to have the KeyCertSign flag set to true.
-        if is_ca || key_cert_sign {
-            if !is_ca {
-                return Err(ConstraintError::Malformed(Some(
+        if key_cert_sign && !is_ca {
+            violations.push(ConstraintViolation::new(
+                ViolationCode::KeyCertSignWithoutCa,
+                "basic_constraints",
+                ConstraintError::Malformed(Some(
                     "If KeyCertSign capability is wanted, CA flag must be true".to_string(),
-                )));
-            }
-            if !key_cert_sign {
-                return Err(ConstraintError::Malformed(Some(format!(
+                )),
+            ));
+        }
+        if is_ca && !key_cert_sign {
+            violations.push(ConstraintViolation::new(
+                ViolationCode::CaWithoutKeyCertSign,
+                "key_usage",
+                ConstraintError::Malformed(Some(format!(
                     "{} Missing capability \"KeyCertSign\"",
                     ERR_MSG_HOME_SERVER_MISSING_CA_ATTR
-                ))));
-            }
+                ))),
+            ));
         }
 
         // has_key_agreement needs to be true if has_only_encipher or _decipher are true.
         // See: <https://cryptography.io/en/latest/x509/reference/#cryptography.x509.KeyUsage.encipher_only>
         // See: <https://cryptography.io/en/latest/x509/reference/#cryptography.x509.KeyUsage.decipher_only>
         if (has_only_encipher || has_only_decipher) && !has_key_agreement {
-            Err(ConstraintError::Malformed(Some(
-                "KeyAgreement capability needs to be true to use OnlyEncipher or OnlyDecipher"
-                    .to_string(),
-            )))
-        } else {
-            Ok(())
+            violations.push(ConstraintViolation::new(
+                ViolationCode::EncipherDecipherWithoutKeyAgreement,
+                "key_usage",
+                ConstraintError::Malformed(Some(
+                    "KeyAgreement capability needs to be true to use OnlyEncipher or OnlyDecipher"
+                        .to_string(),
+                )),
+            ));
         }
+        violations
     }
 }
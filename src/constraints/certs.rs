@@ -4,6 +4,8 @@
 
 use log::{debug, warn};
 
+use crate::certs::serial_number::validate_serial_number;
+
 use crate::errors::{
     ERR_MSG_ACTOR_CANNOT_BE_CA, ERR_MSG_DC_MISMATCH_ISSUER_SUBJECT,
     ERR_MSG_HOME_SERVER_MISSING_CA_ATTR, ERR_MSG_SIGNATURE_MISMATCH,
@@ -13,97 +15,174 @@ use super::*;
 
 impl<S: Signature, P: PublicKey<S>> Constrained for IdCsrInner<S, P> {
     fn validate(&self, target: Option<Target>) -> Result<(), ConstraintError> {
+        first_violation(self.validate_all(target))
+    }
+
+    /// Offending components are prefixed with `capabilities` or `subject`, e.g. `subject.uid`.
+    fn validate_all(&self, target: Option<Target>) -> Vec<ConstraintViolation> {
+        self.violations(target, &ValidationPolicy::spec_default())
+    }
+
+    fn validate_all_with_policy(
+        &self,
+        target: Option<Target>,
+        policy: &ValidationPolicy,
+    ) -> Vec<ConstraintViolation> {
+        let mut violations = self.violations(target, policy);
+        violations.extend(within(
+            "capabilities",
+            policy.capabilities_violations(&self.capabilities),
+        ));
+        violations.extend(within("subject", policy.name_violations(&self.subject)));
+        violations.extend(policy.algorithm_violations(
+            "subject_public_key",
+            &self.subject_public_key.public_key_info().algorithm,
+        ));
+        violations
+    }
+}
+
+impl<S: Signature, P: PublicKey<S>> IdCsrInner<S, P> {
+    /// Checks this CSR against the polyproto specification, requiring the extended key usage
+    /// purpose which `policy` defines for `target`.
+    fn violations(
+        &self,
+        target: Option<Target>,
+        policy: &ValidationPolicy,
+    ) -> Vec<ConstraintViolation> {
         log::trace!(
-            "[IdCsrInner::validate()] validating capabilities for target: {:?}",
+            "[IdCsrInner::violations()] validating capabilities for target: {:?}",
             target
         );
-        self.capabilities.validate(target)?;
+        let mut violations = within("capabilities", self.capabilities.violations(target, policy));
         log::trace!(
-            "[IdCsrInner::validate()] validating subject for target: {:?}",
+            "[IdCsrInner::violations()] validating subject for target: {:?}",
             target
         );
-        self.subject.validate(target)?;
-        if let Some(target) = target {
-            match target {
-                Target::Actor => {
-                    if self.capabilities.basic_constraints.ca {
-                        return Err(ConstraintError::Malformed(Some(
-                            ERR_MSG_ACTOR_CANNOT_BE_CA.to_string(),
-                        )));
-                    }
-                }
-                Target::HomeServer => {
-                    if !self.capabilities.basic_constraints.ca {
-                        return Err(ConstraintError::Malformed(Some(
-                            ERR_MSG_HOME_SERVER_MISSING_CA_ATTR.to_string(),
-                        )));
-                    }
-                }
-            }
-        }
-        Ok(())
+        violations.extend(within("subject", self.subject.validate_all(target)));
+        violations.extend(validate_ca_flag(&self.capabilities, target));
+        violations
     }
 }
 
 impl<S: Signature, P: PublicKey<S>> Constrained for IdCsr<S, P> {
     fn validate(&self, target: Option<Target>) -> Result<(), ConstraintError> {
+        first_violation(self.validate_all(target))
+    }
+
+    /// Offending components are named like those of [IdCsrInner], with a mismatching signature
+    /// being reported as `signature`.
+    fn validate_all(&self, target: Option<Target>) -> Vec<ConstraintViolation> {
         log::trace!(
-            "[IdCsr::validate()] validating inner CSR with target {:?}",
+            "[IdCsr::validate_all()] validating inner CSR with target {:?}",
             target
         );
-        self.inner_csr.validate(target)?;
-        log::trace!("[IdCsr::validate()] verifying signature");
-        match self.inner_csr.subject_public_key.verify_signature(
-            &self.signature,
-            match &self.inner_csr.clone().to_der() {
-                Ok(data) => data,
-                Err(_) => {
-                    log::warn!("[IdCsr::validate()] DER conversion failure when converting inner IdCsr to DER. IdCsr is likely malformed");
-                    return Err(ConstraintError::Malformed(Some("DER conversion failure when converting inner IdCsr to DER. IdCsr is likely malformed".to_string())))}
-            }
-        ) {
-            Ok(_) => (),
-            Err(_) => {
-                log::warn!(
-                    "[IdCsr::validate()] {}", ERR_MSG_SIGNATURE_MISMATCH);
-                return Err(ConstraintError::Malformed(Some(ERR_MSG_SIGNATURE_MISMATCH.to_string())))}
-        };
-        Ok(())
+        let mut violations = self.inner_csr().validate_all(target);
+        violations.extend(validate_csr_signature(self));
+        violations
+    }
+
+    fn validate_all_with_policy(
+        &self,
+        target: Option<Target>,
+        policy: &ValidationPolicy,
+    ) -> Vec<ConstraintViolation> {
+        let mut violations = self.inner_csr().validate_all_with_policy(target, policy);
+        violations
+            .extend(policy.algorithm_violations("signature_algorithm", &self.signature_algorithm));
+        violations.extend(validate_csr_signature(self));
+        violations
     }
 }
 
 impl<S: Signature, P: PublicKey<S>> Constrained for IdCert<S, P> {
     fn validate(&self, target: Option<Target>) -> Result<(), ConstraintError> {
+        first_violation(self.validate_all(target))
+    }
+
+    /// Offending components are named like those of [IdCertTbs].
+    fn validate_all(&self, target: Option<Target>) -> Vec<ConstraintViolation> {
         log::trace!(
-            "[IdCert::validate()] validating inner IdCertTbs with target {:?}",
+            "[IdCert::validate_all()] validating inner IdCertTbs with target {:?}",
             target
         );
-        self.id_cert_tbs.validate(target)?;
-        Ok(())
+        self.id_cert_tbs().validate_all(target)
+    }
+
+    fn validate_all_with_policy(
+        &self,
+        target: Option<Target>,
+        policy: &ValidationPolicy,
+    ) -> Vec<ConstraintViolation> {
+        self.id_cert_tbs().validate_all_with_policy(target, policy)
     }
 }
 
 impl<S: Signature, P: PublicKey<S>> Constrained for IdCertTbs<S, P> {
     fn validate(&self, target: Option<Target>) -> Result<(), ConstraintError> {
+        first_violation(self.validate_all(target))
+    }
+
+    /// Offending components are prefixed with `capabilities`, `issuer` or `subject`, e.g.
+    /// `issuer.uid`. An invalid serial number is reported as `serial_number`.
+    fn validate_all(&self, target: Option<Target>) -> Vec<ConstraintViolation> {
+        self.violations(target, &ValidationPolicy::spec_default())
+    }
+
+    /// Policy violations concerning the validity period are reported as `validity`.
+    fn validate_all_with_policy(
+        &self,
+        target: Option<Target>,
+        policy: &ValidationPolicy,
+    ) -> Vec<ConstraintViolation> {
+        let mut violations = self.violations(target, policy);
+        violations.extend(within(
+            "capabilities",
+            policy.capabilities_violations(&self.capabilities),
+        ));
+        violations.extend(policy.validity_violations(&self.validity));
+        violations.extend(within("issuer", policy.name_violations(&self.issuer)));
+        violations.extend(within("subject", policy.name_violations(&self.subject)));
+        violations
+            .extend(policy.algorithm_violations("signature_algorithm", &self.signature_algorithm));
+        violations.extend(policy.algorithm_violations(
+            "subject_public_key",
+            &self.subject_public_key.public_key_info().algorithm,
+        ));
+        violations
+    }
+}
+
+impl<S: Signature, P: PublicKey<S>> IdCertTbs<S, P> {
+    /// Checks this certificate against the polyproto specification, requiring the extended key
+    /// usage purpose which `policy` defines for `target`.
+    fn violations(
+        &self,
+        target: Option<Target>,
+        policy: &ValidationPolicy,
+    ) -> Vec<ConstraintViolation> {
         log::trace!(
-            "[IdCertTbs::validate()] validating capabilities for target: {:?}",
+            "[IdCertTbs::violations()] validating capabilities for target: {:?}",
             target
         );
-        self.capabilities.validate(target)?;
-        dbg!(self.issuer.to_string());
-        self.issuer.validate(Some(Target::HomeServer))?;
-        self.subject.validate(target)?;
-        log::trace!(
-            "[IdCertTbs::validate()] checking if domain components of issuer and subject are equal"
-        );
-        log::trace!(
-            "[IdCertTbs::validate()] Issuer: {}",
-            self.issuer.to_string()
-        );
+        let mut violations = within("capabilities", self.capabilities.violations(target, policy));
+        if let Err(error) = validate_serial_number(&self.serial_number) {
+            violations.push(ConstraintViolation::new(
+                ViolationCode::InvalidSerialNumber,
+                "serial_number",
+                error,
+            ));
+        }
+        violations.extend(within(
+            "issuer",
+            self.issuer.validate_all(Some(Target::HomeServer)),
+        ));
+        violations.extend(within("subject", self.subject.validate_all(target)));
         log::trace!(
-            "[IdCertTbs::validate()] Subject: {}",
-            self.subject.to_string()
+            "[IdCertTbs::violations()] checking if domain components of issuer and subject are equal"
         );
+        log::trace!("[IdCertTbs::violations()] Issuer: {}", self.issuer);
+        log::trace!("[IdCertTbs::violations()] Subject: {}", self.subject);
         match equal_domain_components(&self.issuer, &self.subject) {
             true => debug!("Domain components of issuer and subject are equal"),
             false => {
@@ -111,29 +190,62 @@ impl<S: Signature, P: PublicKey<S>> Constrained for IdCertTbs<S, P> {
                     "{}\nIssuer: {}\nSubject: {}",
                     ERR_MSG_DC_MISMATCH_ISSUER_SUBJECT, &self.issuer, &self.subject
                 );
-                return Err(ConstraintError::Malformed(Some(
-                    ERR_MSG_DC_MISMATCH_ISSUER_SUBJECT.to_string(),
-                )));
+                violations.push(ConstraintViolation::new(
+                    ViolationCode::IssuerSubjectDomainMismatch,
+                    "subject.dc",
+                    ConstraintError::Malformed(Some(
+                        ERR_MSG_DC_MISMATCH_ISSUER_SUBJECT.to_string(),
+                    )),
+                ));
             }
         }
-        if let Some(target) = target {
-            match target {
-                Target::Actor => {
-                    if self.capabilities.basic_constraints.ca {
-                        return Err(ConstraintError::Malformed(Some(
-                            ERR_MSG_ACTOR_CANNOT_BE_CA.to_string(),
-                        )));
-                    }
-                }
-                Target::HomeServer => {
-                    if !self.capabilities.basic_constraints.ca {
-                        return Err(ConstraintError::Malformed(Some(
-                            ERR_MSG_HOME_SERVER_MISSING_CA_ATTR.to_string(),
-                        )));
-                    }
-                }
-            }
-        }
-        Ok(())
+        violations.extend(validate_ca_flag(&self.capabilities, target));
+        violations
+    }
+}
+
+/// Checks that the signature of `id_csr` verifies under its own subject public key.
+fn validate_csr_signature<S: Signature, P: PublicKey<S>>(
+    id_csr: &IdCsr<S, P>,
+) -> Option<ConstraintViolation> {
+    log::trace!("[IdCsr::validate_all()] verifying signature");
+    if id_csr.verify_signature().is_ok() {
+        return None;
     }
+    log::warn!("[IdCsr::validate_all()] {}", ERR_MSG_SIGNATURE_MISMATCH);
+    Some(ConstraintViolation::new(
+        ViolationCode::SignatureMismatch,
+        "signature",
+        ConstraintError::Malformed(Some(ERR_MSG_SIGNATURE_MISMATCH.to_string())),
+    ))
+}
+
+/// Checks that actors are not CAs, while home servers are.
+fn validate_ca_flag(
+    capabilities: &Capabilities,
+    target: Option<Target>,
+) -> Option<ConstraintViolation> {
+    let (code, message) = match target? {
+        Target::Actor if capabilities.basic_constraints.ca => {
+            (ViolationCode::ActorIsCa, ERR_MSG_ACTOR_CANNOT_BE_CA)
+        }
+        Target::HomeServer if !capabilities.basic_constraints.ca => (
+            ViolationCode::HomeServerIsNotCa,
+            ERR_MSG_HOME_SERVER_MISSING_CA_ATTR,
+        ),
+        _ => return None,
+    };
+    Some(ConstraintViolation::new(
+        code,
+        "capabilities.basic_constraints",
+        ConstraintError::Malformed(Some(message.to_string())),
+    ))
+}
+
+/// Prepends `parent` to the components of all `violations`.
+fn within(parent: &str, violations: Vec<ConstraintViolation>) -> Vec<ConstraintViolation> {
+    violations
+        .into_iter()
+        .map(|violation| violation.within(parent))
+        .collect()
 }
@@ -4,20 +4,18 @@
 
 use der::Length;
 use regex::Regex;
-use x509_cert::name::{Name, RelativeDistinguishedName};
+use x509_cert::name::Name;
 
 use crate::certs::capabilities::{Capabilities, KeyUsage};
 use crate::certs::idcert::IdCert;
 use crate::certs::idcerttbs::IdCertTbs;
 use crate::certs::idcsr::{IdCsr, IdCsrInner};
+use crate::certs::policy::ValidationPolicy;
 use crate::certs::{equal_domain_components, SessionId, Target};
-use crate::errors::ConstraintError;
+use crate::errors::{ConstraintError, ConstraintViolation, ViolationCode};
 use crate::key::PublicKey;
 use crate::signature::Signature;
-use crate::{
-    Constrained, OID_RDN_COMMON_NAME, OID_RDN_DOMAIN_COMPONENT, OID_RDN_UID,
-    OID_RDN_UNIQUE_IDENTIFIER,
-};
+use crate::Constrained;
 
 mod capabilities;
 mod certs;
@@ -26,6 +24,15 @@ mod session_id;
 #[cfg(feature = "types")]
 mod types;
 
+/// Turns the violations found by [Constrained::validate_all()] into the result of
+/// [Constrained::validate()], which is the error of the first violation.
+fn first_violation(violations: Vec<ConstraintViolation>) -> Result<(), ConstraintError> {
+    match violations.into_iter().next() {
+        Some(violation) => Err(violation.error),
+        None => Ok(()),
+    }
+}
+
 #[cfg(test)]
 mod name_constraints {
     use std::str::FromStr;
@@ -33,6 +40,7 @@ mod name_constraints {
     use x509_cert::name::Name;
 
     use crate::certs::Target;
+    use crate::errors::ViolationCode;
     use crate::testing_utils::init_logger;
     use crate::Constrained;
 
@@ -186,6 +194,46 @@ mod name_constraints {
             assert!(name.validate(target).is_err());
         }
     }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn validate_all_collects_violations() {
+        init_logger();
+        let name = Name::from_str(
+            "CN=flori,CN=xenia,DC=localhost,uid=numbaone@localhost,uid=numbatwo@localhost",
+        )
+        .unwrap();
+        let violations = name.validate_all(Some(Target::Actor));
+        let found = violations
+            .iter()
+            .map(|violation| (violation.code, violation.component.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            vec![
+                (ViolationCode::TooManyCommonNames, "cn"),
+                (ViolationCode::UidCommonNameMismatch, "cn"),
+                (ViolationCode::TooManyUids, "uid"),
+                (ViolationCode::MissingUniqueIdentifier, "uniqueIdentifier"),
+            ]
+        );
+        assert_eq!(
+            name.validate(Some(Target::Actor)).err().unwrap(),
+            violations[0].error
+        );
+
+        let name = Name::from_str("DC=polyphony,DC=chat,UID=flori@polyphony.chat").unwrap();
+        let found = name
+            .validate_all(Some(Target::HomeServer))
+            .into_iter()
+            .map(|violation| violation.code)
+            .collect::<Vec<_>>();
+        assert_eq!(found, vec![ViolationCode::UnexpectedUid]);
+        assert!(Name::from_str("DC=polyphony,DC=chat")
+            .unwrap()
+            .validate_all(Some(Target::HomeServer))
+            .is_empty());
+    }
 }
 
 #[cfg(test)]
@@ -2,10 +2,9 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use crate::certs::NameComponents;
 use crate::errors::ERR_MSG_DC_UID_MISMATCH;
 
-use x509_cert::attr::AttributeTypeAndValue;
-
 use super::*;
 
 impl Constrained for Name {
@@ -20,168 +19,220 @@ impl Constrained for Name {
     ///     - uniqueIdentifier is the [SessionId] of the actor.
     /// - MAY have "organizational unit" attributes
     /// - MAY have other attributes, which might be ignored by other home servers and other clients.
-    // I apologize. This is horrible. I'll redo it eventually. Depression made me do it. -bitfl0wer
     fn validate(&self, target: Option<Target>) -> Result<(), ConstraintError> {
-        log::trace!("[Name::validate()] Validating Name: {}", self.to_string());
-        let mut num_cn: u8 = 0;
-        let mut num_dc: u8 = 0;
-        let mut num_uid: u8 = 0;
-        let mut num_unique_identifier: u8 = 0;
-        let mut vec_dc: Vec<RelativeDistinguishedName> = Vec::new();
-        let mut uid: RelativeDistinguishedName = RelativeDistinguishedName::default();
-        let mut cn: RelativeDistinguishedName = RelativeDistinguishedName::default();
+        first_violation(self.validate_all(target))
+    }
 
-        let rdns = &self.0;
-        for rdn in rdns.iter() {
-            log::trace!(
-                "[Name::validate()] Determining OID of RDN {} and performing appropriate validation",
-                rdn.to_string()
-            );
-            for item in rdn.0.iter() {
-                match item.oid.to_string().as_str() {
-                    OID_RDN_UID => {
-                        log::trace!("[Name::validate()] Found UID in RDN: {}", item.to_string());
-                        num_uid += 1;
-                        uid = rdn.clone();
-                        validate_rdn_uid(item)?;
-                    }
-                    OID_RDN_UNIQUE_IDENTIFIER => {
-                        log::trace!(
-                            "[Name::validate()] Found uniqueIdentifier in RDN: {}",
-                            item.to_string()
-                        );
-                        num_unique_identifier += 1;
-                        validate_rdn_unique_identifier(item)?;
-                    }
-                    OID_RDN_COMMON_NAME => {
-                        log::trace!(
-                            "[Name::validate()] Found Common Name in RDN: {}",
-                            item.to_string()
-                        );
-                        num_cn += 1;
-                        cn = rdn.clone();
-                        if num_cn > 1 {
-                            return Err(ConstraintError::OutOfBounds {
-                                lower: 1,
-                                upper: 1,
-                                actual: num_cn.to_string(),
-                                reason: "[Name::validate()] Distinguished Names must not contain more than one Common Name field".to_string()
-                            });
-                        }
-                    }
-                    OID_RDN_DOMAIN_COMPONENT => {
-                        log::trace!(
-                            "[Name::validate()] Found Domain Component in RDN: {}",
-                            item.to_string()
-                        );
-                        num_dc += 1;
-                        vec_dc.push(rdn.clone());
-                    }
-                    _ => {
-                        log::trace!(
-                            "[Name::validate()] Found unknown/non-validated component in RDN: {}",
-                            item.to_string()
-                        );
-                    }
-                }
+    /// Offending components are named after the attributes of the [Name]: `cn`, `dc`, `uid` and
+    /// `uniqueIdentifier`.
+    fn validate_all(&self, target: Option<Target>) -> Vec<ConstraintViolation> {
+        log::trace!("[Name::validate_all()] Validating Name: {}", self);
+        let mut violations = Vec::new();
+        let components = NameComponents::parse(self);
+        let num_cn = components.common_names.len();
+        let num_dc = components.domain_components.len();
+        let num_uid = components.uids.len();
+        let num_unique_identifier = components.unique_identifiers.len();
+        log::trace!(
+            "[Name::validate_all()] Found {} Common Name, {} Domain Component, {} UID and {} uniqueIdentifier components",
+            num_cn,
+            num_dc,
+            num_uid,
+            num_unique_identifier
+        );
+
+        if num_cn > 1 {
+            violations.push(ConstraintViolation::new(
+                ViolationCode::TooManyCommonNames,
+                "cn",
+                ConstraintError::OutOfBounds {
+                    lower: 1,
+                    upper: 1,
+                    actual: num_cn.to_string(),
+                    reason: "Distinguished Names must not contain more than one Common Name field"
+                        .to_string(),
+                },
+            ));
+        }
+        // Checks comparing the UID to other attributes only make sense for well-formed UIDs
+        let mut uids_valid = true;
+        for uid in components.uids.iter() {
+            if let Err(error) = validate_rdn_uid(uid) {
+                uids_valid = false;
+                violations.push(ConstraintViolation::new(
+                    ViolationCode::InvalidUid,
+                    "uid",
+                    error,
+                ));
             }
         }
-        // The order of the DCs is reversed in the [Name] object, compared to the order of the DCs in the UID.
-        vec_dc.reverse();
-        if let Some(target) = target {
-            match target {
-                Target::Actor => {
-                    log::trace!(
-                        "[Name::validate()] Validating DC {:?} matches DC in UID {}",
-                        vec_dc
-                            .iter()
-                            .map(|dc| dc.to_string())
-                            .collect::<Vec<String>>(),
-                        uid.to_string()
-                    );
-                    validate_dc_matches_dc_in_uid(&vec_dc, &uid)?;
-                }
-                Target::HomeServer => {
-                    if num_uid > 0 || num_unique_identifier > 0 {
-                        return Err(ConstraintError::OutOfBounds {
+        for unique_identifier in components.unique_identifiers.iter() {
+            if let Err(error) = validate_rdn_unique_identifier(unique_identifier) {
+                violations.push(ConstraintViolation::new(
+                    ViolationCode::InvalidUniqueIdentifier,
+                    "uniqueIdentifier",
+                    error,
+                ));
+            }
+        }
+        let uid = components
+            .uids
+            .last()
+            .map(String::as_str)
+            .unwrap_or_default();
+        match target {
+            Some(Target::Actor) if num_uid == 0 => {
+                violations.push(ConstraintViolation::new(
+                    ViolationCode::MissingUid,
+                    "uid",
+                    ConstraintError::Malformed(Some("Actors must have a UID".to_string())),
+                ));
+            }
+            Some(Target::HomeServer) => {
+                let home_server_violation = |code, component, actual: usize| {
+                    ConstraintViolation::new(
+                        code,
+                        component,
+                        ConstraintError::OutOfBounds {
                             lower: 0,
                             upper: 0,
-                            actual: "1".to_string(),
+                            actual: actual.to_string(),
                             reason: "Home Servers must not have UID or uniqueIdentifier"
                                 .to_string(),
-                        });
-                    }
+                        },
+                    )
+                };
+                if num_uid > 0 {
+                    violations.push(home_server_violation(
+                        ViolationCode::UnexpectedUid,
+                        "uid",
+                        num_uid,
+                    ));
+                }
+                if num_unique_identifier > 0 {
+                    violations.push(home_server_violation(
+                        ViolationCode::UnexpectedUniqueIdentifier,
+                        "uniqueIdentifier",
+                        num_unique_identifier,
+                    ));
                 }
-            };
-        } else if num_uid != 0 {
-            validate_dc_matches_dc_in_uid(&vec_dc, &uid)?;
+            }
+            _ if num_uid != 0 && uids_valid => {
+                log::trace!(
+                    "[Name::validate_all()] Validating DC {:?} matches DC in UID {}",
+                    components.domain_components,
+                    uid
+                );
+                if let Err(error) =
+                    validate_dc_matches_dc_in_uid(&components.domain_components, uid)
+                {
+                    violations.push(ConstraintViolation::new(
+                        ViolationCode::UidDomainMismatch,
+                        "uid",
+                        error,
+                    ));
+                }
+            }
+            _ => (),
         }
-        log::trace!(
-            "Encountered {} UID components and {} Common Name components",
-            num_uid,
-            num_cn
-        );
-        if num_uid != 0 && num_cn != 0 {
+        if num_uid != 0 && num_cn != 0 && uids_valid {
             log::trace!("Validating UID username matches Common Name");
-            validate_uid_username_matches_cn(&uid, &cn)?;
+            if let Err(error) = validate_uid_username_matches_cn(uid, &components.common_names[0]) {
+                violations.push(ConstraintViolation::new(
+                    ViolationCode::UidCommonNameMismatch,
+                    "cn",
+                    error,
+                ));
+            }
         }
         if num_dc == 0 {
-            return Err(ConstraintError::OutOfBounds {
-                lower: 1,
-                upper: u8::MAX as i32,
-                actual: "0".to_string(),
-                reason: "Domain Component is missing in Name component".to_string(),
-            });
+            violations.push(ConstraintViolation::new(
+                ViolationCode::MissingDomainComponent,
+                "dc",
+                ConstraintError::OutOfBounds {
+                    lower: 1,
+                    upper: u8::MAX as i32,
+                    actual: "0".to_string(),
+                    reason: "Domain Component is missing in Name component".to_string(),
+                },
+            ));
         }
         if num_uid > 1 {
-            return Err(ConstraintError::OutOfBounds {
-                lower: 0,
-                upper: 1,
-                actual: num_uid.to_string(),
-                reason: "Too many UID components supplied".to_string(),
-            });
+            violations.push(ConstraintViolation::new(
+                ViolationCode::TooManyUids,
+                "uid",
+                ConstraintError::OutOfBounds {
+                    lower: 0,
+                    upper: 1,
+                    actual: num_uid.to_string(),
+                    reason: "Too many UID components supplied".to_string(),
+                },
+            ));
         }
         if num_unique_identifier > 1 {
-            return Err(ConstraintError::OutOfBounds {
-                lower: 0,
-                upper: 1,
-                actual: num_unique_identifier.to_string(),
-                reason: "Too many uniqueIdentifier components supplied".to_string(),
-            });
+            violations.push(ConstraintViolation::new(
+                ViolationCode::TooManyUniqueIdentifiers,
+                "uniqueIdentifier",
+                ConstraintError::OutOfBounds {
+                    lower: 0,
+                    upper: 1,
+                    actual: num_unique_identifier.to_string(),
+                    reason: "Too many uniqueIdentifier components supplied".to_string(),
+                },
+            ));
         }
-        if num_unique_identifier > 0 && num_uid == 0 {
-            return Err(ConstraintError::OutOfBounds {
-                lower: 1,
-                upper: 1,
-                actual: num_uid.to_string(),
-                reason: "Actors must have uniqueIdentifier AND UID, only uniqueIdentifier found"
-                    .to_string(),
-            });
-        }
-        if num_uid > 0 && num_unique_identifier == 0 {
-            return Err(ConstraintError::OutOfBounds {
-                lower: 1,
-                upper: 1,
-                actual: num_unique_identifier.to_string(),
-                reason: "Actors must have uniqueIdentifier AND UID, only UID found".to_string(),
-            });
+        // Home servers have already been checked for the absence of both attributes above
+        if target != Some(Target::HomeServer) {
+            if num_unique_identifier > 0 && num_uid == 0 && target.is_none() {
+                violations.push(ConstraintViolation::new(
+                    ViolationCode::MissingUid,
+                    "uid",
+                    ConstraintError::OutOfBounds {
+                        lower: 1,
+                        upper: 1,
+                        actual: num_uid.to_string(),
+                        reason:
+                            "Actors must have uniqueIdentifier AND UID, only uniqueIdentifier found"
+                                .to_string(),
+                    },
+                ));
+            }
+            if num_unique_identifier == 0 && (num_uid > 0 || target == Some(Target::Actor)) {
+                violations.push(ConstraintViolation::new(
+                    ViolationCode::MissingUniqueIdentifier,
+                    "uniqueIdentifier",
+                    ConstraintError::OutOfBounds {
+                        lower: 1,
+                        upper: 1,
+                        actual: num_unique_identifier.to_string(),
+                        reason: "Actors must have uniqueIdentifier AND UID".to_string(),
+                    },
+                ));
+            }
         }
-        Ok(())
+        violations
+    }
+
+    fn validate_all_with_policy(
+        &self,
+        target: Option<Target>,
+        policy: &ValidationPolicy,
+    ) -> Vec<ConstraintViolation> {
+        let mut violations = self.validate_all(target);
+        violations.extend(policy.name_violations(self));
+        violations
     }
 }
 
 /// Check if the domain components are equal between the UID and the DCs
-fn validate_dc_matches_dc_in_uid(
-    vec_dc: &[RelativeDistinguishedName],
-    uid: &RelativeDistinguishedName,
-) -> Result<(), ConstraintError> {
+fn validate_dc_matches_dc_in_uid(vec_dc: &[String], uid: &str) -> Result<(), ConstraintError> {
     // Find the position of the @ in the UID
-    let position_of_at = match uid.to_string().find('@') {
+    let position_of_at = match uid.find('@') {
         Some(pos) => pos,
         None => {
             log::warn!(
                 "[validate_dc_matches_dc_in_uid] UID {} does not contain an @",
-                uid.to_string()
+                uid
             );
             return Err(ConstraintError::Malformed(Some(
                 "UID does not contain an @".to_string(),
@@ -189,45 +240,27 @@ fn validate_dc_matches_dc_in_uid(
         }
     };
     // Split the UID at the @
-    let uid_without_username = uid.to_string().split_at(position_of_at + 1).1.to_string(); // +1 to not include the @
-    let dc_normalized_uid: Vec<&str> = uid_without_username.split('.').collect();
-    dbg!(dc_normalized_uid.clone());
-    let mut index = 0u8;
-    // Iterate over the DCs in the UID and check if they are equal to the DCs in the DCs
-    for component in dc_normalized_uid.iter() {
-        let equivalent_dc = match vec_dc.get(index as usize) {
-            Some(dc) => dc,
-            None => {
+    let uid_without_username = uid.split_at(position_of_at + 1).1; // +1 to not include the @
+                                                                   // Iterate over the DCs in the UID and check if they are equal to the DCs in the DCs
+    for (index, component) in uid_without_username.split('.').enumerate() {
+        match vec_dc.get(index) {
+            Some(dc) if dc == component => (),
+            _ => {
                 return Err(ConstraintError::Malformed(Some(
                     ERR_MSG_DC_UID_MISMATCH.to_string(),
                 )))
             }
-        };
-        let equivalent_dc = equivalent_dc.to_string().split_at(3).1.to_string();
-        if component != &equivalent_dc.to_string() {
-            return Err(ConstraintError::Malformed(Some(
-                ERR_MSG_DC_UID_MISMATCH.to_string(),
-            )));
         }
-        index = match index.checked_add(1) {
-            Some(i) => i,
-            None => {
-                return Err(ConstraintError::Malformed(Some(
-                    "More than 255 Domain Components found".to_string(),
-                )))
-            }
-        };
     }
     Ok(())
 }
 
 /// Validate the UID field in the RDN. This performs a regex check to see if the UID is a valid
 /// Federation ID (FID).
-fn validate_rdn_uid(item: &AttributeTypeAndValue) -> Result<(), ConstraintError> {
+fn validate_rdn_uid(uid: &str) -> Result<(), ConstraintError> {
     let fid_regex = Regex::new(r"\b([a-z0-9._%+-]+)@([a-z0-9-]+(\.[a-z0-9-]+)*)")
         .expect("Regex failed to compile");
-    let string = String::from_utf8_lossy(item.value.value()).to_string();
-    if !fid_regex.is_match(&string) {
+    if !fid_regex.is_match(uid) {
         Err(ConstraintError::Malformed(Some(
             "Provided Federation ID (FID) in uid field seems to be invalid".to_string(),
         )))
@@ -238,25 +271,20 @@ fn validate_rdn_uid(item: &AttributeTypeAndValue) -> Result<(), ConstraintError>
 
 /// Validate the uniqueIdentifier field in the RDN. This performs a check to see if the provided
 /// input is a valid [SessionId].
-fn validate_rdn_unique_identifier(item: &AttributeTypeAndValue) -> Result<(), ConstraintError> {
-    SessionId::new_validated(&String::from_utf8_lossy(item.value.value()))?;
+fn validate_rdn_unique_identifier(unique_identifier: &str) -> Result<(), ConstraintError> {
+    SessionId::new_validated(unique_identifier)?;
     Ok(())
 }
 
 /// Validate that the UID username matches the Common Name
-fn validate_uid_username_matches_cn(
-    uid: &RelativeDistinguishedName,
-    cn: &RelativeDistinguishedName,
-) -> Result<(), ConstraintError> {
+fn validate_uid_username_matches_cn(uid: &str, cn: &str) -> Result<(), ConstraintError> {
     // Find the position of the @ in the UID
-    let uid_str = uid.to_string().split_off(4);
-    let cn_str = cn.to_string().split_off(3);
-    let position_of_at = match uid_str.find('@') {
+    let position_of_at = match uid.find('@') {
         Some(pos) => pos,
         None => {
             log::warn!(
                 "[validate_dc_matches_dc_in_uid] UID \"{}\" does not contain an @",
-                uid.to_string()
+                uid
             );
             return Err(ConstraintError::Malformed(Some(
                 "UID does not contain an @".to_string(),
@@ -264,14 +292,14 @@ fn validate_uid_username_matches_cn(
         }
     };
     // Split the UID at the @
-    let uid_username_only = uid_str.to_string().split_at(position_of_at).0.to_string();
-    match uid_username_only == cn_str {
+    let uid_username_only = uid.split_at(position_of_at).0;
+    match uid_username_only == cn {
         true => Ok(()),
         false => {
             log::warn!(
                 "[validate_uid_username_matches_cn] UID username \"{}\" does not match the Common Name \"{}\"",
                 uid_username_only,
-                cn_str
+                cn
             );
             Err(ConstraintError::Malformed(Some(
                 "UID username does not match the Common Name".to_string(),
@@ -316,16 +344,8 @@ mod test {
     #[test]
     fn cn_has_to_match_uid_name() {
         init_logger();
-        let cn = Name::from_str("cn=bitfl0wer").unwrap();
-        let uid = Name::from_str("uid=flori@localhost").unwrap();
-        assert!(
-            validate_uid_username_matches_cn(uid.0.first().unwrap(), cn.0.first().unwrap())
-                .is_err()
-        );
-        let cn = Name::from_str("cn=flori").unwrap();
-        assert!(
-            validate_uid_username_matches_cn(uid.0.first().unwrap(), cn.0.first().unwrap()).is_ok()
-        );
+        assert!(validate_uid_username_matches_cn("flori@localhost", "bitfl0wer").is_err());
+        assert!(validate_uid_username_matches_cn("flori@localhost", "flori").is_ok());
         let good_name = Name::from_str(
             "CN=flori,DC=polyphony,DC=chat,UID=flori@polyphony.chat,uniqueIdentifier=client1",
         )
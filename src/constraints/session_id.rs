@@ -6,15 +6,23 @@ use super::*;
 
 impl Constrained for SessionId {
     /// [SessionId] must be longer than 0 and not longer than 32 characters to be deemed valid.
-    fn validate(&self, _target: Option<Target>) -> Result<(), ConstraintError> {
+    fn validate(&self, target: Option<Target>) -> Result<(), ConstraintError> {
+        first_violation(self.validate_all(target))
+    }
+
+    fn validate_all(&self, _target: Option<Target>) -> Vec<ConstraintViolation> {
         if self.len() > Length::new(32) || self.len() == Length::ZERO {
-            return Err(ConstraintError::OutOfBounds {
-                lower: 1,
-                upper: 32,
-                actual: self.len().to_string(),
-                reason: "SessionId too long".to_string(),
-            });
+            return vec![ConstraintViolation::new(
+                ViolationCode::SessionIdLength,
+                "",
+                ConstraintError::OutOfBounds {
+                    lower: 1,
+                    upper: 32,
+                    actual: self.len().to_string(),
+                    reason: "SessionId must be between 1 and 32 characters long".to_string(),
+                },
+            )];
         }
-        Ok(())
+        Vec::new()
     }
 }
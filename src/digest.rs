@@ -0,0 +1,156 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::fmt::{Display, Formatter};
+
+use sha2::Digest as _;
+
+/// The length of the key identifiers computed by [KeyIdentifierDigest], in bytes
+const KEY_IDENTIFIER_LENGTH: usize = 20;
+
+/// A cryptographic hash function. Apart from [KeyIdentifierDigest], this crate does not ship any
+/// hash function implementations; implement this trait for a wrapper around the hash function
+/// crate of your choice.
+pub trait Digest {
+    /// Computes the digest of the given data.
+    fn digest(data: &[u8]) -> Vec<u8>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// The [Digest] used to compute the default [PublicKey::key_identifier()](crate::key::PublicKey::key_identifier())
+/// of all public keys: the leftmost 160 bits of the SHA-256 digest of the data, following method 1
+/// of [RFC 7093, Section 2](https://datatracker.ietf.org/doc/html/rfc7093#section-2).
+pub struct KeyIdentifierDigest;
+
+impl Digest for KeyIdentifierDigest {
+    fn digest(data: &[u8]) -> Vec<u8> {
+        let mut digest = sha2::Sha256::digest(data).to_vec();
+        digest.truncate(KEY_IDENTIFIER_LENGTH);
+        digest
+    }
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// The digest of the DER encoding of a certificate, certificate signing request or public key,
+/// computed using a [Digest] of choice. Fingerprints are stable identifiers, which can be used as
+/// keys in caches or displayed to users for manual comparison.
+///
+/// The [Display] implementation formats the fingerprint as lowercase hexadecimal.
+pub struct Fingerprint(Vec<u8>);
+
+impl Fingerprint {
+    /// Computes the [Fingerprint] of `data` using the [Digest] `D`.
+    pub fn new<D: Digest>(data: &[u8]) -> Self {
+        Self(D::digest(data))
+    }
+
+    /// The raw bytes of the fingerprint.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Formats the fingerprint as lowercase hexadecimal, e.g. `"09af"`.
+    pub fn to_hex(&self) -> String {
+        self.0.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    /// Formats the fingerprint as uppercase hexadecimal, with bytes separated by colons, e.g.
+    /// `"09:AF"`. This is the format commonly used to display certificate fingerprints to users.
+    pub fn to_hex_colon_separated(&self) -> String {
+        self.0
+            .iter()
+            .map(|byte| format!("{:02X}", byte))
+            .collect::<Vec<String>>()
+            .join(":")
+    }
+
+    /// Formats the fingerprint using the standard, padded base64 alphabet defined in
+    /// [RFC 4648, Section 4](https://datatracker.ietf.org/doc/html/rfc4648#section-4).
+    pub fn to_base64(&self) -> String {
+        let mut encoded = String::with_capacity((self.0.len() + 2) / 3 * 4);
+        for chunk in self.0.chunks(3) {
+            let bytes = [
+                chunk[0],
+                chunk.get(1).copied().unwrap_or(0),
+                chunk.get(2).copied().unwrap_or(0),
+            ];
+            let group = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+            for index in 0..4 {
+                if index <= chunk.len() {
+                    let sextet = (group >> (18 - 6 * index)) & 0b11_1111;
+                    encoded.push(BASE64_ALPHABET[sextet as usize] as char);
+                } else {
+                    encoded.push('=');
+                }
+            }
+        }
+        encoded
+    }
+}
+
+impl Display for Fingerprint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl AsRef<[u8]> for Fingerprint {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<Vec<u8>> for Fingerprint {
+    fn from(value: Vec<u8>) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Fingerprint> for Vec<u8> {
+    fn from(value: Fingerprint) -> Self {
+        value.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn display_forms() {
+        // Test vectors from RFC 4648, Section 10
+        for (input, base64) in [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ] {
+            assert_eq!(
+                Fingerprint::from(input.as_bytes().to_vec()).to_base64(),
+                base64
+            );
+        }
+        let fingerprint = Fingerprint::from(vec![0x09, 0xaf, 0xff]);
+        assert_eq!(fingerprint.to_hex(), "09afff");
+        assert_eq!(fingerprint.to_string(), "09afff");
+        assert_eq!(fingerprint.to_hex_colon_separated(), "09:AF:FF");
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn key_identifier_digest() {
+        // The SHA-256 digest of "abc", from FIPS 180-2, Appendix B.1
+        assert_eq!(
+            Fingerprint::new::<KeyIdentifierDigest>(b"abc").to_hex(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a3"
+        );
+    }
+}
@@ -0,0 +1,367 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::fmt::{Debug, Display, Formatter};
+
+use der::asn1::BitString;
+use der::pem::LineEnding;
+use der::{Any, AnyRef, Decode, DecodePem};
+use p256::ecdsa::signature::{Keypair, Signer, Verifier};
+use p256::pkcs8::{DecodePrivateKey, EncodePrivateKey, SecretDocument};
+use rand_core::CryptoRngCore;
+use spki::{AlgorithmIdentifierOwned, ObjectIdentifier, SignatureBitStringEncoding};
+
+use crate::certs::{ensure_algorithm, PublicKeyInfo};
+use crate::errors::{ConversionError, InvalidInput, PublicKeyError, SigningError};
+use crate::key::{PrivateKey, PublicKey};
+use crate::signature::Signature;
+
+pub use p256::NistP256;
+pub use p384::NistP384;
+
+/// The OID of the `id-ecPublicKey` algorithm of elliptic curve public keys, as defined in
+/// [RFC 5480, Section 2.1.1](https://datatracker.ietf.org/doc/html/rfc5480#section-2.1.1)
+pub const OID_EC_PUBLIC_KEY: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.2.1");
+/// The OID of the `ecdsa-with-SHA256` signature algorithm, as defined in
+/// [RFC 5758, Section 3.2](https://datatracker.ietf.org/doc/html/rfc5758#section-3.2)
+pub const OID_ECDSA_WITH_SHA256: ObjectIdentifier =
+    ObjectIdentifier::new_unwrap("1.2.840.10045.4.3.2");
+/// The OID of the `ecdsa-with-SHA384` signature algorithm, as defined in
+/// [RFC 5758, Section 3.2](https://datatracker.ietf.org/doc/html/rfc5758#section-3.2)
+pub const OID_ECDSA_WITH_SHA384: ObjectIdentifier =
+    ObjectIdentifier::new_unwrap("1.2.840.10045.4.3.3");
+/// The OID of the named curve `secp256r1`, also known as P-256
+pub const OID_SECP256R1: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.3.1.7");
+/// The OID of the named curve `secp384r1`, also known as P-384
+pub const OID_SECP384R1: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.132.0.34");
+
+/// An elliptic curve supported by the ECDSA implementations of this module, together with the
+/// hash function used to create signatures. Implemented for [NistP256] with SHA-256 and
+/// [NistP384] with SHA-384.
+pub trait EcdsaCurve: Copy + Debug + PartialEq + Eq + 'static {
+    /// The OID of the signature algorithm, such as [OID_ECDSA_WITH_SHA256]
+    const SIGNATURE_ALGORITHM: ObjectIdentifier;
+    /// The OID of the named curve, which is the parameter of the [OID_EC_PUBLIC_KEY] algorithm
+    const NAMED_CURVE: ObjectIdentifier;
+    /// The signature type of this curve
+    type Signature: Copy + Debug + Display + PartialEq + Eq;
+    /// The verifying (public) key type of this curve
+    type VerifyingKey: Verifier<Self::Signature> + Copy + Debug + PartialEq + Eq;
+    /// The signing (private) key type of this curve
+    type SigningKey: Signer<Self::Signature>
+        + Keypair<VerifyingKey = Self::VerifyingKey>
+        + DecodePrivateKey
+        + EncodePrivateKey
+        + Clone
+        + Debug
+        + PartialEq
+        + Eq;
+
+    /// Generates a new signing key using the given cryptographically secure random number
+    /// generator.
+    fn generate<R: CryptoRngCore + ?Sized>(csprng: &mut R) -> Self::SigningKey;
+    /// Decodes a signature from its ASN.1 DER encoding, as specified in
+    /// [RFC 3279, Section 2.2.3](https://datatracker.ietf.org/doc/html/rfc3279#section-2.2.3).
+    fn signature_from_der(bytes: &[u8]) -> Result<Self::Signature, ConversionError>;
+    /// Encodes a signature using ASN.1 DER.
+    fn signature_to_der(signature: &Self::Signature) -> Vec<u8>;
+    /// Decodes a verifying key from a SEC1 encoded curve point. Fails for the identity point and
+    /// for points which are not on the curve.
+    fn verifying_key_from_sec1(bytes: &[u8]) -> Result<Self::VerifyingKey, ConversionError>;
+    /// Encodes a verifying key as uncompressed SEC1 curve point.
+    fn verifying_key_to_sec1(key: &Self::VerifyingKey) -> Vec<u8>;
+}
+
+impl EcdsaCurve for NistP256 {
+    const SIGNATURE_ALGORITHM: ObjectIdentifier = OID_ECDSA_WITH_SHA256;
+    const NAMED_CURVE: ObjectIdentifier = OID_SECP256R1;
+    type Signature = p256::ecdsa::Signature;
+    type VerifyingKey = p256::ecdsa::VerifyingKey;
+    type SigningKey = p256::ecdsa::SigningKey;
+
+    fn generate<R: CryptoRngCore + ?Sized>(mut csprng: &mut R) -> Self::SigningKey {
+        p256::ecdsa::SigningKey::random(&mut csprng)
+    }
+
+    fn signature_from_der(bytes: &[u8]) -> Result<Self::Signature, ConversionError> {
+        p256::ecdsa::Signature::from_der(bytes)
+            .map_err(|e| InvalidInput::Malformed(e.to_string()).into())
+    }
+
+    fn signature_to_der(signature: &Self::Signature) -> Vec<u8> {
+        signature.to_der().as_bytes().to_vec()
+    }
+
+    fn verifying_key_from_sec1(bytes: &[u8]) -> Result<Self::VerifyingKey, ConversionError> {
+        p256::ecdsa::VerifyingKey::from_sec1_bytes(bytes)
+            .map_err(|e| InvalidInput::Malformed(e.to_string()).into())
+    }
+
+    fn verifying_key_to_sec1(key: &Self::VerifyingKey) -> Vec<u8> {
+        key.to_encoded_point(false).as_bytes().to_vec()
+    }
+}
+
+impl EcdsaCurve for NistP384 {
+    const SIGNATURE_ALGORITHM: ObjectIdentifier = OID_ECDSA_WITH_SHA384;
+    const NAMED_CURVE: ObjectIdentifier = OID_SECP384R1;
+    type Signature = p384::ecdsa::Signature;
+    type VerifyingKey = p384::ecdsa::VerifyingKey;
+    type SigningKey = p384::ecdsa::SigningKey;
+
+    fn generate<R: CryptoRngCore + ?Sized>(mut csprng: &mut R) -> Self::SigningKey {
+        p384::ecdsa::SigningKey::random(&mut csprng)
+    }
+
+    fn signature_from_der(bytes: &[u8]) -> Result<Self::Signature, ConversionError> {
+        p384::ecdsa::Signature::from_der(bytes)
+            .map_err(|e| InvalidInput::Malformed(e.to_string()).into())
+    }
+
+    fn signature_to_der(signature: &Self::Signature) -> Vec<u8> {
+        signature.to_der().as_bytes().to_vec()
+    }
+
+    fn verifying_key_from_sec1(bytes: &[u8]) -> Result<Self::VerifyingKey, ConversionError> {
+        p384::ecdsa::VerifyingKey::from_sec1_bytes(bytes)
+            .map_err(|e| InvalidInput::Malformed(e.to_string()).into())
+    }
+
+    fn verifying_key_to_sec1(key: &Self::VerifyingKey) -> Vec<u8> {
+        key.to_encoded_point(false).as_bytes().to_vec()
+    }
+}
+
+/// An ECDSA signature using P-256 and SHA-256
+pub type P256Signature = EcdsaSignature<NistP256>;
+/// An ECDSA private key for P-256
+pub type P256PrivateKey = EcdsaPrivateKey<NistP256>;
+/// An ECDSA public key for P-256
+pub type P256PublicKey = EcdsaPublicKey<NistP256>;
+/// An ECDSA signature using P-384 and SHA-384
+pub type P384Signature = EcdsaSignature<NistP384>;
+/// An ECDSA private key for P-384
+pub type P384PrivateKey = EcdsaPrivateKey<NistP384>;
+/// An ECDSA public key for P-384
+pub type P384PublicKey = EcdsaPublicKey<NistP384>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// An ECDSA signature on the curve `C`. In certificates, ECDSA signatures are encoded as the ASN.1
+/// DER encoding of the `Ecdsa-Sig-Value` structure, which is also the encoding used by
+/// [Signature::to_bytes()] and [Signature::try_from_bytes()].
+pub struct EcdsaSignature<C: EcdsaCurve> {
+    signature: C::Signature,
+}
+
+impl<C: EcdsaCurve> EcdsaSignature<C> {
+    /// Wraps a signature of the curve `C`.
+    pub fn new(signature: C::Signature) -> Self {
+        Self { signature }
+    }
+
+    /// The [AlgorithmIdentifierOwned] of the ECDSA signature algorithm of `C`
+    fn algorithm() -> AlgorithmIdentifierOwned {
+        AlgorithmIdentifierOwned {
+            oid: C::SIGNATURE_ALGORITHM,
+            parameters: None,
+        }
+    }
+}
+
+impl<C: EcdsaCurve> Display for EcdsaSignature<C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.signature)
+    }
+}
+
+impl<C: EcdsaCurve> SignatureBitStringEncoding for EcdsaSignature<C> {
+    fn to_bitstring(&self) -> der::Result<BitString> {
+        BitString::from_bytes(&C::signature_to_der(&self.signature))
+    }
+}
+
+impl<C: EcdsaCurve> Signature for EcdsaSignature<C> {
+    type Signature = C::Signature;
+
+    fn as_signature(&self) -> &Self::Signature {
+        &self.signature
+    }
+
+    /// The [AlgorithmIdentifierOwned] of the ECDSA signature algorithm of `C`. As required by
+    /// [RFC 5758, Section 3.2](https://datatracker.ietf.org/doc/html/rfc5758#section-3.2), its
+    /// parameters are absent.
+    fn algorithm_identifier() -> Option<AlgorithmIdentifierOwned> {
+        Some(Self::algorithm())
+    }
+
+    fn signature_algorithm(&self) -> AlgorithmIdentifierOwned {
+        Self::algorithm()
+    }
+
+    /// Decodes an [EcdsaSignature] from its DER encoding.
+    fn try_from_bytes(signature: &[u8]) -> Result<Self, ConversionError> {
+        Ok(Self::new(C::signature_from_der(signature)?))
+    }
+
+    /// Encodes this signature using DER.
+    fn to_bytes(&self) -> Vec<u8> {
+        C::signature_to_der(&self.signature)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An ECDSA private key on the curve `C`, together with its [EcdsaPublicKey]. Signatures are
+/// created deterministically, as described in
+/// [RFC 6979](https://datatracker.ietf.org/doc/html/rfc6979). The key material is zeroized when
+/// the private key is dropped, and is not printed by its [Debug] implementation.
+pub struct EcdsaPrivateKey<C: EcdsaCurve> {
+    public_key: EcdsaPublicKey<C>,
+    key: C::SigningKey,
+}
+
+impl<C: EcdsaCurve> EcdsaPrivateKey<C> {
+    /// Generates a new private key using the given cryptographically secure random number
+    /// generator, e.g. `rand::rngs::OsRng`.
+    pub fn generate<R: CryptoRngCore + ?Sized>(csprng: &mut R) -> Self {
+        Self::new(C::generate(csprng))
+    }
+
+    /// Wraps a signing key of the curve `C`.
+    pub fn new(key: C::SigningKey) -> Self {
+        Self {
+            public_key: EcdsaPublicKey {
+                key: key.verifying_key(),
+            },
+            key,
+        }
+    }
+
+    /// Decodes a private key from a DER encoded PKCS #8 `PrivateKeyInfo` structure, as specified
+    /// in [RFC 5915](https://datatracker.ietf.org/doc/html/rfc5915). Fails, if the key belongs to
+    /// a different curve.
+    pub fn from_pkcs8_der(bytes: &[u8]) -> Result<Self, ConversionError> {
+        C::SigningKey::from_pkcs8_der(bytes)
+            .map(Self::new)
+            .map_err(|e| InvalidInput::Malformed(e.to_string()).into())
+    }
+
+    /// Decodes a private key from a PEM encoded PKCS #8 structure, labelled "PRIVATE KEY".
+    pub fn from_pkcs8_pem(pem: &str) -> Result<Self, ConversionError> {
+        C::SigningKey::from_pkcs8_pem(pem)
+            .map(Self::new)
+            .map_err(|e| InvalidInput::Malformed(e.to_string()).into())
+    }
+
+    /// Encodes this private key as DER encoded PKCS #8 `PrivateKeyInfo` structure. The returned
+    /// document is zeroized when dropped.
+    pub fn to_pkcs8_der(&self) -> Result<SecretDocument, ConversionError> {
+        self.key
+            .to_pkcs8_der()
+            .map_err(|e| InvalidInput::Malformed(e.to_string()).into())
+    }
+
+    /// The underlying signing key.
+    pub fn signing_key(&self) -> &C::SigningKey {
+        &self.key
+    }
+}
+
+impl<C: EcdsaCurve> PrivateKey<EcdsaSignature<C>> for EcdsaPrivateKey<C> {
+    type PublicKey = EcdsaPublicKey<C>;
+
+    fn pubkey(&self) -> &Self::PublicKey {
+        &self.public_key
+    }
+
+    fn sign(&self, data: &[u8]) -> Result<EcdsaSignature<C>, SigningError> {
+        self.key
+            .try_sign(data)
+            .map(EcdsaSignature::new)
+            .map_err(|e| SigningError::Failed(e.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// An ECDSA public key on the curve `C`. Its [PublicKeyInfo] uses the `id-ecPublicKey` algorithm,
+/// with the named curve of `C` as parameter.
+pub struct EcdsaPublicKey<C: EcdsaCurve> {
+    key: C::VerifyingKey,
+}
+
+impl<C: EcdsaCurve> EcdsaPublicKey<C> {
+    /// Wraps a verifying key of the curve `C`.
+    pub fn new(key: C::VerifyingKey) -> Self {
+        Self { key }
+    }
+
+    /// The [AlgorithmIdentifierOwned] of public keys on the curve `C`, as specified in
+    /// [RFC 5480, Section 2.1.1](https://datatracker.ietf.org/doc/html/rfc5480#section-2.1.1).
+    pub fn public_key_algorithm() -> AlgorithmIdentifierOwned {
+        AlgorithmIdentifierOwned {
+            oid: OID_EC_PUBLIC_KEY,
+            parameters: Some(Any::from(AnyRef::from(&C::NAMED_CURVE))),
+        }
+    }
+
+    /// Decodes a public key from a DER encoded `SubjectPublicKeyInfo` structure.
+    pub fn from_public_key_der(bytes: &[u8]) -> Result<Self, ConversionError> {
+        Self::try_from_public_key_info(spki::SubjectPublicKeyInfoOwned::from_der(bytes)?.into())
+    }
+
+    /// Decodes a public key from a PEM encoded `SubjectPublicKeyInfo` structure, labelled
+    /// "PUBLIC KEY".
+    pub fn from_public_key_pem(pem: &str) -> Result<Self, ConversionError> {
+        Self::try_from_public_key_info(spki::SubjectPublicKeyInfoOwned::from_pem(pem)?.into())
+    }
+
+    /// Encodes this public key as DER encoded `SubjectPublicKeyInfo` structure.
+    pub fn to_public_key_der(&self) -> Result<Vec<u8>, ConversionError> {
+        self.public_key_info().to_der()
+    }
+
+    /// Encodes this public key as PEM encoded `SubjectPublicKeyInfo` structure.
+    pub fn to_public_key_pem(&self, line_ending: LineEnding) -> Result<String, ConversionError> {
+        self.public_key_info().to_pem(line_ending)
+    }
+
+    /// The underlying verifying key.
+    pub fn verifying_key(&self) -> &C::VerifyingKey {
+        &self.key
+    }
+}
+
+impl<C: EcdsaCurve> PublicKey<EcdsaSignature<C>> for EcdsaPublicKey<C> {
+    fn verify_signature(
+        &self,
+        signature: &EcdsaSignature<C>,
+        data: &[u8],
+    ) -> Result<(), PublicKeyError> {
+        self.key
+            .verify(data, &signature.signature)
+            .map_err(|_| PublicKeyError::BadSignature)
+    }
+
+    fn public_key_info(&self) -> PublicKeyInfo {
+        PublicKeyInfo {
+            algorithm: Self::public_key_algorithm(),
+            public_key_bitstring: BitString::from_bytes(&C::verifying_key_to_sec1(&self.key))
+                .expect("Whole octets are a valid BIT STRING"),
+        }
+    }
+
+    /// Decodes an ECDSA public key. Fails, if the algorithm is not `id-ecPublicKey` with the named
+    /// curve of `C` as parameter, or if the key is not a valid point on that curve.
+    fn try_from_public_key_info(public_key_info: PublicKeyInfo) -> Result<Self, ConversionError> {
+        ensure_algorithm(&Self::public_key_algorithm(), &public_key_info.algorithm)?;
+        let bytes = public_key_info
+            .public_key_bitstring
+            .as_bytes()
+            .ok_or_else(|| {
+                InvalidInput::Malformed(
+                    "The ECDSA public key is not a whole number of octets".to_string(),
+                )
+            })?;
+        Ok(Self::new(C::verifying_key_from_sec1(bytes)?))
+    }
+}
@@ -0,0 +1,257 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::fmt::{Display, Formatter};
+
+use der::asn1::BitString;
+use der::pem::LineEnding;
+use der::{Decode, DecodePem};
+use ed25519_dalek::pkcs8::{DecodePrivateKey, EncodePrivateKey, SecretDocument};
+use ed25519_dalek::{Signer, SigningKey, VerifyingKey};
+use rand_core::CryptoRngCore;
+use spki::{AlgorithmIdentifierOwned, ObjectIdentifier, SignatureBitStringEncoding};
+
+use crate::certs::{ensure_algorithm, PublicKeyInfo};
+use crate::errors::{ConversionError, InvalidInput, PublicKeyError, SigningError};
+use crate::key::{PrivateKey, PublicKey};
+use crate::signature::Signature;
+
+/// The OID of the Ed25519 signature algorithm, as defined in
+/// [RFC 8410, Section 3](https://datatracker.ietf.org/doc/html/rfc8410#section-3)
+pub const OID_ED25519: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.101.112");
+
+/// The length of an Ed25519 signature in bytes
+const SIGNATURE_LENGTH: usize = ed25519_dalek::SIGNATURE_LENGTH;
+/// The length of an Ed25519 public key in bytes
+const PUBLIC_KEY_LENGTH: usize = ed25519_dalek::PUBLIC_KEY_LENGTH;
+
+/// The Ed25519 [AlgorithmIdentifierOwned], whose parameters are absent
+fn ed25519_algorithm() -> AlgorithmIdentifierOwned {
+    AlgorithmIdentifierOwned {
+        oid: OID_ED25519,
+        parameters: None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// An Ed25519 signature, as specified in [RFC 8032](https://datatracker.ietf.org/doc/html/rfc8032).
+pub struct Ed25519Signature {
+    signature: ed25519_dalek::Signature,
+}
+
+impl From<ed25519_dalek::Signature> for Ed25519Signature {
+    fn from(signature: ed25519_dalek::Signature) -> Self {
+        Self { signature }
+    }
+}
+
+impl Display for Ed25519Signature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.signature)
+    }
+}
+
+impl SignatureBitStringEncoding for Ed25519Signature {
+    fn to_bitstring(&self) -> der::Result<BitString> {
+        BitString::from_bytes(&self.signature.to_bytes())
+    }
+}
+
+impl Signature for Ed25519Signature {
+    type Signature = ed25519_dalek::Signature;
+
+    fn as_signature(&self) -> &Self::Signature {
+        &self.signature
+    }
+
+    /// The Ed25519 [AlgorithmIdentifierOwned]. As required by
+    /// [RFC 8410, Section 3](https://datatracker.ietf.org/doc/html/rfc8410#section-3), its
+    /// parameters are absent.
+    fn algorithm_identifier() -> Option<AlgorithmIdentifierOwned> {
+        Some(ed25519_algorithm())
+    }
+
+    fn signature_algorithm(&self) -> AlgorithmIdentifierOwned {
+        ed25519_algorithm()
+    }
+
+    /// Creates an [Ed25519Signature] from exactly 64 bytes.
+    fn try_from_bytes(signature: &[u8]) -> Result<Self, ConversionError> {
+        let signature: [u8; SIGNATURE_LENGTH] =
+            signature.try_into().map_err(|_| InvalidInput::Length {
+                min_length: SIGNATURE_LENGTH,
+                max_length: SIGNATURE_LENGTH,
+                actual_length: signature.len().to_string(),
+            })?;
+        Ok(Self {
+            signature: ed25519_dalek::Signature::from_bytes(&signature),
+        })
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.signature.to_bytes().to_vec()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An Ed25519 private key, together with its [Ed25519PublicKey]. The key material is zeroized
+/// when the private key is dropped, and is not printed by its [Debug] implementation.
+pub struct Ed25519PrivateKey {
+    public_key: Ed25519PublicKey,
+    key: SigningKey,
+}
+
+impl Ed25519PrivateKey {
+    /// Generates a new private key using the given cryptographically secure random number
+    /// generator, e.g. `rand::rngs::OsRng`.
+    pub fn generate<R: CryptoRngCore + ?Sized>(csprng: &mut R) -> Self {
+        Self::from(SigningKey::generate(csprng))
+    }
+
+    /// Decodes a private key from a DER encoded PKCS #8 `PrivateKeyInfo` or `OneAsymmetricKey`
+    /// structure, as specified in [RFC 8410, Section 7](https://datatracker.ietf.org/doc/html/rfc8410#section-7).
+    pub fn from_pkcs8_der(bytes: &[u8]) -> Result<Self, ConversionError> {
+        SigningKey::from_pkcs8_der(bytes)
+            .map(Self::from)
+            .map_err(|e| InvalidInput::Malformed(e.to_string()).into())
+    }
+
+    /// Decodes a private key from a PEM encoded PKCS #8 structure, labelled "PRIVATE KEY".
+    pub fn from_pkcs8_pem(pem: &str) -> Result<Self, ConversionError> {
+        SigningKey::from_pkcs8_pem(pem)
+            .map(Self::from)
+            .map_err(|e| InvalidInput::Malformed(e.to_string()).into())
+    }
+
+    /// Encodes this private key as DER encoded PKCS #8 `OneAsymmetricKey` structure, including
+    /// the public key. The returned document is zeroized when dropped.
+    pub fn to_pkcs8_der(&self) -> Result<SecretDocument, ConversionError> {
+        self.key
+            .to_pkcs8_der()
+            .map_err(|e| InvalidInput::Malformed(e.to_string()).into())
+    }
+
+    /// The underlying [SigningKey].
+    pub fn signing_key(&self) -> &SigningKey {
+        &self.key
+    }
+}
+
+impl From<SigningKey> for Ed25519PrivateKey {
+    fn from(key: SigningKey) -> Self {
+        Self {
+            public_key: Ed25519PublicKey {
+                key: key.verifying_key(),
+            },
+            key,
+        }
+    }
+}
+
+impl PrivateKey<Ed25519Signature> for Ed25519PrivateKey {
+    type PublicKey = Ed25519PublicKey;
+
+    fn pubkey(&self) -> &Self::PublicKey {
+        &self.public_key
+    }
+
+    fn sign(&self, data: &[u8]) -> Result<Ed25519Signature, SigningError> {
+        self.key
+            .try_sign(data)
+            .map(Ed25519Signature::from)
+            .map_err(|e| SigningError::Failed(e.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// An Ed25519 public key. Weak public keys, which are of small order, are rejected when decoding,
+/// and signatures are verified using the strict verification rules of
+/// [VerifyingKey::verify_strict()].
+pub struct Ed25519PublicKey {
+    key: VerifyingKey,
+}
+
+impl Ed25519PublicKey {
+    /// Creates a new [Ed25519PublicKey] from a [VerifyingKey]. Fails, if the key is weak.
+    pub fn new(key: VerifyingKey) -> Result<Self, ConversionError> {
+        if key.is_weak() {
+            log::debug!("[Ed25519PublicKey::new()] Rejecting weak public key");
+            return Err(InvalidInput::Malformed(
+                "The Ed25519 public key is of small order".to_string(),
+            )
+            .into());
+        }
+        Ok(Self { key })
+    }
+
+    /// Decodes a public key from a DER encoded `SubjectPublicKeyInfo` structure.
+    pub fn from_public_key_der(bytes: &[u8]) -> Result<Self, ConversionError> {
+        Self::try_from_public_key_info(spki::SubjectPublicKeyInfoOwned::from_der(bytes)?.into())
+    }
+
+    /// Decodes a public key from a PEM encoded `SubjectPublicKeyInfo` structure, labelled
+    /// "PUBLIC KEY".
+    pub fn from_public_key_pem(pem: &str) -> Result<Self, ConversionError> {
+        Self::try_from_public_key_info(spki::SubjectPublicKeyInfoOwned::from_pem(pem)?.into())
+    }
+
+    /// Encodes this public key as DER encoded `SubjectPublicKeyInfo` structure.
+    pub fn to_public_key_der(&self) -> Result<Vec<u8>, ConversionError> {
+        self.public_key_info().to_der()
+    }
+
+    /// Encodes this public key as PEM encoded `SubjectPublicKeyInfo` structure.
+    pub fn to_public_key_pem(&self, line_ending: LineEnding) -> Result<String, ConversionError> {
+        self.public_key_info().to_pem(line_ending)
+    }
+
+    /// The underlying [VerifyingKey].
+    pub fn verifying_key(&self) -> &VerifyingKey {
+        &self.key
+    }
+}
+
+impl PublicKey<Ed25519Signature> for Ed25519PublicKey {
+    fn verify_signature(
+        &self,
+        signature: &Ed25519Signature,
+        data: &[u8],
+    ) -> Result<(), PublicKeyError> {
+        self.key
+            .verify_strict(data, &signature.signature)
+            .map_err(|_| PublicKeyError::BadSignature)
+    }
+
+    fn public_key_info(&self) -> PublicKeyInfo {
+        PublicKeyInfo {
+            algorithm: ed25519_algorithm(),
+            public_key_bitstring: BitString::new(0, self.key.to_bytes())
+                .expect("32 bytes with no unused bits are a valid BIT STRING"),
+        }
+    }
+
+    /// Decodes an Ed25519 public key. Fails, if the algorithm is not Ed25519 with absent
+    /// parameters, if the key is not exactly 32 bytes long, or if it is not a valid, strong
+    /// public key.
+    fn try_from_public_key_info(public_key_info: PublicKeyInfo) -> Result<Self, ConversionError> {
+        ensure_algorithm(&ed25519_algorithm(), &public_key_info.algorithm)?;
+        let bytes = public_key_info
+            .public_key_bitstring
+            .as_bytes()
+            .ok_or_else(|| {
+                InvalidInput::Malformed(
+                    "The Ed25519 public key is not a whole number of octets".to_string(),
+                )
+            })?;
+        let bytes: [u8; PUBLIC_KEY_LENGTH] =
+            bytes.try_into().map_err(|_| InvalidInput::Length {
+                min_length: PUBLIC_KEY_LENGTH,
+                max_length: PUBLIC_KEY_LENGTH,
+                actual_length: bytes.len().to_string(),
+            })?;
+        let key =
+            VerifyingKey::from_bytes(&bytes).map_err(|e| InvalidInput::Malformed(e.to_string()))?;
+        Self::new(key)
+    }
+}
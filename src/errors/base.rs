@@ -42,3 +42,167 @@ pub enum InvalidInput {
         actual_length: String,
     },
 }
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[non_exhaustive]
+/// Machine-readable identifiers of the rules checked by [crate::Constrained] validation. Use
+/// [ViolationCode::as_str()] to obtain a stable string representation, e.g. for use in an API
+/// response.
+pub enum ViolationCode {
+    /// A [Name](x509_cert::name::Name) contains more than one "common name" attribute
+    TooManyCommonNames,
+    /// A [Name](x509_cert::name::Name) does not contain a "domain component" attribute
+    MissingDomainComponent,
+    /// The "UID" attribute is not a valid federation ID
+    InvalidUid,
+    /// An actor [Name](x509_cert::name::Name) does not contain a "UID" attribute
+    MissingUid,
+    /// A [Name](x509_cert::name::Name) contains more than one "UID" attribute
+    TooManyUids,
+    /// A home server [Name](x509_cert::name::Name) contains a "UID" attribute
+    UnexpectedUid,
+    /// The domain of the "UID" attribute does not match the "domain component" attributes
+    UidDomainMismatch,
+    /// The local name of the "UID" attribute does not match the "common name" attribute
+    UidCommonNameMismatch,
+    /// The "uniqueIdentifier" attribute is not a valid [SessionId](crate::certs::SessionId)
+    InvalidUniqueIdentifier,
+    /// An actor [Name](x509_cert::name::Name) does not contain a "uniqueIdentifier" attribute
+    MissingUniqueIdentifier,
+    /// A [Name](x509_cert::name::Name) contains more than one "uniqueIdentifier" attribute
+    TooManyUniqueIdentifiers,
+    /// A home server [Name](x509_cert::name::Name) contains a "uniqueIdentifier" attribute
+    UnexpectedUniqueIdentifier,
+    /// A [SessionId](crate::certs::SessionId) is empty or longer than 32 characters
+    SessionIdLength,
+    /// The extended key usages do not permit the usage required for the target
+    MissingExtendedKeyUsage,
+    /// A non-CA is missing both the "DigitalSignature" and "ContentCommitment" key usages
+    MissingSigningKeyUsage,
+    /// Both the "DigitalSignature" and "ContentCommitment" key usages are present
+    ConflictingSigningKeyUsages,
+    /// The "KeyCertSign" key usage is present, but the "CA" flag is not set
+    KeyCertSignWithoutCa,
+    /// The "CA" flag is set, but the "KeyCertSign" key usage is missing
+    CaWithoutKeyCertSign,
+    /// The "EncipherOnly" or "DecipherOnly" key usage is present without "KeyAgreement"
+    EncipherDecipherWithoutKeyAgreement,
+    /// An actor has the "CA" flag set
+    ActorIsCa,
+    /// A home server does not have the "CA" flag set
+    HomeServerIsNotCa,
+    /// The serial number is not positive or too long
+    InvalidSerialNumber,
+    /// The domain components of the issuer and the subject differ
+    IssuerSubjectDomainMismatch,
+    /// The signature does not match the signed data
+    SignatureMismatch,
+    /// The validity period is longer than permitted by a
+    /// [ValidationPolicy](crate::certs::policy::ValidationPolicy)
+    LifetimeExceeded,
+    /// A [ValidationPolicy](crate::certs::policy::ValidationPolicy) requires the
+    /// "ContentCommitment" key usage, which is missing
+    ContentCommitmentRequired,
+    /// A [Name](x509_cert::name::Name) contains more "domain component" attributes than permitted
+    /// by a [ValidationPolicy](crate::certs::policy::ValidationPolicy)
+    TooManyDomainComponents,
+    /// A [Name](x509_cert::name::Name) contains an attribute which is not described by the
+    /// polyproto specification, and forbidden by a
+    /// [ValidationPolicy](crate::certs::policy::ValidationPolicy)
+    UnexpectedAttribute,
+    /// A signature or public key algorithm is not accepted by a
+    /// [ValidationPolicy](crate::certs::policy::ValidationPolicy)
+    AlgorithmNotAllowed,
+    /// A rule without a dedicated code has been violated
+    Unspecified,
+}
+
+impl ViolationCode {
+    /// The snake case string representation of this code, e.g. `"too_many_common_names"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ViolationCode::TooManyCommonNames => "too_many_common_names",
+            ViolationCode::MissingDomainComponent => "missing_domain_component",
+            ViolationCode::InvalidUid => "invalid_uid",
+            ViolationCode::MissingUid => "missing_uid",
+            ViolationCode::TooManyUids => "too_many_uids",
+            ViolationCode::UnexpectedUid => "unexpected_uid",
+            ViolationCode::UidDomainMismatch => "uid_domain_mismatch",
+            ViolationCode::UidCommonNameMismatch => "uid_common_name_mismatch",
+            ViolationCode::InvalidUniqueIdentifier => "invalid_unique_identifier",
+            ViolationCode::MissingUniqueIdentifier => "missing_unique_identifier",
+            ViolationCode::TooManyUniqueIdentifiers => "too_many_unique_identifiers",
+            ViolationCode::UnexpectedUniqueIdentifier => "unexpected_unique_identifier",
+            ViolationCode::SessionIdLength => "session_id_length",
+            ViolationCode::MissingExtendedKeyUsage => "missing_extended_key_usage",
+            ViolationCode::MissingSigningKeyUsage => "missing_signing_key_usage",
+            ViolationCode::ConflictingSigningKeyUsages => "conflicting_signing_key_usages",
+            ViolationCode::KeyCertSignWithoutCa => "key_cert_sign_without_ca",
+            ViolationCode::CaWithoutKeyCertSign => "ca_without_key_cert_sign",
+            ViolationCode::EncipherDecipherWithoutKeyAgreement => {
+                "encipher_decipher_without_key_agreement"
+            }
+            ViolationCode::ActorIsCa => "actor_is_ca",
+            ViolationCode::HomeServerIsNotCa => "home_server_is_not_ca",
+            ViolationCode::InvalidSerialNumber => "invalid_serial_number",
+            ViolationCode::IssuerSubjectDomainMismatch => "issuer_subject_domain_mismatch",
+            ViolationCode::SignatureMismatch => "signature_mismatch",
+            ViolationCode::LifetimeExceeded => "lifetime_exceeded",
+            ViolationCode::ContentCommitmentRequired => "content_commitment_required",
+            ViolationCode::TooManyDomainComponents => "too_many_domain_components",
+            ViolationCode::UnexpectedAttribute => "unexpected_attribute",
+            ViolationCode::AlgorithmNotAllowed => "algorithm_not_allowed",
+            ViolationCode::Unspecified => "unspecified",
+        }
+    }
+}
+
+impl std::fmt::Display for ViolationCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Clone)]
+#[error("{component} violates \"{code}\": {rule}")]
+/// A single violated constraint, as returned by [crate::Constrained::validate_all()].
+pub struct ConstraintViolation {
+    /// Which rule has been violated
+    pub code: ViolationCode,
+    /// Dot-separated path of the offending component, relative to the validated value, e.g.
+    /// `subject.uid` or `capabilities.key_usage`. Empty, if the validated value as a whole is
+    /// offending.
+    pub component: String,
+    /// Human-readable description of the violated rule
+    pub rule: String,
+    /// The error [crate::Constrained::validate()] returns for this violation
+    pub error: ConstraintError,
+}
+
+impl ConstraintViolation {
+    /// Creates a new [ConstraintViolation], describing the violated rule using the message of
+    /// `error`.
+    pub fn new(code: ViolationCode, component: &str, error: ConstraintError) -> Self {
+        let rule = match &error {
+            ConstraintError::Malformed(Some(message)) => message.clone(),
+            ConstraintError::OutOfBounds { reason, .. } => reason.clone(),
+            other => other.to_string(),
+        };
+        Self {
+            code,
+            component: component.to_string(),
+            rule,
+            error,
+        }
+    }
+
+    /// Prepends `parent` to the path of the offending component, for violations found while
+    /// validating a component of a larger value.
+    pub(crate) fn within(mut self, parent: &str) -> Self {
+        self.component = match self.component.is_empty() {
+            true => parent.to_string(),
+            false => format!("{}.{}", parent, self.component),
+        };
+        self
+    }
+}
@@ -19,6 +19,87 @@ pub enum InvalidCert {
     #[error("The validity period of the certificate is invalid, or the certificate is expired")]
     /// The certificate is expired or has an invalid validity period
     InvalidValidity,
+    #[error("The certificate has been revoked by its issuer")]
+    /// The certificate is listed in a certificate revocation list of its issuer
+    Revoked,
+    #[error("Expected algorithm {expected}, found algorithm {found}")]
+    /// A signature or public key is declared to use a different algorithm than expected, for
+    /// example when the signature algorithm of a certificate does not match the
+    /// [Signature](crate::signature::Signature) type it is decoded as. Algorithms with equal OIDs
+    /// but differing parameters are mismatching as well.
+    AlgorithmMismatch {
+        /// The OID of the expected algorithm
+        expected: ObjectIdentifier,
+        /// The OID of the algorithm found in the signature or public key
+        found: ObjectIdentifier,
+    },
+    #[error("The algorithm {oid} is not supported")]
+    /// A signature or public key uses an algorithm which is not supported, e.g. because it has
+    /// not been registered using [register_algorithm()](crate::any::register_algorithm())
+    UnsupportedAlgorithm {
+        /// The OID of the unsupported algorithm
+        oid: ObjectIdentifier,
+    },
+}
+
+#[derive(Error, Debug, PartialEq, Clone)]
+/// Errors that can occur when verifying a chain of certificates. The `index` of each variant
+/// refers to the position of the offending certificate in the chain, where index `0` is the leaf
+/// (actor) certificate and index `1` is the certificate of its issuer.
+pub enum InvalidChain {
+    #[error("The certificate chain does not contain any issuer certificates")]
+    /// No issuer certificates were supplied
+    MissingIssuer,
+    #[error("Certificate {index} of the chain is invalid: {source}")]
+    /// A certificate of the chain is invalid on its own
+    InvalidCert {
+        /// Position of the offending certificate in the chain
+        index: usize,
+        /// The reason why the certificate is invalid
+        source: InvalidCert,
+    },
+    #[error("The issuer of certificate {index} does not match the subject of the next certificate in the chain")]
+    /// The issuer [Name](x509_cert::name::Name) of a certificate is not equal to the subject of
+    /// the next certificate in the chain
+    IssuerMismatch {
+        /// Position of the offending certificate in the chain
+        index: usize,
+    },
+    #[error("The AuthorityKeyIdentifier of certificate {index} does not match the SubjectKeyIdentifier of the next certificate in the chain")]
+    /// The AuthorityKeyIdentifier of a certificate does not match the SubjectKeyIdentifier of the
+    /// next certificate in the chain, meaning that the certificate was issued by a different key
+    KeyIdentifierMismatch {
+        /// Position of the offending certificate in the chain
+        index: usize,
+    },
+    #[error("The validity period of certificate {index} is not contained in the validity period of its issuer")]
+    /// The validity period of a certificate exceeds the validity period of its issuer
+    ValidityNotNested {
+        /// Position of the offending certificate in the chain
+        index: usize,
+    },
+    #[error("Certificate {index} is not allowed to issue certificates, as it is not a CA")]
+    /// An issuer certificate does not have the "CA" flag of its BasicConstraints set to `true`
+    NotCa {
+        /// Position of the offending certificate in the chain
+        index: usize,
+    },
+    #[error("Certificate {index} is missing the \"KeyCertSign\" key usage")]
+    /// An issuer certificate is missing the "KeyCertSign" key usage
+    MissingKeyCertSign {
+        /// Position of the offending certificate in the chain
+        index: usize,
+    },
+    #[error("Certificate {index} does not allow a path length of {actual}, the maximum is {max}")]
+    /// The path length constraint of an issuer certificate is violated
+    PathLengthExceeded {
+        /// Position of the offending certificate in the chain
+        index: usize,
+        /// The maximum path length, as specified by the BasicConstraints of the certificate
+        max: u64,
+        /// The actual amount of intermediate certificates following the certificate
+        actual: u64,
+    },
 }
 
 #[derive(Error, Debug, PartialEq, Hash, Clone, Copy)]
@@ -32,6 +113,23 @@ pub enum PublicKeyError {
     BadPublicKeyInfo,
 }
 
+#[derive(Error, Debug, PartialEq, Clone)]
+/// Errors that can occur when creating a [Signature](crate::signature::Signature) using a
+/// [PrivateKey](crate::key::PrivateKey) or an [AsyncPrivateKey](crate::key::AsyncPrivateKey)
+pub enum SigningError {
+    #[error("The signer is unavailable: {0}")]
+    /// The signer holding the private key cannot be reached, e.g. because the process, device or
+    /// service holding the key is not available
+    Unavailable(String),
+    #[error("The signer refused to sign the data: {0}")]
+    /// The signer is available, but refused to sign the data, e.g. because the key is disabled or
+    /// access to it has been denied
+    Refused(String),
+    #[error("The signer failed to create a signature: {0}")]
+    /// The signer encountered an error while creating the signature
+    Failed(String),
+}
+
 #[derive(Error, Debug, PartialEq, Clone)]
 /// Errors that can occur when converting between types
 pub enum ConversionError {
@@ -56,7 +154,118 @@ pub enum ConversionError {
     #[error(transparent)]
     /// The source or target certificate is invalid
     InvalidCert(#[from] InvalidCert),
+    #[error(transparent)]
+    /// The target type could not be signed
+    SigningError(#[from] SigningError),
 }
+#[derive(Error, Debug, PartialEq, Clone)]
+/// Errors that can occur when building an [IdCsr](crate::certs::idcsr::IdCsr) or
+/// [IdCert](crate::certs::idcert::IdCert) using one of the builders in
+/// [crate::certs::builder]
+pub enum BuilderError {
+    #[error("The required field \"{field}\" has not been set")]
+    /// A component required for the [Target](crate::certs::Target) has not been supplied
+    MissingField {
+        /// The name of the missing field
+        field: &'static str,
+    },
+    #[error("The field \"{field}\" must not be set for this target")]
+    /// A component has been supplied which is not allowed for the
+    /// [Target](crate::certs::Target)
+    UnexpectedField {
+        /// The name of the unexpected field
+        field: &'static str,
+    },
+    #[error("\"{0}\" is not a valid home server domain")]
+    /// The home server domain is empty or contains invalid labels
+    InvalidHomeServerDomain(String),
+    #[error("The domain of federation ID \"{federation_id}\" does not match the home server domain \"{home_server_domain}\"")]
+    /// The domain of the federation ID differs from the explicitly supplied home server domain
+    DomainMismatch {
+        /// The supplied federation ID
+        federation_id: String,
+        /// The supplied home server domain
+        home_server_domain: String,
+    },
+    #[error("The validity period cannot be represented as a certificate validity")]
+    /// The validity period overflows or cannot be encoded as X.509 time
+    InvalidValidity,
+    #[error(transparent)]
+    /// The supplied components do not pass polyproto constraint validation
+    ConstraintError(#[from] ConstraintError),
+    #[error(transparent)]
+    /// The supplied components could not be converted into the target type
+    ConversionError(#[from] ConversionError),
+    #[error(transparent)]
+    /// The built certificate does not pass verification
+    InvalidCert(#[from] InvalidCert),
+    #[error(transparent)]
+    /// The built certificate could not be signed
+    SigningError(#[from] SigningError),
+}
+
+#[derive(Error, Debug, PartialEq, Clone)]
+/// Errors that can occur when allocating a serial number using a
+/// [SerialNumberGenerator](crate::certs::serial_number::SerialNumberGenerator)
+pub enum SerialNumberError {
+    #[error("The serial number generator has run out of serial numbers")]
+    /// All serial numbers which the generator can produce have been issued
+    Exhausted,
+    #[error("Failed to gather randomness for a serial number: {0}")]
+    /// The random number generator of the operating system failed
+    Randomness(String),
+    #[error("Failed to encode the serial number: {0}")]
+    /// The serial number could not be encoded as an ASN.1 integer
+    DerError(der::Error),
+}
+
+impl From<der::Error> for SerialNumberError {
+    fn from(value: der::Error) -> Self {
+        Self::DerError(value)
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Clone)]
+/// Errors that can occur when parsing or writing a [PemBundle](crate::certs::bundle::PemBundle).
+/// The `index` of each variant refers to the position of the offending block in the bundle,
+/// starting at `0`.
+pub enum PemBundleError {
+    #[error("PEM block {index} is not terminated by an \"-----END {label}-----\" line")]
+    /// A block was opened by a "BEGIN" line, but not closed by a matching "END" line
+    Unterminated {
+        /// Position of the offending block in the bundle
+        index: usize,
+        /// The label of the "BEGIN" line
+        label: String,
+    },
+    #[error("PEM block {index} has the unsupported label \"{label}\"")]
+    /// The label of a block does not denote a type which can be contained in a bundle
+    UnsupportedLabel {
+        /// Position of the offending block in the bundle
+        index: usize,
+        /// The label of the block
+        label: String,
+    },
+    #[error("PEM block {index} is labelled \"{found}\", expected \"{expected}\"")]
+    /// A block does not contain the expected type
+    UnexpectedLabel {
+        /// Position of the offending block in the bundle
+        index: usize,
+        /// The label of the expected type
+        expected: &'static str,
+        /// The label of the block
+        found: &'static str,
+    },
+    #[error("PEM block {index} is invalid: {source}")]
+    /// The contents of a block could not be decoded or encoded
+    InvalidBlock {
+        /// Position of the offending block in the bundle
+        index: usize,
+        /// The reason why the block is invalid
+        source: ConversionError,
+    },
+}
+
 #[cfg(feature = "reqwest")]
 #[derive(Error, Debug)]
 /// Errors that can occur when making a request
@@ -18,6 +18,20 @@ pub static ERR_MSG_DC_MISMATCH_ISSUER_SUBJECT: &str =
     "The domain components of the issuer and the subject do not match!";
 pub static ERR_CERTIFICATE_TO_DER_ERROR: &str =
     "The certificate seems to be malformed, as it cannot be converted to DER.";
+pub static ERR_MSG_CERT_ISSUER_MISMATCH: &str =
+    "The issuer of the certificate does not match the subject of the home server certificate!";
+pub static ERR_MSG_CRL_ISSUER_MISMATCH: &str =
+    "The issuer of the CRL does not match the subject of the home server certificate!";
+pub static ERR_MSG_CRL_AUTHORITY_KEY_ID_MISMATCH: &str =
+    "The AuthorityKeyIdentifier of the CRL does not match the SubjectKeyIdentifier of the home server certificate!";
+pub static ERR_MSG_HOME_SERVER_MISSING_CRL_SIGN: &str =
+    "Home server certificates used to verify CRLs must have the \"CrlSign\" capability!";
+pub static ERR_MSG_SIGNING_KEY_WITHOUT_ALGORITHM: &str =
+    "The signing key does not declare the algorithm of its signatures!";
+pub static ERR_MSG_SERIAL_NUMBER_NOT_POSITIVE: &str =
+    "Certificate serial numbers must be positive integers!";
+pub static ERR_MSG_SERIAL_NUMBER_LENGTH: &str =
+    "Certificate serial numbers must not be longer than 20 octets!";
 #[cfg(feature = "types")]
 pub static ERR_MSG_CHALLENGE_STRING_LENGTH: &str =
     "Challenge strings must be between 32 and 255 bytes long!";
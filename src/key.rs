@@ -2,10 +2,14 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::future::Future;
+use std::pin::Pin;
+
 use spki::AlgorithmIdentifierOwned;
 
 use crate::certs::PublicKeyInfo;
-use crate::errors::{ConversionError, PublicKeyError};
+use crate::digest::KeyIdentifierDigest;
+use crate::errors::{ConversionError, PublicKeyError, SigningError};
 use crate::signature::Signature;
 
 /// A cryptographic private key generated by a [AlgorithmIdentifierOwned], with
@@ -15,11 +19,85 @@ pub trait PrivateKey<S: Signature>: PartialEq + Eq {
     type PublicKey: PublicKey<S>;
     /// Returns the public key corresponding to this private key.
     fn pubkey(&self) -> &Self::PublicKey;
-    /// Creates a [Signature] for the given data.
-    fn sign(&self, data: &[u8]) -> S;
-    /// Returns the [AlgorithmIdentifierOwned] associated with this key's signature algorithm.
-    fn algorithm_identifier(&self) -> AlgorithmIdentifierOwned {
-        S::algorithm_identifier()
+    /// Creates a [Signature] for the given data. Fails, if the signature cannot be created, e.g.
+    /// because the underlying cryptographic library reports an error.
+    fn sign(&self, data: &[u8]) -> Result<S, SigningError>;
+    /// Returns the [AlgorithmIdentifierOwned] of the signatures created by this key. Defaults to
+    /// the [PublicKey::algorithm_identifier()] of [PrivateKey::pubkey()].
+    fn algorithm_identifier(&self) -> Option<AlgorithmIdentifierOwned> {
+        self.pubkey().algorithm_identifier()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+/// The [Future] returned by [AsyncPrivateKey::sign()], resolving to the created [Signature].
+///
+/// On `wasm32` targets, this future is not required to be [Send], so that signers backed by
+/// JavaScript objects, such as WebCrypto keys, can be used.
+pub type SignatureFuture<'a, S> =
+    Pin<Box<dyn Future<Output = Result<S, SigningError>> + Send + 'a>>;
+#[cfg(target_arch = "wasm32")]
+/// The [Future] returned by [AsyncPrivateKey::sign()], resolving to the created [Signature].
+///
+/// On `wasm32` targets, this future is not required to be [Send], so that signers backed by
+/// JavaScript objects, such as WebCrypto keys, can be used.
+pub type SignatureFuture<'a, S> = Pin<Box<dyn Future<Output = Result<S, SigningError>> + 'a>>;
+
+#[cfg(not(target_arch = "wasm32"))]
+/// Types which are [Send] on all targets except `wasm32`, where this trait is implemented for all
+/// types. Used to bound the [Signature]s of the blanket [AsyncPrivateKey] implementation to match
+/// [SignatureFuture].
+pub trait MaybeSend: Send {}
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: Send + ?Sized> MaybeSend for T {}
+#[cfg(target_arch = "wasm32")]
+/// Types which are [Send] on all targets except `wasm32`, where this trait is implemented for all
+/// types. Used to bound the [Signature]s of the blanket [AsyncPrivateKey] implementation to match
+/// [SignatureFuture].
+pub trait MaybeSend {}
+#[cfg(target_arch = "wasm32")]
+impl<T: ?Sized> MaybeSend for T {}
+
+/// A cryptographic private key which creates [Signature]s asynchronously and fallibly, with a
+/// corresponding [PublicKey]. Use this trait for private keys which are not held in memory, e.g.
+/// keys held by a separate process, a key management service or a WebCrypto `CryptoKey` handle.
+///
+/// Every [PrivateKey] is an `AsyncPrivateKey`, whose signatures are available immediately. `AsyncPrivateKey`s can be used with [IdCsr::new_async()](crate::certs::idcsr::IdCsr::new_async()),
+/// [IdCert::from_actor_csr_async()](crate::certs::idcert::IdCert::from_actor_csr_async()) and
+/// [IdCert::from_ca_csr_async()](crate::certs::idcert::IdCert::from_ca_csr_async()).
+pub trait AsyncPrivateKey<S: Signature> {
+    /// The public key type corresponding to this private key.
+    type PublicKey: PublicKey<S>;
+    /// Returns the public key corresponding to this private key.
+    fn pubkey(&self) -> &Self::PublicKey;
+    /// Creates a [Signature] for the given data. Fails, if the signer cannot be reached or
+    /// refuses to create the signature.
+    fn sign<'a>(&'a self, data: &'a [u8]) -> SignatureFuture<'a, S>
+    where
+        S: 'a;
+    /// Returns the [AlgorithmIdentifierOwned] of the signatures created by this key. Defaults to
+    /// the [PublicKey::algorithm_identifier()] of [AsyncPrivateKey::pubkey()].
+    fn algorithm_identifier(&self) -> Option<AlgorithmIdentifierOwned> {
+        self.pubkey().algorithm_identifier()
+    }
+}
+
+impl<S: Signature + MaybeSend, T: PrivateKey<S>> AsyncPrivateKey<S> for T {
+    type PublicKey = <T as PrivateKey<S>>::PublicKey;
+
+    fn pubkey(&self) -> &Self::PublicKey {
+        PrivateKey::pubkey(self)
+    }
+
+    fn sign<'a>(&'a self, data: &'a [u8]) -> SignatureFuture<'a, S>
+    where
+        S: 'a,
+    {
+        Box::pin(std::future::ready(PrivateKey::sign(self, data)))
+    }
+
+    fn algorithm_identifier(&self) -> Option<AlgorithmIdentifierOwned> {
+        PrivateKey::algorithm_identifier(self)
     }
 }
 
@@ -31,10 +109,28 @@ pub trait PublicKey<S: Signature>: PartialEq + Eq + Clone {
     fn verify_signature(&self, signature: &S, data: &[u8]) -> Result<(), PublicKeyError>;
     /// Returns the [PublicKeyInfo] associated with this key's signature algorithm.
     fn public_key_info(&self) -> PublicKeyInfo;
-    /// Returns the [AlgorithmIdentifierOwned] associated with this key's signature algorithm.
-    fn algorithm_identifier(&self) -> AlgorithmIdentifierOwned {
+    /// Returns the [AlgorithmIdentifierOwned] of the signatures verified by this key. Defaults to
+    /// [Signature::algorithm_identifier()]. Public keys used with signature types without a single
+    /// algorithm, such as [AnyPublicKey](crate::any::AnyPublicKey), return the algorithm of the
+    /// key value.
+    ///
+    /// Returns `None`, if the algorithm is unknown, in which case the key cannot be used to issue
+    /// certificates or CRLs.
+    fn algorithm_identifier(&self) -> Option<AlgorithmIdentifierOwned> {
         S::algorithm_identifier()
     }
     /// Creates a new [Self] from a [PublicKeyInfo].
     fn try_from_public_key_info(public_key_info: PublicKeyInfo) -> Result<Self, ConversionError>;
+    /// Returns the key identifier of this public key, which is encoded in the
+    /// SubjectKeyIdentifier and AuthorityKeyIdentifier extensions of [IdCert](crate::certs::idcert::IdCert)s.
+    ///
+    /// Defaults to the [PublicKeyInfo::key_identifier()] of [PublicKey::public_key_info()],
+    /// computed using the [KeyIdentifierDigest]. Implementations can override this method to use
+    /// a different digest, or return `None` to omit the extensions.
+    fn key_identifier(&self) -> Option<Vec<u8>> {
+        Some(
+            self.public_key_info()
+                .key_identifier::<KeyIdentifierDigest>(),
+        )
+    }
 }
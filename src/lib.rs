@@ -43,11 +43,17 @@ crate with the ED25519 signature algorithm.
 
 ## Cryptography
 
-This crate provides no cryptographic functionality whatsoever; its sole purpose is to aid in
+By default, this crate provides no cryptographic functionality whatsoever; its sole purpose is to aid in
 implementing polyproto by transforming the
 [polyproto specification](https://docs.polyphony.chat/Protocol%20Specifications/core/) into
 well-defined yet adaptable Rust types.
 
+Implementations of the signature and key traits for Ed25519 are available in the `ed25519` module,
+using the optional `ed25519` feature, which is backed by `ed25519-dalek`. Implementations for ECDSA
+with P-256/SHA-256 and P-384/SHA-384 are available in the `ecdsa` module, using the optional `ecdsa`
+feature, which is backed by `p256` and `p384`. The `rustcrypto` feature provides adapters, which
+implement the signature and key traits for the signature and key types of any RustCrypto crate.
+
 ## Safety
 
 Please refer to the documentation of individual functions for information on which safety guarantees
@@ -105,18 +111,37 @@ pub const OID_RDN_UNIQUE_IDENTIFIER: &str = "0.9.2342.19200300.100.1.44";
 /// The OID for the `uid` RDN
 pub const OID_RDN_UID: &str = "0.9.2342.19200300.100.1.1";
 
+use certs::policy::ValidationPolicy;
 use certs::Target;
-use errors::base::ConstraintError;
+use errors::base::{ConstraintError, ConstraintViolation, ViolationCode};
 
+/// Type-erased signatures and public keys, allowing to handle certificates of any registered
+/// algorithm at runtime.
+pub mod any;
 #[cfg(feature = "reqwest")]
 /// Ready-to-use API routes, implemented using `reqwest`
 pub mod api;
 /// Generic polyproto certificate types and traits.
 pub mod certs;
+/// Clocks used for time-based checks, such as certificate validity periods.
+pub mod clock;
+/// Generic polyproto digest (hash function) traits and fingerprints.
+pub mod digest;
+#[cfg(feature = "ecdsa")]
+/// ECDSA signatures and keys for the NIST curves P-256 and P-384, implemented using `p256` and
+/// `p384`
+pub mod ecdsa;
+#[cfg(feature = "ed25519")]
+/// Ed25519 signatures and keys, implemented using `ed25519-dalek`
+pub mod ed25519;
 /// Error types used in this crate
 pub mod errors;
 /// Generic polyproto public- and private key traits.
 pub mod key;
+#[cfg(feature = "rustcrypto")]
+/// Adapters implementing the signature and key traits for the signature and key types of
+/// RustCrypto crates
+pub mod rustcrypto;
 /// Generic polyproto signature traits.
 pub mod signature;
 #[cfg(feature = "types")]
@@ -158,6 +183,54 @@ pub use x509_cert::name::*;
 pub trait Constrained {
     /// Perform validation on the type, returning an error if the type is not well-formed.
     fn validate(&self, target: Option<Target>) -> Result<(), ConstraintError>;
+
+    /// Perform validation on the type, returning all violated constraints instead of only the
+    /// first one. An empty list means that the type is well-formed. The first returned
+    /// [ConstraintViolation] carries the error which [Constrained::validate()] returns.
+    ///
+    /// The default implementation reports the error of [Constrained::validate()] as a single
+    /// violation with [ViolationCode::Unspecified].
+    fn validate_all(&self, target: Option<Target>) -> Vec<ConstraintViolation> {
+        match self.validate(target) {
+            Ok(()) => Vec::new(),
+            Err(error) => vec![ConstraintViolation::new(
+                ViolationCode::Unspecified,
+                "",
+                error,
+            )],
+        }
+    }
+
+    /// Perform validation on the type like [Constrained::validate()], additionally enforcing the
+    /// rules of the given [ValidationPolicy].
+    fn validate_with_policy(
+        &self,
+        target: Option<Target>,
+        policy: &ValidationPolicy,
+    ) -> Result<(), ConstraintError> {
+        match self
+            .validate_all_with_policy(target, policy)
+            .into_iter()
+            .next()
+        {
+            Some(violation) => Err(violation.error),
+            None => Ok(()),
+        }
+    }
+
+    /// Perform validation on the type like [Constrained::validate_all()], additionally reporting
+    /// violations of the rules of the given [ValidationPolicy]. Violations of the polyproto
+    /// specification are listed first.
+    ///
+    /// The default implementation does not enforce any policy rules, as they do not apply to all
+    /// types.
+    fn validate_all_with_policy(
+        &self,
+        target: Option<Target>,
+        _policy: &ValidationPolicy,
+    ) -> Vec<ConstraintViolation> {
+        self.validate_all(target)
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,315 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+
+use der::asn1::BitString;
+use der::{Any, Decode};
+use signature::{Keypair, SignatureEncoding, Signer, Verifier};
+use spki::{
+    AlgorithmIdentifierOwned, DecodePublicKey, EncodePublicKey, SignatureAlgorithmIdentifier,
+    SignatureBitStringEncoding, SubjectPublicKeyInfoOwned,
+};
+
+use crate::certs::PublicKeyInfo;
+use crate::errors::{ConversionError, InvalidInput, PublicKeyError, SigningError};
+use crate::key::{PrivateKey, PublicKey};
+use crate::signature::Signature;
+
+/// Converts the [SignatureAlgorithmIdentifier] of `V` into an [AlgorithmIdentifierOwned].
+///
+/// ## Panics
+///
+/// Panics, if the algorithm parameters of `V` cannot be encoded, which constitutes a bug in the
+/// implementation of [SignatureAlgorithmIdentifier] for `V`.
+fn signature_algorithm<V: SignatureAlgorithmIdentifier>() -> AlgorithmIdentifierOwned {
+    let algorithm = V::SIGNATURE_ALGORITHM_IDENTIFIER;
+    AlgorithmIdentifierOwned {
+        oid: algorithm.oid,
+        parameters: algorithm.parameters.as_ref().map(|parameters| {
+            Any::encode_from(parameters).expect("Signature algorithm parameters must be encodable")
+        }),
+    }
+}
+
+/// A [Signature] wrapping a signature type `S` of a RustCrypto crate, which implements
+/// [SignatureEncoding]. The signature algorithm is determined by the verifying key type `V`,
+/// using its [SignatureAlgorithmIdentifier] implementation. For the verifying keys of the `ecdsa`
+/// crate, this implementation requires the `oid` feature of the digest crate, e.g. `sha2`.
+///
+/// The encoding of `S` is used as-is in [IdCert](crate::certs::idcert::IdCert)s,
+/// [IdCsr](crate::certs::idcsr::IdCsr)s and [IdCrl](crate::certs::idcrl::IdCrl)s. For algorithms
+/// whose signatures are DER encoded in X.509, such as ECDSA, `S` must be the DER encoded signature
+/// type, e.g. `ecdsa::der::Signature`.
+pub struct RustCryptoSignature<S, V> {
+    signature: S,
+    algorithm: PhantomData<fn() -> V>,
+}
+
+impl<S, V> RustCryptoSignature<S, V> {
+    /// Wraps a RustCrypto signature.
+    pub fn new(signature: S) -> Self {
+        Self {
+            signature,
+            algorithm: PhantomData,
+        }
+    }
+
+    /// Returns the wrapped RustCrypto signature.
+    pub fn into_inner(self) -> S {
+        self.signature
+    }
+}
+
+impl<S: Debug, V> Debug for RustCryptoSignature<S, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RustCryptoSignature")
+            .field("signature", &self.signature)
+            .finish()
+    }
+}
+
+impl<S: Clone, V> Clone for RustCryptoSignature<S, V> {
+    fn clone(&self) -> Self {
+        Self::new(self.signature.clone())
+    }
+}
+
+impl<S: SignatureEncoding, V> PartialEq for RustCryptoSignature<S, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.signature.to_bytes().as_ref() == other.signature.to_bytes().as_ref()
+    }
+}
+
+impl<S: SignatureEncoding, V> Eq for RustCryptoSignature<S, V> {}
+
+impl<S: SignatureEncoding, V> Display for RustCryptoSignature<S, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for byte in self.signature.to_bytes().as_ref() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl<S: SignatureEncoding, V> SignatureBitStringEncoding for RustCryptoSignature<S, V> {
+    fn to_bitstring(&self) -> der::Result<BitString> {
+        BitString::from_bytes(self.signature.to_bytes().as_ref())
+    }
+}
+
+impl<S: SignatureEncoding, V: SignatureAlgorithmIdentifier> Signature
+    for RustCryptoSignature<S, V>
+{
+    type Signature = S;
+
+    fn as_signature(&self) -> &Self::Signature {
+        &self.signature
+    }
+
+    /// The [SignatureAlgorithmIdentifier] of `V`.
+    fn algorithm_identifier() -> Option<AlgorithmIdentifierOwned> {
+        Some(signature_algorithm::<V>())
+    }
+
+    fn signature_algorithm(&self) -> AlgorithmIdentifierOwned {
+        signature_algorithm::<V>()
+    }
+
+    fn try_from_bytes(signature: &[u8]) -> Result<Self, ConversionError> {
+        S::try_from(signature).map(Self::new).map_err(|_| {
+            InvalidInput::Malformed("The bytes do not encode a valid signature".to_string()).into()
+        })
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.signature.to_bytes().as_ref().to_vec()
+    }
+}
+
+/// A [PublicKey] wrapping a verifying key type `V` of a RustCrypto crate, which verifies
+/// signatures of type `S` and implements [EncodePublicKey] and [DecodePublicKey]. The
+/// [PublicKeyInfo] of the key is derived from its `SubjectPublicKeyInfo` encoding, which is also
+/// used to decode public keys, so that `V` checks the algorithm and its parameters.
+pub struct RustCryptoPublicKey<V, S> {
+    key: V,
+    public_key_info: PublicKeyInfo,
+    signature: PhantomData<fn() -> S>,
+}
+
+impl<V: EncodePublicKey, S> RustCryptoPublicKey<V, S> {
+    /// Wraps a RustCrypto verifying key. Fails, if the key cannot be encoded as
+    /// `SubjectPublicKeyInfo`.
+    pub fn new(key: V) -> Result<Self, ConversionError> {
+        let document = key
+            .to_public_key_der()
+            .map_err(|e| InvalidInput::Malformed(e.to_string()))?;
+        let public_key_info = SubjectPublicKeyInfoOwned::from_der(document.as_bytes())?.into();
+        Ok(Self {
+            key,
+            public_key_info,
+            signature: PhantomData,
+        })
+    }
+}
+
+impl<V, S> RustCryptoPublicKey<V, S> {
+    /// The wrapped RustCrypto verifying key.
+    pub fn verifying_key(&self) -> &V {
+        &self.key
+    }
+}
+
+impl<V: Debug, S> Debug for RustCryptoPublicKey<V, S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RustCryptoPublicKey")
+            .field("key", &self.key)
+            .field("public_key_info", &self.public_key_info)
+            .finish()
+    }
+}
+
+impl<V: Clone, S> Clone for RustCryptoPublicKey<V, S> {
+    fn clone(&self) -> Self {
+        Self {
+            key: self.key.clone(),
+            public_key_info: self.public_key_info.clone(),
+            signature: PhantomData,
+        }
+    }
+}
+
+/// Public keys are equal, if their `SubjectPublicKeyInfo` encodings are equal.
+impl<V, S> PartialEq for RustCryptoPublicKey<V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.public_key_info == other.public_key_info
+    }
+}
+
+impl<V, S> Eq for RustCryptoPublicKey<V, S> {}
+
+impl<V, S> PublicKey<RustCryptoSignature<S, V>> for RustCryptoPublicKey<V, S>
+where
+    V: Verifier<S> + EncodePublicKey + DecodePublicKey + SignatureAlgorithmIdentifier + Clone,
+    S: SignatureEncoding,
+{
+    fn verify_signature(
+        &self,
+        signature: &RustCryptoSignature<S, V>,
+        data: &[u8],
+    ) -> Result<(), PublicKeyError> {
+        self.key
+            .verify(data, &signature.signature)
+            .map_err(|_| PublicKeyError::BadSignature)
+    }
+
+    fn public_key_info(&self) -> PublicKeyInfo {
+        self.public_key_info.clone()
+    }
+
+    /// Decodes the public key using the [DecodePublicKey] implementation of `V`.
+    fn try_from_public_key_info(public_key_info: PublicKeyInfo) -> Result<Self, ConversionError> {
+        let der = public_key_info.to_der()?;
+        let key =
+            V::from_public_key_der(&der).map_err(|e| InvalidInput::Malformed(e.to_string()))?;
+        Self::new(key)
+    }
+}
+
+/// A [PrivateKey] wrapping a signing key type `K` of a RustCrypto crate, which creates signatures
+/// of type `S`, together with its [RustCryptoPublicKey].
+///
+/// Private keys are compared by their public keys. The [Debug] implementation only prints the
+/// public key.
+pub struct RustCryptoPrivateKey<K, V, S> {
+    key: K,
+    public_key: RustCryptoPublicKey<V, S>,
+}
+
+impl<K, V, S> RustCryptoPrivateKey<K, V, S>
+where
+    K: EncodePublicKey,
+    V: DecodePublicKey + EncodePublicKey,
+{
+    /// Wraps a RustCrypto signing key, which encodes its public key using [EncodePublicKey]. The
+    /// public key is decoded as `V`.
+    pub fn new(key: K) -> Result<Self, ConversionError> {
+        let document = key
+            .to_public_key_der()
+            .map_err(|e| InvalidInput::Malformed(e.to_string()))?;
+        let verifying_key = V::from_public_key_der(document.as_bytes())
+            .map_err(|e| InvalidInput::Malformed(e.to_string()))?;
+        Ok(Self {
+            key,
+            public_key: RustCryptoPublicKey::new(verifying_key)?,
+        })
+    }
+}
+
+impl<K, V, S> RustCryptoPrivateKey<K, V, S>
+where
+    K: Keypair<VerifyingKey = V>,
+    V: EncodePublicKey,
+{
+    /// Wraps a RustCrypto signing key, whose verifying key is obtained using [Keypair]. This is
+    /// the case for most RustCrypto signing keys, which do not implement [EncodePublicKey]
+    /// themselves.
+    pub fn from_keypair(key: K) -> Result<Self, ConversionError> {
+        let public_key = RustCryptoPublicKey::new(key.verifying_key())?;
+        Ok(Self { key, public_key })
+    }
+}
+
+impl<K, V, S> RustCryptoPrivateKey<K, V, S> {
+    /// The wrapped RustCrypto signing key.
+    pub fn signing_key(&self) -> &K {
+        &self.key
+    }
+}
+
+impl<K, V: Debug, S> Debug for RustCryptoPrivateKey<K, V, S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RustCryptoPrivateKey")
+            .field("public_key", &self.public_key)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K: Clone, V: Clone, S> Clone for RustCryptoPrivateKey<K, V, S> {
+    fn clone(&self) -> Self {
+        Self {
+            key: self.key.clone(),
+            public_key: self.public_key.clone(),
+        }
+    }
+}
+
+impl<K, V, S> PartialEq for RustCryptoPrivateKey<K, V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.public_key == other.public_key
+    }
+}
+
+impl<K, V, S> Eq for RustCryptoPrivateKey<K, V, S> {}
+
+impl<K, V, S> PrivateKey<RustCryptoSignature<S, V>> for RustCryptoPrivateKey<K, V, S>
+where
+    K: Signer<S>,
+    V: Verifier<S> + EncodePublicKey + DecodePublicKey + SignatureAlgorithmIdentifier + Clone,
+    S: SignatureEncoding,
+{
+    type PublicKey = RustCryptoPublicKey<V, S>;
+
+    fn pubkey(&self) -> &Self::PublicKey {
+        &self.public_key
+    }
+
+    fn sign(&self, data: &[u8]) -> Result<RustCryptoSignature<S, V>, SigningError> {
+        self.key
+            .try_sign(data)
+            .map(RustCryptoSignature::new)
+            .map_err(|e| SigningError::Failed(e.to_string()))
+    }
+}
@@ -4,14 +4,51 @@
 
 use spki::{AlgorithmIdentifierOwned, SignatureBitStringEncoding};
 
+use crate::certs::ensure_algorithm;
+use crate::errors::{ConversionError, InvalidCert};
+
 /// A signature value, generated using a [SignatureAlgorithm]
 pub trait Signature: PartialEq + Eq + SignatureBitStringEncoding + Clone + ToString {
     /// The underlying signature type
     type Signature;
     /// The signature value
     fn as_signature(&self) -> &Self::Signature;
-    /// The [AlgorithmIdentifierOwned] associated with this signature
-    fn algorithm_identifier() -> AlgorithmIdentifierOwned;
-    /// From a byte slice, create a new [Self]
-    fn from_bytes(signature: &[u8]) -> Self;
+    /// The [AlgorithmIdentifierOwned] shared by all signatures of this type. Returns `None` for
+    /// types representing signatures of multiple algorithms, such as
+    /// [AnySignature](crate::any::AnySignature), whose algorithm is only known for a signature
+    /// value, see [Signature::signature_algorithm()].
+    fn algorithm_identifier() -> Option<AlgorithmIdentifierOwned>;
+    /// The [AlgorithmIdentifierOwned] this signature value has been created with. For types with a
+    /// single algorithm, this is the [Signature::algorithm_identifier()].
+    fn signature_algorithm(&self) -> AlgorithmIdentifierOwned;
+    /// Checks, whether signatures created with `algorithm` can be represented by this type. By
+    /// default, `algorithm` must be equal to [Signature::algorithm_identifier()]. Types without a
+    /// single algorithm must override this method, as they reject all algorithms otherwise.
+    fn check_algorithm(algorithm: &AlgorithmIdentifierOwned) -> Result<(), InvalidCert> {
+        match Self::algorithm_identifier() {
+            Some(expected) => ensure_algorithm(&expected, algorithm),
+            None => Err(InvalidCert::UnsupportedAlgorithm { oid: algorithm.oid }),
+        }
+    }
+    /// Creates a new [Self] from a byte slice, which encodes a signature created with `algorithm`.
+    /// By default, checks `algorithm` using [Signature::check_algorithm()] and decodes the bytes
+    /// using [Signature::try_from_bytes()].
+    fn try_from_algorithm_and_bytes(
+        algorithm: &AlgorithmIdentifierOwned,
+        signature: &[u8],
+    ) -> Result<Self, ConversionError> {
+        Self::check_algorithm(algorithm)?;
+        Self::try_from_bytes(signature)
+    }
+    /// From a byte slice, create a new [Self]. Fails, if the bytes do not exactly encode a
+    /// signature of this type, e.g. because they are too short or too long.
+    ///
+    /// Signatures are decoded using this method when parsing [IdCert](crate::certs::idcert::IdCert)s,
+    /// [IdCsr](crate::certs::idcsr::IdCsr)s and [IdCrl](crate::certs::idcrl::IdCrl)s, see
+    /// [Signature::try_from_algorithm_and_bytes()]. Errors should be reported using
+    /// [ConversionError::InvalidInput].
+    fn try_from_bytes(signature: &[u8]) -> Result<Self, ConversionError>;
+    /// Returns the encoding of this signature as a byte vector, which [Signature::try_from_bytes()]
+    /// decodes into a signature equal to `self`.
+    fn to_bytes(&self) -> Vec<u8>;
 }
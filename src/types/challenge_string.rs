@@ -2,6 +2,8 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use crate::clock::Clock;
+
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 /// A struct that holds a challenge string and its expiration time.
@@ -12,3 +14,11 @@ pub struct ChallengeString {
     /// any longer.
     pub expires: u64,
 }
+
+impl ChallengeString {
+    /// Checks, if the challenge has expired at the current time of the given [Clock], taking its
+    /// skew tolerance into account. An expired challenge cannot be completed any longer.
+    pub fn is_expired(&self, clock: &dyn Clock) -> bool {
+        clock.is_expired(self.expires)
+    }
+}
@@ -180,6 +180,7 @@ mod serde_support {
 }
 
 #[cfg(test)]
+#[allow(clippy::manual_unwrap_or)]
 mod test {
     use log::trace;
     use serde_json::json;
@@ -0,0 +1,233 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::str::FromStr;
+
+use der::asn1::Uint;
+use der::Encode;
+use polyproto::any::{register_algorithm, registered_algorithms, AnyPublicKey, AnySignature};
+use polyproto::certs::capabilities::Capabilities;
+use polyproto::certs::idcert::IdCert;
+use polyproto::certs::idcsr::IdCsr;
+use polyproto::certs::Target;
+use polyproto::errors::{ConversionError, InvalidCert, PublicKeyError, SigningError};
+use polyproto::key::{PrivateKey, PublicKey};
+use polyproto::signature::Signature;
+use spki::{AlgorithmIdentifierOwned, ObjectIdentifier};
+use x509_cert::Certificate;
+
+use crate::common::*;
+
+type AnyIdCert = IdCert<AnySignature, AnyPublicKey>;
+
+fn register_ed25519() {
+    register_algorithm::<Ed25519Signature, Ed25519PublicKey>().unwrap();
+}
+
+fn unregistered_algorithm() -> AlgorithmIdentifierOwned {
+    AlgorithmIdentifierOwned {
+        // Ed448
+        oid: ObjectIdentifier::from_str("1.3.101.113").unwrap(),
+        parameters: None,
+    }
+}
+
+#[test]
+fn certificates_of_registered_algorithms_verify() {
+    init_logger();
+    register_ed25519();
+    assert!(registered_algorithms().contains(&Ed25519Signature::algorithm_identifier().unwrap()));
+
+    let home_server_key = gen_priv_key();
+    let home_server_cert = IdCert::from_ca_csr(
+        home_server_csr(&home_server_key),
+        &home_server_key,
+        Uint::new(&[1]).unwrap(),
+        home_server_subject(),
+        default_validity(),
+    )
+    .unwrap();
+    let actor_cert = IdCert::from_actor_csr(
+        actor_csr("flori", &gen_priv_key()),
+        &home_server_key,
+        Uint::new(&[2]).unwrap(),
+        home_server_subject(),
+        default_validity(),
+    )
+    .unwrap();
+
+    let home_server_der = home_server_cert.clone().to_der().unwrap();
+    let any_home_server_cert =
+        AnyIdCert::from_der(&home_server_der, Target::HomeServer, 100, &dummy_key()).unwrap();
+    assert_eq!(
+        any_home_server_cert.clone().to_der().unwrap(),
+        home_server_der
+    );
+    assert_eq!(
+        any_home_server_cert
+            .signature
+            .downcast::<Ed25519Signature>()
+            .unwrap(),
+        home_server_cert.signature
+    );
+
+    let actor_der = actor_cert.clone().to_der().unwrap();
+    let home_server_public_key = &any_home_server_cert.id_cert_tbs().subject_public_key;
+    let any_actor_cert =
+        AnyIdCert::from_der(&actor_der, Target::Actor, 100, home_server_public_key).unwrap();
+    assert_eq!(any_actor_cert.clone().to_der().unwrap(), actor_der);
+    assert_eq!(
+        any_actor_cert
+            .id_cert_tbs()
+            .subject_public_key
+            .downcast::<Ed25519Signature, Ed25519PublicKey>()
+            .unwrap(),
+        actor_cert.id_cert_tbs().subject_public_key
+    );
+
+    // Signatures of a different key do not verify
+    assert_eq!(
+        any_actor_cert
+            .full_verify_actor(100, &AnyPublicKey::new(gen_priv_key().pubkey()))
+            .unwrap_err(),
+        InvalidCert::PublicKeyError(PublicKeyError::BadSignature)
+    );
+}
+
+/// An [AnyPublicKey] of a registered algorithm, for verifying self-signed certificates
+fn dummy_key() -> AnyPublicKey {
+    AnyPublicKey::new(gen_priv_key().pubkey())
+}
+
+#[test]
+fn csrs_of_registered_algorithms_verify() {
+    init_logger();
+    register_ed25519();
+    let csr = actor_csr("flori", &gen_priv_key());
+    let any_csr = IdCsr::<AnySignature, AnyPublicKey>::from_der_verified(
+        &csr.clone().to_der().unwrap(),
+        Some(Target::Actor),
+    )
+    .unwrap();
+    assert_eq!(any_csr.signature, AnySignature::new(&csr.signature));
+    assert_eq!(any_csr.to_der().unwrap(), csr.to_der().unwrap());
+}
+
+#[test]
+fn key_identifiers_match_registered_type() {
+    init_logger();
+    register_ed25519();
+    let public_key = gen_priv_key().public_key;
+    let any_public_key =
+        AnyPublicKey::try_from_public_key_info(public_key.public_key_info()).unwrap();
+    assert_eq!(any_public_key, AnyPublicKey::new(&public_key));
+    assert_eq!(any_public_key.key_identifier(), public_key.key_identifier());
+    assert_eq!(
+        any_public_key.algorithm_identifier(),
+        Ed25519Signature::algorithm_identifier()
+    );
+}
+
+#[test]
+fn unregistered_algorithms_are_rejected() {
+    init_logger();
+    register_ed25519();
+    let mut certificate = Certificate::try_from(actor_id_cert("flori")).unwrap();
+    certificate.signature_algorithm = unregistered_algorithm();
+    certificate.tbs_certificate.signature = unregistered_algorithm();
+    assert_eq!(
+        AnyIdCert::from_der_unchecked(&certificate.to_der().unwrap()).unwrap_err(),
+        ConversionError::InvalidCert(InvalidCert::UnsupportedAlgorithm {
+            oid: unregistered_algorithm().oid
+        })
+    );
+
+    let mut public_key_info = gen_priv_key().public_key.public_key_info();
+    public_key_info.algorithm = unregistered_algorithm();
+    assert_eq!(
+        AnyPublicKey::try_from_public_key_info(public_key_info).unwrap_err(),
+        ConversionError::InvalidCert(InvalidCert::UnsupportedAlgorithm {
+            oid: unregistered_algorithm().oid
+        })
+    );
+
+    assert!(AnySignature::try_from_bytes(&[0; 64]).is_err());
+    let signature = AnySignature::try_from_algorithm_and_bytes(
+        &Ed25519Signature::algorithm_identifier().unwrap(),
+        &[0; 63],
+    );
+    assert!(matches!(
+        signature.unwrap_err(),
+        ConversionError::InvalidInput(_)
+    ));
+}
+
+/// A [PrivateKey] creating [AnySignature]s, which does not override
+/// [PrivateKey::algorithm_identifier()]
+#[derive(Debug, PartialEq, Eq)]
+struct AnyPrivateKey {
+    key: Ed25519PrivateKey,
+    public_key: AnyPublicKey,
+}
+
+impl PrivateKey<AnySignature> for AnyPrivateKey {
+    type PublicKey = AnyPublicKey;
+
+    fn pubkey(&self) -> &Self::PublicKey {
+        &self.public_key
+    }
+
+    fn sign(&self, data: &[u8]) -> Result<AnySignature, SigningError> {
+        Ok(AnySignature::new(&self.key.sign(data)?))
+    }
+}
+
+#[test]
+fn any_private_keys_issue_certificates() {
+    init_logger();
+    register_ed25519();
+    assert_eq!(AnySignature::algorithm_identifier(), None);
+
+    let key = gen_priv_key();
+    let public_key = key.public_key.clone();
+    let any_key = AnyPrivateKey {
+        public_key: AnyPublicKey::new(&public_key),
+        key,
+    };
+    assert_eq!(
+        any_key.algorithm_identifier(),
+        Ed25519Signature::algorithm_identifier()
+    );
+
+    let cert = AnyIdCert::from_ca_csr(
+        IdCsr::new(
+            &home_server_subject(),
+            &any_key,
+            &Capabilities::default_home_server(),
+            Some(Target::HomeServer),
+        )
+        .unwrap(),
+        &any_key,
+        Uint::new(&[1]).unwrap(),
+        home_server_subject(),
+        default_validity(),
+    )
+    .unwrap();
+    cert.full_verify_home_server(100).unwrap();
+    IdCert::<Ed25519Signature, Ed25519PublicKey>::from_der(
+        &cert.to_der().unwrap(),
+        Target::HomeServer,
+        100,
+        &public_key,
+    )
+    .unwrap();
+}
+
+#[test]
+fn types_without_a_single_algorithm_cannot_be_registered() {
+    assert!(matches!(
+        register_algorithm::<AnySignature, AnyPublicKey>(),
+        Err(ConversionError::InvalidInput(_))
+    ));
+}
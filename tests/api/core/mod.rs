@@ -2,16 +2,16 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use der::asn1::{BitString, GeneralizedTime, Uint};
+use der::asn1::{BitString, Uint};
 use httptest::matchers::request::method_path;
 use httptest::matchers::{eq, json_decoded, matches, request};
 use httptest::responders::{json_encoded, status_code};
 use httptest::*;
-use polyproto::api::core::current_unix_time;
 use polyproto::certs::capabilities::Capabilities;
 use polyproto::certs::idcert::IdCert;
 use polyproto::certs::idcsr::IdCsr;
 use polyproto::certs::SessionId;
+use polyproto::clock::FixedClock;
 use polyproto::key::PublicKey;
 use polyproto::types::routes::core::v1::{
     DELETE_ENCRYPTED_PKM, DELETE_SESSION, GET_ACTOR_IDCERTS, GET_CHALLENGE_STRING,
@@ -24,7 +24,6 @@ use polyproto::types::x509_cert::SerialNumber;
 use polyproto::types::{EncryptedPkm, PrivateKeyInfo};
 use serde_json::json;
 use spki::ObjectIdentifier;
-use x509_cert::time::Validity;
 
 use crate::common::{
     actor_id_cert, actor_subject, default_validity, gen_priv_key, home_server_id_cert,
@@ -73,22 +72,7 @@ async fn rotate_server_identity_key() {
         &home_server_signing_key,
         Uint::new(9u64.to_be_bytes().as_slice()).unwrap(),
         home_server_subject(),
-        Validity {
-            not_before: x509_cert::time::Time::GeneralTime(
-                GeneralizedTime::from_unix_duration(std::time::Duration::new(
-                    current_unix_time() - 1000,
-                    0,
-                ))
-                .unwrap(),
-            ),
-            not_after: x509_cert::time::Time::GeneralTime(
-                GeneralizedTime::from_unix_duration(std::time::Duration::new(
-                    current_unix_time() + 1000,
-                    0,
-                ))
-                .unwrap(),
-            ),
-        },
+        default_validity(),
     )
     .unwrap();
     let cert_pem = id_cert.to_pem(der::pem::LineEnding::LF).unwrap();
@@ -101,7 +85,8 @@ async fn rotate_server_identity_key() {
         .respond_with(json_encoded(json!(cert_pem))),
     );
     let url = server_url(&server);
-    let client = polyproto::api::HttpClient::new(&url).unwrap();
+    let mut client = polyproto::api::HttpClient::new(&url).unwrap();
+    client.set_clock(FixedClock::new(100));
     let cert = client
         .rotate_server_identity_key::<Ed25519Signature, Ed25519PublicKey>()
         .await
@@ -164,6 +149,36 @@ async fn get_server_id_cert() {
     assert_eq!(cert.to_pem(der::pem::LineEnding::LF).unwrap(), cert_pem);
 }
 
+#[tokio::test]
+async fn get_server_id_cert_uses_client_clock() {
+    init_logger();
+    let id_cert = home_server_id_cert();
+    let cert_pem = id_cert.to_pem(der::pem::LineEnding::LF).unwrap();
+    let server = Server::run();
+    let url = server_url(&server);
+    let mut client = polyproto::api::HttpClient::new(&url).unwrap();
+    server.expect(
+        Expectation::matching(all_of![
+            request::method(GET_SERVER_PUBLIC_IDCERT.method.as_str()),
+            request::path(GET_SERVER_PUBLIC_IDCERT.path),
+        ])
+        .times(2)
+        .respond_with(json_encoded(json!(cert_pem))),
+    );
+
+    // The certificate expires at 1000
+    client.set_clock(FixedClock::new(1010));
+    assert!(client
+        .get_server_id_cert::<Ed25519Signature, Ed25519PublicKey>(None)
+        .await
+        .is_err());
+    client.set_clock(FixedClock::new(1010).with_skew_tolerance(10));
+    client
+        .get_server_id_cert::<Ed25519Signature, Ed25519PublicKey>(None)
+        .await
+        .unwrap();
+}
+
 #[tokio::test]
 async fn get_actor_id_certs() {
     init_logger();
@@ -5,6 +5,7 @@
 pub(crate) mod core;
 
 use super::*;
+use polyproto::clock::{FixedClock, OffsetClock};
 use polyproto::types::ChallengeString;
 use polyproto::types::FederationId;
 
@@ -36,6 +37,18 @@ fn challenge_string_length() {
     assert!(challenge.validate(None).is_err());
 }
 
+#[test]
+fn challenge_string_expiry() {
+    let challenge = ChallengeString {
+        challenge: "a".repeat(32),
+        expires: 100,
+    };
+    assert!(!challenge.is_expired(&FixedClock::new(100)));
+    assert!(challenge.is_expired(&FixedClock::new(101)));
+    assert!(!challenge.is_expired(&FixedClock::new(110).with_skew_tolerance(10)));
+    assert!(challenge.is_expired(&OffsetClock::new(FixedClock::new(50), 60)));
+}
+
 #[test]
 fn valid_federation_id() {
     FederationId::new("flori@polyphony.chat").unwrap();
@@ -0,0 +1,178 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use der::asn1::Uint;
+use polyproto::certs::capabilities::Capabilities;
+use polyproto::certs::idcert::IdCert;
+use polyproto::certs::idcsr::IdCsr;
+use polyproto::certs::Target;
+use polyproto::errors::{ConversionError, SigningError};
+use polyproto::key::{AsyncPrivateKey, SignatureFuture};
+use polyproto::Constrained;
+
+use crate::common::*;
+
+/// Simulates a signer running in a separate process, which yields to the runtime before
+/// answering a signing request.
+struct RemoteSigner {
+    key: Ed25519PrivateKey,
+}
+
+impl AsyncPrivateKey<Ed25519Signature> for RemoteSigner {
+    type PublicKey = Ed25519PublicKey;
+
+    fn pubkey(&self) -> &Self::PublicKey {
+        &self.key.public_key
+    }
+
+    fn sign<'a>(&'a self, data: &'a [u8]) -> SignatureFuture<'a, Ed25519Signature>
+    where
+        Ed25519Signature: 'a,
+    {
+        Box::pin(async move {
+            tokio::task::yield_now().await;
+            AsyncPrivateKey::sign(&self.key, data).await
+        })
+    }
+}
+
+/// A signer which cannot be reached.
+struct UnavailableSigner {
+    public_key: Ed25519PublicKey,
+}
+
+impl AsyncPrivateKey<Ed25519Signature> for UnavailableSigner {
+    type PublicKey = Ed25519PublicKey;
+
+    fn pubkey(&self) -> &Self::PublicKey {
+        &self.public_key
+    }
+
+    fn sign<'a>(&'a self, _data: &'a [u8]) -> SignatureFuture<'a, Ed25519Signature>
+    where
+        Ed25519Signature: 'a,
+    {
+        Box::pin(async { Err(SigningError::Unavailable("connection refused".to_string())) })
+    }
+}
+
+#[tokio::test]
+async fn private_keys_sign_asynchronously() {
+    init_logger();
+    let priv_key = gen_priv_key();
+    let csr = IdCsr::new_async(
+        &actor_subject("flori"),
+        &priv_key,
+        &Capabilities::default_actor(),
+        Some(Target::Actor),
+    )
+    .await
+    .unwrap();
+    // Ed25519 signatures are deterministic
+    assert_eq!(csr, actor_csr("flori", &priv_key));
+
+    let cert = IdCert::from_actor_csr_async(
+        csr,
+        &gen_priv_key(),
+        Uint::new(&[8]).unwrap(),
+        home_server_subject(),
+        default_validity(),
+    )
+    .await
+    .unwrap();
+    cert.validate(Some(Target::Actor)).unwrap();
+}
+
+#[tokio::test]
+async fn remote_signer_issues_certificates() {
+    init_logger();
+    let home_server = RemoteSigner {
+        key: gen_priv_key(),
+    };
+    // Signing futures can be moved between threads of the runtime
+    let home_server_cert = tokio::spawn(async move {
+        let csr = IdCsr::new_async(
+            &home_server_subject(),
+            &home_server,
+            &Capabilities::default_home_server(),
+            Some(Target::HomeServer),
+        )
+        .await
+        .unwrap();
+        let cert = IdCert::from_ca_csr_async(
+            csr,
+            &home_server,
+            Uint::new(&[1]).unwrap(),
+            home_server_subject(),
+            default_validity(),
+        )
+        .await
+        .unwrap();
+        (home_server, cert)
+    });
+    let (home_server, home_server_cert) = home_server_cert.await.unwrap();
+    home_server_cert.full_verify_home_server(100).unwrap();
+
+    let actor_key = RemoteSigner {
+        key: gen_priv_key(),
+    };
+    let csr = IdCsr::new_async(
+        &actor_subject("flori"),
+        &actor_key,
+        &Capabilities::default_actor(),
+        Some(Target::Actor),
+    )
+    .await
+    .unwrap();
+    let actor_cert = IdCert::from_actor_csr_async(
+        csr,
+        &home_server,
+        Uint::new(&[2]).unwrap(),
+        home_server_subject(),
+        default_validity(),
+    )
+    .await
+    .unwrap();
+    actor_cert
+        .full_verify_actor(100, &home_server.key.public_key)
+        .unwrap();
+}
+
+#[tokio::test]
+async fn signer_errors_are_reported() {
+    init_logger();
+    let signer = UnavailableSigner {
+        public_key: gen_priv_key().public_key,
+    };
+    let expected =
+        ConversionError::SigningError(SigningError::Unavailable("connection refused".to_string()));
+    let csr = IdCsr::<Ed25519Signature, Ed25519PublicKey>::new_async(
+        &home_server_subject(),
+        &signer,
+        &Capabilities::default_home_server(),
+        Some(Target::HomeServer),
+    )
+    .await;
+    assert_eq!(csr.unwrap_err(), expected);
+
+    let cert = IdCert::from_ca_csr_async(
+        home_server_csr(&gen_priv_key()),
+        &signer,
+        Uint::new(&[1]).unwrap(),
+        home_server_subject(),
+        default_validity(),
+    )
+    .await;
+    assert_eq!(cert.unwrap_err(), expected);
+
+    let cert = IdCert::from_actor_csr_async(
+        actor_csr("flori", &gen_priv_key()),
+        &signer,
+        Uint::new(&[2]).unwrap(),
+        home_server_subject(),
+        default_validity(),
+    )
+    .await;
+    assert_eq!(cert.unwrap_err(), expected);
+}
@@ -0,0 +1,159 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::str::FromStr;
+use std::time::Duration;
+
+use der::asn1::Uint;
+use polyproto::certs::builder::{IdCertBuilder, IdCsrBuilder};
+use polyproto::certs::idcert::IdCert;
+use polyproto::certs::idcsr::IdCsr;
+use polyproto::certs::{SessionId, Target};
+use polyproto::errors::BuilderError;
+use polyproto::types::FederationId;
+use polyproto::Name;
+
+use crate::common::*;
+
+fn actor_builder() -> IdCsrBuilder {
+    IdCsrBuilder::new()
+        .federation_id(FederationId::new("flori@polyphony.chat").unwrap())
+        .session_id(SessionId::new_validated("client1").unwrap())
+}
+
+#[test]
+fn actor_subject_matches_hand_written_name() {
+    init_logger();
+    assert_eq!(
+        actor_builder().subject(Target::Actor).unwrap(),
+        Name::from_str(
+            "CN=flori,DC=polyphony,DC=chat,UID=flori@polyphony.chat,uniqueIdentifier=client1"
+        )
+        .unwrap()
+    );
+    assert_eq!(
+        IdCsrBuilder::new()
+            .home_server_domain("polyphony.chat")
+            .subject(Target::HomeServer)
+            .unwrap(),
+        home_server_subject()
+    );
+}
+
+#[test]
+fn build_actor_and_home_server_certs() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let actor_key = gen_priv_key();
+    let home_server_csr: IdCsr<Ed25519Signature, Ed25519PublicKey> = IdCsrBuilder::new()
+        .home_server_domain("polyphony.chat")
+        .build_home_server(&home_server_key)
+        .unwrap();
+    let home_server_cert = IdCertBuilder::new(home_server_csr)
+        .serial_number(Uint::new(&[1]).unwrap())
+        .home_server_domain("polyphony.chat")
+        .not_before(10)
+        .valid_for(Duration::from_secs(990))
+        .build_home_server(&home_server_key)
+        .unwrap();
+    home_server_cert.full_verify_home_server(100).unwrap();
+
+    let actor_csr = actor_builder().build_actor(&actor_key).unwrap();
+    let actor_cert: IdCert<Ed25519Signature, Ed25519PublicKey> = IdCertBuilder::new(actor_csr)
+        .serial_number(Uint::new(&[2]).unwrap())
+        .home_server_domain("polyphony.chat")
+        .not_before(10)
+        .valid_for(Duration::from_secs(990))
+        .build_actor(&home_server_key)
+        .unwrap();
+    assert!(!actor_cert.valid_at(1001));
+    actor_cert
+        .full_verify_actor(1000, &home_server_cert.id_cert_tbs().subject_public_key)
+        .unwrap();
+}
+
+#[test]
+fn csr_builder_errors() {
+    init_logger();
+    let priv_key = gen_priv_key();
+    assert_eq!(
+        IdCsrBuilder::new()
+            .session_id(SessionId::new_validated("client1").unwrap())
+            .build_actor::<Ed25519Signature, Ed25519PublicKey>(&priv_key)
+            .err()
+            .unwrap(),
+        BuilderError::MissingField {
+            field: "federation_id"
+        }
+    );
+    assert_eq!(
+        actor_builder()
+            .home_server_domain("polyphony.example")
+            .build_actor::<Ed25519Signature, Ed25519PublicKey>(&priv_key)
+            .err()
+            .unwrap(),
+        BuilderError::DomainMismatch {
+            federation_id: "flori@polyphony.chat".to_string(),
+            home_server_domain: "polyphony.example".to_string()
+        }
+    );
+    assert_eq!(
+        actor_builder()
+            .build_home_server::<Ed25519Signature, Ed25519PublicKey>(&priv_key)
+            .err()
+            .unwrap(),
+        BuilderError::UnexpectedField {
+            field: "federation_id"
+        }
+    );
+    assert_eq!(
+        IdCsrBuilder::new()
+            .home_server_domain("polyphony..chat")
+            .build_home_server::<Ed25519Signature, Ed25519PublicKey>(&priv_key)
+            .err()
+            .unwrap(),
+        BuilderError::InvalidHomeServerDomain("polyphony..chat".to_string())
+    );
+}
+
+#[test]
+fn cert_builder_errors() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let csr = actor_csr("flori", &gen_priv_key());
+    assert_eq!(
+        IdCertBuilder::new(csr.clone())
+            .serial_number(Uint::new(&[2]).unwrap())
+            .home_server_domain("polyphony.chat")
+            .valid_for(Duration::from_secs(990))
+            .build_actor(&home_server_key)
+            .err()
+            .unwrap(),
+        BuilderError::MissingField {
+            field: "not_before"
+        }
+    );
+    assert!(matches!(
+        IdCertBuilder::new(csr.clone())
+            .serial_number(Uint::new(&[2]).unwrap())
+            .home_server_domain("polyphony.example")
+            .not_before(10)
+            .valid_for(Duration::from_secs(990))
+            .build_actor(&home_server_key)
+            .err()
+            .unwrap(),
+        BuilderError::ConversionError(_)
+    ));
+    assert_eq!(
+        IdCertBuilder::new(csr)
+            .serial_number(Uint::new(&[2]).unwrap())
+            .home_server_domain("polyphony.chat")
+            .not_before(u64::MAX)
+            .valid_for(Duration::from_secs(990))
+            .build_actor(&home_server_key)
+            .err()
+            .unwrap(),
+        BuilderError::InvalidValidity
+    );
+}
@@ -0,0 +1,203 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::str::FromStr;
+
+use der::asn1::Uint;
+use polyproto::certs::capabilities::{
+    Capabilities, ExtendedKeyUsage, ExtendedKeyUsages, OID_EXTENDED_KEY_USAGE,
+};
+use polyproto::certs::idcert::IdCert;
+use polyproto::certs::idcsr::IdCsr;
+use polyproto::certs::policy::ValidationPolicy;
+use polyproto::certs::Target;
+use polyproto::errors::ViolationCode;
+use polyproto::Constrained;
+use spki::ObjectIdentifier;
+use x509_cert::attr::Attributes;
+use x509_cert::ext::{Extension, Extensions};
+
+use crate::common::*;
+
+fn actor_capabilities(usages: &[ExtendedKeyUsage]) -> Capabilities {
+    let mut capabilities = Capabilities::default_actor();
+    capabilities.extended_key_usage = ExtendedKeyUsages::new(usages);
+    capabilities
+}
+
+#[test]
+fn extension_round_trip() {
+    init_logger();
+    let other = ExtendedKeyUsage::Other(ObjectIdentifier::from_str("1.3.6.1.4.1.99999.1").unwrap());
+    let usages = ExtendedKeyUsages::new(&[ExtendedKeyUsage::ClientAuth, other]);
+    let extension = Extension::try_from(usages.clone()).unwrap();
+    assert_eq!(extension.extn_id.to_string(), OID_EXTENDED_KEY_USAGE);
+    assert!(!extension.critical);
+    assert_eq!(ExtendedKeyUsages::try_from(extension).unwrap(), usages);
+}
+
+#[test]
+fn critical_flag_is_preserved() {
+    init_logger();
+    let mut extension =
+        Extension::try_from(ExtendedKeyUsages::new(&[ExtendedKeyUsage::ClientAuth])).unwrap();
+    extension.critical = true;
+    let usages = ExtendedKeyUsages::try_from(extension.clone()).unwrap();
+    assert!(usages.critical);
+    assert_eq!(Extension::try_from(usages).unwrap(), extension);
+}
+
+#[test]
+fn capabilities_round_trip() {
+    init_logger();
+    let capabilities = actor_capabilities(&[ExtendedKeyUsage::ClientAuth]);
+    let extensions = Extensions::try_from(capabilities.clone()).unwrap();
+    assert_eq!(extensions.len(), 3);
+    assert_eq!(Capabilities::try_from(extensions).unwrap(), capabilities);
+    let attributes = Attributes::try_from(capabilities.clone()).unwrap();
+    assert_eq!(Capabilities::try_from(attributes).unwrap(), capabilities);
+
+    // Without extended key usages, no extension is emitted at all
+    let extensions = Extensions::try_from(Capabilities::default_actor()).unwrap();
+    assert_eq!(extensions.len(), 2);
+}
+
+fn tls_policy() -> ValidationPolicy {
+    ValidationPolicy {
+        message_signing_purpose: Some(ExtendedKeyUsage::ClientAuth),
+        server_auth_purpose: Some(ExtendedKeyUsage::ServerAuth),
+        ..ValidationPolicy::spec_default()
+    }
+}
+
+#[test]
+fn no_purposes_required_by_default() {
+    init_logger();
+    for policy in [ValidationPolicy::spec_default(), ValidationPolicy::strict()] {
+        assert_eq!(policy.required_purpose(Target::Actor), None);
+        assert_eq!(policy.required_purpose(Target::HomeServer), None);
+    }
+    actor_capabilities(&[ExtendedKeyUsage::ServerAuth])
+        .validate(Some(Target::Actor))
+        .unwrap();
+}
+
+#[test]
+fn required_per_target() {
+    init_logger();
+    let policy = tls_policy();
+    let capabilities = actor_capabilities(&[ExtendedKeyUsage::ClientAuth]);
+    capabilities
+        .validate_with_policy(Some(Target::Actor), &policy)
+        .unwrap();
+    capabilities.validate_with_policy(None, &policy).unwrap();
+    let capabilities = actor_capabilities(&[ExtendedKeyUsage::ServerAuth]);
+    assert!(capabilities
+        .validate_with_policy(Some(Target::Actor), &policy)
+        .is_err());
+    capabilities.validate_with_policy(None, &policy).unwrap();
+    Capabilities::default_actor()
+        .validate_with_policy(Some(Target::Actor), &policy)
+        .unwrap();
+
+    let mut capabilities = Capabilities::default_home_server();
+    capabilities.extended_key_usage = ExtendedKeyUsages::new(&[ExtendedKeyUsage::ClientAuth]);
+    assert!(capabilities
+        .validate_with_policy(Some(Target::HomeServer), &policy)
+        .is_err());
+    capabilities.extended_key_usage = ExtendedKeyUsages::new(&[ExtendedKeyUsage::ServerAuth]);
+    capabilities
+        .validate_with_policy(Some(Target::HomeServer), &policy)
+        .unwrap();
+}
+
+#[test]
+fn purposes_are_configurable_per_target() {
+    init_logger();
+    let message_signing =
+        ExtendedKeyUsage::Other(ObjectIdentifier::from_str("1.3.6.1.4.1.99999.2").unwrap());
+    let policy = ValidationPolicy {
+        message_signing_purpose: Some(message_signing),
+        ..tls_policy()
+    };
+    assert_eq!(
+        policy.required_purpose(Target::Actor),
+        Some(message_signing)
+    );
+    assert_eq!(
+        policy.required_purpose(Target::HomeServer),
+        Some(ExtendedKeyUsage::ServerAuth)
+    );
+
+    let capabilities = actor_capabilities(&[message_signing]);
+    capabilities
+        .validate_with_policy(Some(Target::Actor), &policy)
+        .unwrap();
+    assert!(capabilities
+        .validate_with_policy(Some(Target::Actor), &tls_policy())
+        .is_err());
+    let violations = actor_capabilities(&[ExtendedKeyUsage::ClientAuth])
+        .validate_all_with_policy(Some(Target::Actor), &policy);
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].code, ViolationCode::MissingExtendedKeyUsage);
+
+    // The purposes apply to CSRs and certificates as well
+    let csr = IdCsr::new(
+        &actor_subject("flori"),
+        &gen_priv_key(),
+        &capabilities,
+        None,
+    )
+    .unwrap();
+    csr.validate_with_policy(Some(Target::Actor), &policy)
+        .unwrap();
+    assert!(csr
+        .validate_with_policy(Some(Target::Actor), &tls_policy())
+        .is_err());
+
+    let mut capabilities = Capabilities::default_home_server();
+    capabilities.extended_key_usage = ExtendedKeyUsages::new(&[ExtendedKeyUsage::ClientAuth]);
+    capabilities
+        .validate_with_policy(
+            Some(Target::HomeServer),
+            &ValidationPolicy {
+                server_auth_purpose: None,
+                ..policy
+            },
+        )
+        .unwrap();
+}
+
+#[test]
+fn id_cert_with_extended_key_usage() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let actor_key = gen_priv_key();
+    let capabilities = actor_capabilities(&[ExtendedKeyUsage::ClientAuth]);
+    let csr = IdCsr::new(
+        &actor_subject("flori"),
+        &actor_key,
+        &capabilities,
+        Some(Target::Actor),
+    )
+    .unwrap();
+    let csr = IdCsr::<Ed25519Signature, Ed25519PublicKey>::from_der(
+        &csr.to_der().unwrap(),
+        Some(Target::Actor),
+    )
+    .unwrap();
+    assert_eq!(csr.inner_csr().capabilities, capabilities);
+    let cert = IdCert::from_actor_csr(
+        csr,
+        &home_server_key,
+        Uint::new(&[8]).unwrap(),
+        home_server_subject(),
+        default_validity(),
+    )
+    .unwrap();
+    let cert =
+        IdCert::<Ed25519Signature, Ed25519PublicKey>::from_der_unchecked(&cert.to_der().unwrap())
+            .unwrap();
+    assert_eq!(cert.id_cert_tbs().capabilities, capabilities);
+}
@@ -0,0 +1,76 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::str::FromStr;
+
+use der::asn1::OctetString;
+use der::{Decode, Encode};
+use polyproto::certs::capabilities::Capabilities;
+use polyproto::certs::idcert::IdCert;
+use polyproto::errors::ConversionError;
+use spki::ObjectIdentifier;
+use x509_cert::ext::{Extension, Extensions};
+use x509_cert::Certificate;
+
+use crate::common::*;
+
+/// An extension from a private arc, which is not interpreted by [Capabilities]
+const OID_UNKNOWN_EXTENSION: &str = "1.3.6.1.4.1.99999.1";
+
+fn unknown_extension(critical: bool) -> Extension {
+    Extension {
+        extn_id: ObjectIdentifier::from_str(OID_UNKNOWN_EXTENSION).unwrap(),
+        critical,
+        extn_value: OctetString::new(OctetString::new([1, 2, 3, 4]).unwrap().to_der().unwrap())
+            .unwrap(),
+    }
+}
+
+#[test]
+fn unknown_non_critical_extension_round_trip() {
+    init_logger();
+    let mut extensions = Extensions::try_from(Capabilities::default_actor()).unwrap();
+    extensions.push(unknown_extension(false));
+    let capabilities = Capabilities::try_from(extensions.clone()).unwrap();
+    assert_eq!(
+        capabilities.unknown_extensions,
+        vec![unknown_extension(false)]
+    );
+    assert_eq!(Extensions::try_from(capabilities).unwrap(), extensions);
+}
+
+#[test]
+fn unknown_critical_extension_is_rejected() {
+    init_logger();
+    let mut extensions = Extensions::try_from(Capabilities::default_actor()).unwrap();
+    extensions.push(unknown_extension(true));
+    assert_eq!(
+        Capabilities::try_from(extensions).err().unwrap(),
+        ConversionError::UnknownCriticalExtension {
+            oid: ObjectIdentifier::from_str(OID_UNKNOWN_EXTENSION).unwrap()
+        }
+    );
+}
+
+#[test]
+fn id_cert_with_unknown_extension() {
+    init_logger();
+    let mut certificate = Certificate::try_from(actor_id_cert("flori")).unwrap();
+    certificate
+        .tbs_certificate
+        .extensions
+        .as_mut()
+        .unwrap()
+        .push(unknown_extension(false));
+    let der = certificate.to_der().unwrap();
+    let cert = IdCert::<Ed25519Signature, Ed25519PublicKey>::from_der_unchecked(&der).unwrap();
+    assert_eq!(
+        cert.id_cert_tbs().capabilities.unknown_extensions,
+        vec![unknown_extension(false)]
+    );
+    assert_eq!(
+        Certificate::from_der(&cert.to_der().unwrap()).unwrap(),
+        certificate
+    );
+}
@@ -0,0 +1,195 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::str::FromStr;
+
+use der::asn1::Uint;
+use polyproto::certs::capabilities::{Capabilities, KeyUsage};
+use polyproto::certs::chain::{verify_chain, CertChain};
+use polyproto::certs::idcert::IdCert;
+use polyproto::certs::idcsr::IdCsr;
+use polyproto::certs::Target;
+use polyproto::errors::{InvalidCert, InvalidChain};
+use polyproto::Name;
+
+use crate::common::*;
+
+#[test]
+fn valid_chain() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let root = home_server_cert(&home_server_key, 1, validity(10, 1000));
+    let actor = actor_cert(
+        &home_server_key,
+        &actor_subject("flori"),
+        home_server_subject(),
+        2,
+        validity(20, 900),
+    );
+    verify_chain(&actor, std::slice::from_ref(&root), 100).unwrap();
+    CertChain::new(actor, vec![root]).verify(100).unwrap();
+}
+
+#[test]
+fn missing_issuer() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let actor = actor_cert(
+        &home_server_key,
+        &actor_subject("flori"),
+        home_server_subject(),
+        2,
+        validity(20, 900),
+    );
+    assert_eq!(
+        verify_chain(&actor, &[], 100).err().unwrap(),
+        InvalidChain::MissingIssuer
+    );
+}
+
+#[test]
+fn wrong_issuer_key() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let root = home_server_cert(&gen_priv_key(), 1, validity(10, 1000));
+    let actor = actor_cert(
+        &home_server_key,
+        &actor_subject("flori"),
+        home_server_subject(),
+        2,
+        validity(20, 900),
+    );
+    assert_eq!(
+        verify_chain(&actor, &[root], 100).err().unwrap(),
+        InvalidChain::KeyIdentifierMismatch { index: 0 }
+    );
+}
+
+#[test]
+fn issuer_subject_mismatch() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let root = home_server_cert(&home_server_key, 1, validity(10, 1000));
+    let actor = actor_cert(
+        &home_server_key,
+        &actor_subject("flori"),
+        Name::from_str("CN=root,DC=polyphony,DC=chat").unwrap(),
+        2,
+        validity(20, 900),
+    );
+    assert_eq!(
+        verify_chain(&actor, &[root], 100).err().unwrap(),
+        InvalidChain::IssuerMismatch { index: 0 }
+    );
+}
+
+#[test]
+fn validity_not_nested() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let root = home_server_cert(&home_server_key, 1, validity(10, 500));
+    let actor = actor_cert(
+        &home_server_key,
+        &actor_subject("flori"),
+        home_server_subject(),
+        2,
+        validity(20, 900),
+    );
+    assert_eq!(
+        verify_chain(&actor, &[root], 100).err().unwrap(),
+        InvalidChain::ValidityNotNested { index: 0 }
+    );
+}
+
+#[test]
+fn expired_actor_cert() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let root = home_server_cert(&home_server_key, 1, validity(10, 1000));
+    let actor = actor_cert(
+        &home_server_key,
+        &actor_subject("flori"),
+        home_server_subject(),
+        2,
+        validity(20, 900),
+    );
+    assert_eq!(
+        verify_chain(&actor, &[root], 950).err().unwrap(),
+        InvalidChain::InvalidCert {
+            index: 0,
+            source: InvalidCert::InvalidValidity
+        }
+    );
+}
+
+#[test]
+fn issuer_without_key_cert_sign() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let mut root = home_server_cert(&home_server_key, 1, validity(10, 1000));
+    root.id_cert_tbs_mut()
+        .capabilities
+        .key_usage
+        .key_usages
+        .retain(|usage| usage != &KeyUsage::KeyCertSign);
+    let actor = actor_cert(
+        &home_server_key,
+        &actor_subject("flori"),
+        home_server_subject(),
+        2,
+        validity(20, 900),
+    );
+    assert_eq!(
+        verify_chain(&actor, &[root], 100).err().unwrap(),
+        InvalidChain::MissingKeyCertSign { index: 1 }
+    );
+}
+
+#[test]
+fn path_length_exceeded() {
+    init_logger();
+    let root_key = gen_priv_key();
+    let intermediate_key = gen_priv_key();
+    let mut capabilities = Capabilities::default_home_server();
+    capabilities.basic_constraints.path_length = Some(0);
+    let root = IdCert::from_ca_csr(
+        IdCsr::new(
+            &home_server_subject(),
+            &root_key,
+            &capabilities,
+            Some(Target::HomeServer),
+        )
+        .unwrap(),
+        &root_key,
+        Uint::new(&[1]).unwrap(),
+        home_server_subject(),
+        validity(10, 1000),
+    )
+    .unwrap();
+    let intermediate = IdCert::from_ca_csr(
+        home_server_csr(&intermediate_key),
+        &root_key,
+        Uint::new(&[3]).unwrap(),
+        home_server_subject(),
+        validity(10, 1000),
+    )
+    .unwrap();
+    let actor = actor_cert(
+        &intermediate_key,
+        &actor_subject("flori"),
+        home_server_subject(),
+        2,
+        validity(20, 900),
+    );
+    assert_eq!(
+        verify_chain(&actor, &[intermediate, root], 100)
+            .err()
+            .unwrap(),
+        InvalidChain::PathLengthExceeded {
+            index: 2,
+            max: 0,
+            actual: 1
+        }
+    );
+}
@@ -0,0 +1,59 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use polyproto::certs::idcert::IdCert;
+use polyproto::certs::idcsr::IdCsr;
+use polyproto::key::{PrivateKey, PublicKey};
+use sha2::Digest as _;
+
+use crate::common::*;
+
+#[test]
+fn id_cert_fingerprint() {
+    init_logger();
+    let cert = actor_id_cert("flori");
+    let fingerprint = cert.fingerprint::<Sha256>().unwrap();
+    let expected = sha2::Sha256::digest(cert.clone().to_der().unwrap());
+    assert_eq!(fingerprint.as_bytes(), expected.as_slice());
+    assert_eq!(fingerprint.to_hex().len(), 64);
+    assert_eq!(fingerprint.to_string(), fingerprint.to_hex());
+
+    let decoded = IdCert::<Ed25519Signature, Ed25519PublicKey>::from_der_unchecked(
+        &cert.clone().to_der().unwrap(),
+    )
+    .unwrap();
+    assert_eq!(decoded.fingerprint::<Sha256>().unwrap(), fingerprint);
+    assert_ne!(
+        actor_id_cert("flori").fingerprint::<Sha256>().unwrap(),
+        fingerprint
+    );
+}
+
+#[test]
+fn id_csr_fingerprint() {
+    init_logger();
+    let csr = actor_csr("flori", &gen_priv_key());
+    let fingerprint = csr.fingerprint::<Sha256>().unwrap();
+    let pem = csr.clone().to_pem(der::pem::LineEnding::LF).unwrap();
+    let decoded = IdCsr::<Ed25519Signature, Ed25519PublicKey>::from_pem_unchecked(&pem).unwrap();
+    assert_eq!(decoded.fingerprint::<Sha256>().unwrap(), fingerprint);
+    assert_eq!(
+        fingerprint.as_bytes(),
+        sha2::Sha256::digest(csr.to_der().unwrap()).as_slice()
+    );
+}
+
+#[test]
+fn public_key_info_fingerprint() {
+    init_logger();
+    let key = gen_priv_key();
+    let public_key_info = key.pubkey().public_key_info();
+    let fingerprint = public_key_info.fingerprint::<Sha256>().unwrap();
+    assert_eq!(
+        fingerprint.as_bytes(),
+        sha2::Sha256::digest(public_key_info.to_der().unwrap()).as_slice()
+    );
+    assert_eq!(fingerprint.to_base64().len(), 44);
+    assert_eq!(fingerprint.to_hex_colon_separated().split(':').count(), 32);
+}
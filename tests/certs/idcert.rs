@@ -12,18 +12,22 @@ use der::{Decode, Encode};
 use ed25519_dalek::{Signature as Ed25519DalekSignature, Signer, SigningKey, VerifyingKey};
 use polyproto::certs::capabilities::{self, Capabilities};
 use polyproto::certs::idcert::IdCert;
+use polyproto::certs::serial_number::{MonotonicSerialNumberGenerator, SerialNumberGenerator};
 use polyproto::certs::{PublicKeyInfo, Target};
-use polyproto::errors::composite::ConversionError;
+use polyproto::clock::{FixedClock, OffsetClock};
+use polyproto::errors::base::InvalidInput;
+use polyproto::errors::composite::{BuilderError, ConversionError, InvalidCert};
 use polyproto::key::{PrivateKey, PublicKey};
 use polyproto::signature::Signature;
+use polyproto::Constrained;
 use rand::rngs::OsRng;
 use spki::{AlgorithmIdentifierOwned, ObjectIdentifier, SignatureBitStringEncoding};
 use thiserror::Error;
 use x509_cert::attr::Attributes;
-use x509_cert::name::RdnSequence;
+use x509_cert::name::{Name, RdnSequence};
 use x509_cert::request::CertReq;
 use x509_cert::time::{Time, Validity};
-use x509_cert::Certificate;
+use x509_cert::{Certificate, TbsCertificate};
 
 use crate::common::*;
 
@@ -192,7 +196,11 @@ fn cert_from_pem() {
     .unwrap();
     log::trace!(
         "Cert from pem key usages: {:#?}",
-        cert_from_pem.id_cert_tbs.capabilities.key_usage.key_usages
+        cert_from_pem
+            .id_cert_tbs()
+            .capabilities
+            .key_usage
+            .key_usages
     );
     assert_eq!(cert_from_pem, cert);
 
@@ -228,7 +236,11 @@ fn cert_from_pem() {
     .unwrap();
     log::trace!(
         "Cert from pem key usages: {:#?}",
-        cert_from_pem.id_cert_tbs.capabilities.key_usage.key_usages
+        cert_from_pem
+            .id_cert_tbs()
+            .capabilities
+            .key_usage
+            .key_usages
     );
     assert_eq!(cert_from_pem, cert);
 }
@@ -277,7 +289,11 @@ fn cert_from_der() {
     .unwrap();
     log::trace!(
         "Cert from pem key usages: {:#?}",
-        cert_from_der.id_cert_tbs.capabilities.key_usage.key_usages
+        cert_from_der
+            .id_cert_tbs()
+            .capabilities
+            .key_usage
+            .key_usages
     );
     assert_eq!(cert_from_der, cert);
 
@@ -313,7 +329,405 @@ fn cert_from_der() {
     .unwrap();
     log::trace!(
         "Cert from pem key usages: {:#?}",
-        cert_from_der.id_cert_tbs.capabilities.key_usage.key_usages
+        cert_from_der
+            .id_cert_tbs()
+            .capabilities
+            .key_usage
+            .key_usages
     );
     assert_eq!(cert_from_der, cert);
 }
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn identity_accessors() {
+    init_logger();
+    let cert = actor_id_cert("flori");
+    assert_eq!(
+        cert.federation_id().unwrap().to_string(),
+        "flori@polyphony.chat"
+    );
+    assert_eq!(cert.session_id().unwrap().to_string(), "client1");
+    assert_eq!(cert.home_server_domain().unwrap(), "polyphony.chat");
+    assert_eq!(cert.common_name().unwrap(), "flori");
+
+    let cert = home_server_id_cert();
+    assert_eq!(cert.home_server_domain().unwrap(), "polyphony.chat");
+    assert!(cert.federation_id().is_err());
+    assert!(cert.session_id().is_err());
+    assert!(cert.common_name().is_err());
+}
+
+#[test]
+fn validity_with_clock() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let cert = IdCert::from_actor_csr(
+        actor_csr("flori", &gen_priv_key()),
+        &home_server_key,
+        Uint::new(&[8]).unwrap(),
+        home_server_subject(),
+        default_validity(),
+    )
+    .unwrap();
+    // default_validity() is valid from 10 to 1000
+    assert!(cert.valid_now(&FixedClock::new(10)));
+    assert!(!cert.valid_now(&FixedClock::new(1001)));
+    assert!(cert.valid_now(&FixedClock::new(1001).with_skew_tolerance(1)));
+    assert!(cert.valid_now(&FixedClock::new(5).with_skew_tolerance(5)));
+    assert!(!cert.valid_now(&OffsetClock::new(FixedClock::new(500), -495)));
+    cert.full_verify_actor_with_clock(&FixedClock::new(500), home_server_key.pubkey())
+        .unwrap();
+    assert!(cert
+        .full_verify_actor_with_clock(&FixedClock::new(2000), home_server_key.pubkey())
+        .is_err());
+    assert!(cert
+        .full_verify_actor_with_clock(
+            &FixedClock::new(2000).with_skew_tolerance(1000),
+            home_server_key.pubkey()
+        )
+        .is_ok());
+}
+
+#[test]
+fn self_signed_home_server() {
+    init_logger();
+    let key = gen_priv_key();
+    let cert = IdCert::new_self_signed_home_server(
+        &key,
+        "polyphony.chat",
+        default_validity(),
+        Uint::new(&[1]).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(cert.id_cert_tbs().issuer, cert.id_cert_tbs().subject);
+    assert_eq!(cert.id_cert_tbs().subject, home_server_subject());
+    assert_eq!(
+        cert.id_cert_tbs().capabilities,
+        Capabilities::default_home_server()
+    );
+    assert_eq!(&cert.id_cert_tbs().subject_public_key, key.pubkey());
+    assert_eq!(cert.home_server_domain().unwrap(), "polyphony.chat");
+    cert.validate(Some(Target::HomeServer)).unwrap();
+    cert.full_verify_home_server(10).unwrap();
+    cert.full_verify_home_server(1000).unwrap();
+    assert!(cert.full_verify_home_server(1001).is_err());
+}
+
+#[test]
+fn self_signed_home_server_invalid_input() {
+    init_logger();
+    let key = gen_priv_key();
+    assert!(matches!(
+        IdCert::new_self_signed_home_server(
+            &key,
+            "polyphony..chat",
+            default_validity(),
+            Uint::new(&[1]).unwrap(),
+        ),
+        Err(BuilderError::InvalidHomeServerDomain(_))
+    ));
+    let inverted = Validity {
+        not_before: default_validity().not_after,
+        not_after: default_validity().not_before,
+    };
+    assert_eq!(
+        IdCert::new_self_signed_home_server(
+            &key,
+            "polyphony.chat",
+            inverted,
+            Uint::new(&[1]).unwrap()
+        )
+        .err()
+        .unwrap(),
+        BuilderError::InvalidValidity
+    );
+}
+
+#[test]
+fn non_conforming_serial_number_is_rejected() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    for serial_number in [Uint::new(&[0]).unwrap(), Uint::new(&[0x7f; 21]).unwrap()] {
+        assert!(IdCert::from_actor_csr(
+            actor_csr("flori", &gen_priv_key()),
+            &home_server_key,
+            serial_number,
+            home_server_subject(),
+            default_validity(),
+        )
+        .is_err());
+    }
+}
+
+#[test]
+fn negative_serial_number_is_rejected() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let mut certificate = Certificate::try_from(actor_id_cert("flori")).unwrap();
+    // DER INTEGER -1, whose content octet equals the positive integer 255
+    certificate.tbs_certificate.serial_number =
+        <x509_cert::serial_number::SerialNumber>::from_der(&[0x02, 0x01, 0xff]).unwrap();
+    certificate.signature = home_server_key
+        .sign(&certificate.tbs_certificate.to_der().unwrap())
+        .unwrap()
+        .to_bitstring()
+        .unwrap();
+    let der = certificate.to_der().unwrap();
+    assert!(matches!(
+        IdCert::<Ed25519Signature, Ed25519PublicKey>::from_der_unchecked(&der).unwrap_err(),
+        ConversionError::ConstraintError(_)
+    ));
+}
+
+#[test]
+fn issue_with_serial_number_generator() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let mut generator = MonotonicSerialNumberGenerator::new(41);
+    let cert = IdCert::from_actor_csr(
+        actor_csr("flori", &gen_priv_key()),
+        &home_server_key,
+        generator.next_serial_number().unwrap(),
+        home_server_subject(),
+        default_validity(),
+    )
+    .unwrap();
+    assert_eq!(cert.id_cert_tbs().serial_number, Uint::new(&[42]).unwrap());
+    assert_eq!(generator.last_issued(), 42);
+}
+
+#[test]
+fn verify_against_original_encoding() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let cert = IdCert::from_actor_csr(
+        actor_csr("flori", &gen_priv_key()),
+        &home_server_key,
+        Uint::new(&[8]).unwrap(),
+        home_server_subject(),
+        default_validity(),
+    )
+    .unwrap();
+    // A different extension order is valid DER, but differs from what this crate would encode
+    let mut certificate = Certificate::try_from(cert).unwrap();
+    certificate
+        .tbs_certificate
+        .extensions
+        .as_mut()
+        .unwrap()
+        .reverse();
+    let tbs_der = certificate.tbs_certificate.to_der().unwrap();
+    certificate.signature = home_server_key
+        .sign(&tbs_der)
+        .unwrap()
+        .to_bitstring()
+        .unwrap();
+    let der = certificate.to_der().unwrap();
+
+    let decoded = IdCert::<Ed25519Signature, Ed25519PublicKey>::from_der(
+        &der,
+        Target::Actor,
+        100,
+        home_server_key.pubkey(),
+    )
+    .unwrap();
+    assert_eq!(decoded.signature_data().unwrap(), tbs_der);
+    assert_eq!(decoded.clone().to_der().unwrap(), der);
+
+    // Modified certificates are re-encoded, and no longer verify
+    let mut modified = decoded;
+    modified.id_cert_tbs_mut().serial_number = Uint::new(&[9]).unwrap();
+    assert_ne!(modified.signature_data().unwrap(), tbs_der);
+    assert!(modified
+        .full_verify_actor(100, home_server_key.pubkey())
+        .is_err());
+}
+
+#[test]
+fn verify_non_canonical_encoding() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let actor_key = gen_priv_key();
+    // A multi-valued RDN is a SET OF attributes, whose elements must be sorted in DER
+    let subject = Name::from_str(
+        "CN=flori,DC=polyphony,DC=chat,UID=flori@polyphony.chat+uniqueIdentifier=client1",
+    )
+    .unwrap();
+    let cert = IdCert::from_actor_csr(
+        polyproto::certs::idcsr::IdCsr::new(
+            &subject,
+            &actor_key,
+            &Capabilities::default_actor(),
+            Some(Target::Actor),
+        )
+        .unwrap(),
+        &home_server_key,
+        Uint::new(&[8]).unwrap(),
+        home_server_subject(),
+        default_validity(),
+    )
+    .unwrap();
+    let rdn = subject.0.iter().find(|rdn| rdn.0.len() == 2).unwrap();
+    let tbs_der = swap_encodings(
+        &cert.signature_data().unwrap(),
+        &rdn.0.get(0).unwrap().to_der().unwrap(),
+        &rdn.0.get(1).unwrap().to_der().unwrap(),
+    );
+    // Decoding sorts the SET OF, so that re-encoding the decoded contents yields other bytes
+    assert_ne!(
+        TbsCertificate::from_der(&tbs_der)
+            .unwrap()
+            .to_der()
+            .unwrap(),
+        tbs_der
+    );
+    let der = encode_sequence(&[
+        &tbs_der,
+        &cert.id_cert_tbs().signature_algorithm.to_der().unwrap(),
+        &home_server_key
+            .sign(&tbs_der)
+            .unwrap()
+            .to_bitstring()
+            .unwrap()
+            .to_der()
+            .unwrap(),
+    ]);
+
+    let decoded = IdCert::<Ed25519Signature, Ed25519PublicKey>::from_der(
+        &der,
+        Target::Actor,
+        100,
+        home_server_key.pubkey(),
+    )
+    .unwrap();
+    assert_eq!(decoded.signature_data().unwrap(), tbs_der);
+    assert_eq!(decoded.clone().to_der().unwrap(), der);
+
+    let pem = der::pem::encode_string("CERTIFICATE", der::pem::LineEnding::LF, &der).unwrap();
+    let decoded = IdCert::<Ed25519Signature, Ed25519PublicKey>::from_pem(
+        &pem,
+        Target::Actor,
+        100,
+        home_server_key.pubkey(),
+    )
+    .unwrap();
+    assert_eq!(decoded.to_pem(der::pem::LineEnding::LF).unwrap(), pem);
+}
+
+fn other_algorithm() -> AlgorithmIdentifierOwned {
+    AlgorithmIdentifierOwned {
+        // Ed448
+        oid: ObjectIdentifier::from_str("1.3.101.113").unwrap(),
+        parameters: None,
+    }
+}
+
+fn assert_algorithm_mismatch(der: &[u8]) {
+    let error = IdCert::<Ed25519Signature, Ed25519PublicKey>::from_der_unchecked(der).unwrap_err();
+    assert!(matches!(
+        error,
+        ConversionError::InvalidCert(InvalidCert::AlgorithmMismatch { .. })
+    ));
+}
+
+#[test]
+fn signature_algorithms_must_match() {
+    init_logger();
+    let cert = Certificate::try_from(actor_id_cert("flori")).unwrap();
+    IdCert::<Ed25519Signature, Ed25519PublicKey>::from_der_unchecked(&cert.to_der().unwrap())
+        .unwrap();
+
+    // RFC 5280, Section 4.1.1.2: outer and TBS signature algorithms must be equal
+    let mut outer_mismatch = cert.clone();
+    outer_mismatch.signature_algorithm = other_algorithm();
+    assert_algorithm_mismatch(&outer_mismatch.to_der().unwrap());
+
+    // Both are equal, but differ from the algorithm of the signature type
+    let mut signature_type_mismatch = cert.clone();
+    signature_type_mismatch.signature_algorithm = other_algorithm();
+    signature_type_mismatch.tbs_certificate.signature = other_algorithm();
+    assert_algorithm_mismatch(&signature_type_mismatch.to_der().unwrap());
+
+    // The subject public key is declared to use a different algorithm than the decoded key
+    let mut key_mismatch = cert;
+    key_mismatch
+        .tbs_certificate
+        .subject_public_key_info
+        .algorithm = other_algorithm();
+    assert_algorithm_mismatch(&key_mismatch.to_der().unwrap());
+}
+
+#[test]
+fn full_verify_checks_signature_algorithm() {
+    init_logger();
+    let priv_key = gen_priv_key();
+    let mut cert = IdCert::new_self_signed_home_server(
+        &priv_key,
+        "polyphony.chat",
+        default_validity(),
+        Uint::new(&[1]).unwrap(),
+    )
+    .unwrap();
+    cert.full_verify_home_server(100).unwrap();
+    cert.id_cert_tbs_mut().signature_algorithm = other_algorithm();
+    // Re-sign, so that only the algorithm is wrong
+    cert.signature = priv_key.sign(&cert.signature_data().unwrap()).unwrap();
+    assert_eq!(
+        cert.full_verify_home_server(100).unwrap_err(),
+        InvalidCert::AlgorithmMismatch {
+            expected: Ed25519Signature::algorithm_identifier().unwrap().oid,
+            found: other_algorithm().oid,
+        }
+    );
+}
+
+#[test]
+fn malformed_signatures_are_rejected() {
+    init_logger();
+    let cert = actor_id_cert("flori");
+    let signature_bytes = cert.signature.to_bytes();
+    assert_eq!(
+        Ed25519Signature::try_from_bytes(&signature_bytes).unwrap(),
+        cert.signature
+    );
+    let certificate = Certificate::try_from(cert).unwrap();
+
+    let mut truncated = certificate.clone();
+    truncated.signature = BitString::from_bytes(&signature_bytes[..63]).unwrap();
+    assert_eq!(
+        IdCert::<Ed25519Signature, Ed25519PublicKey>::from_der_unchecked(
+            &truncated.to_der().unwrap()
+        )
+        .unwrap_err(),
+        ConversionError::InvalidInput(InvalidInput::Length {
+            min_length: 64,
+            max_length: 64,
+            actual_length: "63".to_string(),
+        })
+    );
+
+    // Signatures must consist of whole octets
+    let mut unused_bits = certificate.clone();
+    unused_bits.signature = BitString::new(1, signature_bytes).unwrap();
+    assert!(matches!(
+        IdCert::<Ed25519Signature, Ed25519PublicKey>::from_der_unchecked(
+            &unused_bits.to_der().unwrap()
+        )
+        .unwrap_err(),
+        ConversionError::InvalidInput(InvalidInput::Malformed(_))
+    ));
+
+    let mut bad_key = certificate;
+    bad_key
+        .tbs_certificate
+        .subject_public_key_info
+        .subject_public_key = BitString::from_bytes(&[0; 31]).unwrap();
+    assert!(matches!(
+        IdCert::<Ed25519Signature, Ed25519PublicKey>::from_der_unchecked(
+            &bad_key.to_der().unwrap()
+        )
+        .unwrap_err(),
+        ConversionError::InvalidInput(InvalidInput::Length { .. })
+    ));
+}
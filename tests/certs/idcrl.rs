@@ -0,0 +1,435 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::str::FromStr;
+
+use der::asn1::{OctetString, Uint};
+use der::oid::AssociatedOid;
+use der::pem::LineEnding;
+use der::{Decode, Encode};
+use polyproto::certs::capabilities::KeyUsage;
+use polyproto::certs::idcert::IdCert;
+use polyproto::certs::idcrl::{IdCrl, RevokedIdCert};
+use polyproto::errors::{
+    ConstraintError, InvalidCert, ERR_MSG_CERT_ISSUER_MISMATCH,
+    ERR_MSG_CRL_AUTHORITY_KEY_ID_MISMATCH,
+};
+use polyproto::key::{PrivateKey, PublicKey};
+use polyproto::signature::Signature;
+use polyproto::Name;
+use spki::{ObjectIdentifier, SignatureBitStringEncoding};
+use x509_cert::crl::{CertificateList, RevokedCert, TbsCertList};
+use x509_cert::ext::pkix::crl::CrlReason;
+use x509_cert::ext::pkix::{AuthorityKeyIdentifier, CrlNumber};
+use x509_cert::ext::Extension;
+use x509_cert::serial_number::SerialNumber;
+use x509_cert::Version;
+
+use crate::common::*;
+
+fn crl(
+    home_server_key: &Ed25519PrivateKey,
+    revoked: Vec<RevokedIdCert>,
+) -> IdCrl<Ed25519Signature, Ed25519PublicKey> {
+    IdCrl::new(
+        home_server_subject(),
+        Uint::new(&[1]).unwrap(),
+        revoked,
+        time(100),
+        Some(time(500)),
+        home_server_key,
+    )
+    .unwrap()
+}
+
+#[test]
+fn der_and_pem_roundtrip() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let home_server_cert = home_server_cert(&home_server_key, 1, default_validity());
+    let crl = crl(
+        &home_server_key,
+        vec![
+            RevokedIdCert::new(
+                Uint::new(&[8]).unwrap(),
+                time(50),
+                Some(CrlReason::KeyCompromise),
+            ),
+            RevokedIdCert::new(Uint::new(&[9]).unwrap(), time(60), None),
+        ],
+    );
+    let der = crl.clone().to_der().unwrap();
+    let from_der = IdCrl::from_der(&der, &home_server_cert, 200).unwrap();
+    assert_eq!(from_der, crl);
+    let pem = crl.clone().to_pem(LineEnding::LF).unwrap();
+    assert!(pem.starts_with("-----BEGIN X509 CRL-----"));
+    let from_pem = IdCrl::from_pem(&pem, &home_server_cert, 200).unwrap();
+    assert_eq!(from_pem, crl);
+}
+
+#[test]
+fn empty_crl_roundtrip() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let home_server_cert = home_server_cert(&home_server_key, 1, default_validity());
+    let crl = crl(&home_server_key, Vec::new());
+    let der = crl.clone().to_der().unwrap();
+    assert_eq!(IdCrl::from_der(&der, &home_server_cert, 200).unwrap(), crl);
+}
+
+#[test]
+fn verify_fails_with_wrong_key() {
+    init_logger();
+    let home_server_cert = home_server_cert(&gen_priv_key(), 1, default_validity());
+    let other_key = gen_priv_key();
+    let mut crl = crl(&other_key, Vec::new());
+    // The key identifiers of both keys differ
+    assert_eq!(
+        crl.verify(&home_server_cert, 200).err().unwrap(),
+        InvalidCert::InvalidProperties(ConstraintError::Malformed(Some(
+            ERR_MSG_CRL_AUTHORITY_KEY_ID_MISMATCH.to_string()
+        )))
+    );
+    // Without a key identifier, the signature is checked
+    crl.id_crl_tbs_mut().authority_key_identifier = None;
+    crl.signature = other_key.sign(&crl.signature_data().unwrap()).unwrap();
+    assert!(matches!(
+        crl.verify(&home_server_cert, 200).err().unwrap(),
+        InvalidCert::PublicKeyError(_)
+    ));
+}
+
+#[test]
+fn verify_fails_outside_of_update_period() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let home_server_cert = home_server_cert(&home_server_key, 1, default_validity());
+    let crl = crl(&home_server_key, Vec::new());
+    assert_eq!(
+        crl.verify(&home_server_cert, 50).err().unwrap(),
+        InvalidCert::InvalidValidity
+    );
+    assert_eq!(
+        crl.verify(&home_server_cert, 600).err().unwrap(),
+        InvalidCert::InvalidValidity
+    );
+}
+
+#[test]
+fn verify_fails_without_crl_sign() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let mut home_server_cert = home_server_cert(&home_server_key, 1, default_validity());
+    home_server_cert
+        .id_cert_tbs_mut()
+        .capabilities
+        .key_usage
+        .key_usages
+        .retain(|usage| usage != &KeyUsage::CrlSign);
+    let crl = crl(&home_server_key, Vec::new());
+    assert!(matches!(
+        crl.verify(&home_server_cert, 200).err().unwrap(),
+        InvalidCert::InvalidProperties(_)
+    ));
+}
+
+#[test]
+fn verify_fails_with_mismatched_authority_key_identifier() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let home_server_cert = home_server_cert(&home_server_key, 1, default_validity());
+    let mut crl = crl(&home_server_key, Vec::new());
+    crl.id_crl_tbs_mut().authority_key_identifier = Some(vec![0; 20]);
+    // Re-sign, so that only the key identifier is wrong
+    crl.signature = home_server_key
+        .sign(&crl.signature_data().unwrap())
+        .unwrap();
+    assert_eq!(
+        crl.verify(&home_server_cert, 200).err().unwrap(),
+        InvalidCert::InvalidProperties(ConstraintError::Malformed(Some(
+            ERR_MSG_CRL_AUTHORITY_KEY_ID_MISMATCH.to_string()
+        )))
+    );
+}
+
+#[test]
+fn new_rejects_non_conforming_crl_number() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    for crl_number in [Uint::new(&[0]).unwrap(), Uint::new(&[0x7f; 21]).unwrap()] {
+        assert!(IdCrl::<Ed25519Signature, Ed25519PublicKey>::new(
+            home_server_subject(),
+            crl_number,
+            Vec::new(),
+            time(100),
+            Some(time(500)),
+            &home_server_key,
+        )
+        .is_err());
+    }
+}
+
+#[test]
+fn full_verify_actor_with_crl() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let home_server_cert = home_server_cert(&home_server_key, 1, default_validity());
+    let actor_cert: IdCert<Ed25519Signature, Ed25519PublicKey> = IdCert::from_actor_csr(
+        actor_csr("flori", &gen_priv_key()),
+        &home_server_key,
+        Uint::new(&[8]).unwrap(),
+        home_server_subject(),
+        default_validity(),
+    )
+    .unwrap();
+
+    let not_revoked = crl(
+        &home_server_key,
+        vec![RevokedIdCert::new(Uint::new(&[9]).unwrap(), time(50), None)],
+    );
+    actor_cert
+        .full_verify_actor_with_crl(200, &home_server_cert, &not_revoked)
+        .unwrap();
+
+    let revoked = crl(
+        &home_server_key,
+        vec![RevokedIdCert::new(
+            Uint::new(&[8]).unwrap(),
+            time(150),
+            Some(CrlReason::Superseded),
+        )],
+    );
+    assert!(!revoked.is_revoked(&Uint::new(&[8]).unwrap(), 120));
+    assert_eq!(
+        actor_cert
+            .full_verify_actor_with_crl(200, &home_server_cert, &revoked)
+            .err()
+            .unwrap(),
+        InvalidCert::Revoked
+    );
+}
+
+#[test]
+fn full_verify_actor_with_crl_checks_issuer() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let home_server_cert = home_server_cert(&home_server_key, 1, default_validity());
+    let mut actor_cert: IdCert<Ed25519Signature, Ed25519PublicKey> = IdCert::from_actor_csr(
+        actor_csr("flori", &gen_priv_key()),
+        &home_server_key,
+        Uint::new(&[8]).unwrap(),
+        home_server_subject(),
+        default_validity(),
+    )
+    .unwrap();
+    actor_cert.id_cert_tbs_mut().issuer = Name::from_str("DC=polyphony,DC=example").unwrap();
+    assert_eq!(
+        actor_cert
+            .full_verify_actor_with_crl(200, &home_server_cert, &crl(&home_server_key, Vec::new()))
+            .err()
+            .unwrap(),
+        InvalidCert::InvalidProperties(ConstraintError::Malformed(Some(
+            ERR_MSG_CERT_ISSUER_MISMATCH.to_string()
+        )))
+    );
+}
+
+#[test]
+fn new_emits_crl_number_and_authority_key_identifier() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let crl = crl(&home_server_key, Vec::new());
+    let certificate_list = CertificateList::from_der(&crl.to_der().unwrap()).unwrap();
+    let tbs_cert_list = certificate_list.tbs_cert_list;
+    assert_eq!(tbs_cert_list.version, Version::V2);
+    let extensions = tbs_cert_list.crl_extensions.unwrap();
+
+    let crl_number = extensions
+        .iter()
+        .find(|extension| extension.extn_id == CrlNumber::OID)
+        .unwrap();
+    assert!(!crl_number.critical);
+    assert_eq!(
+        CrlNumber::from_der(crl_number.extn_value.as_bytes())
+            .unwrap()
+            .0,
+        Uint::new(&[1]).unwrap()
+    );
+    let aki = extensions
+        .iter()
+        .find(|extension| extension.extn_id == AuthorityKeyIdentifier::OID)
+        .unwrap();
+    assert!(!aki.critical);
+    assert_eq!(
+        AuthorityKeyIdentifier::from_der(aki.extn_value.as_bytes())
+            .unwrap()
+            .key_identifier
+            .unwrap()
+            .into_bytes(),
+        home_server_key.pubkey().key_identifier().unwrap()
+    );
+}
+
+fn extension(oid: &str, critical: bool) -> Extension {
+    Extension {
+        extn_id: ObjectIdentifier::new_unwrap(oid),
+        critical,
+        extn_value: OctetString::new(().to_der().unwrap()).unwrap(),
+    }
+}
+
+/// A CRL as issued by a third party, carrying extensions unknown to this crate
+fn third_party_crl(
+    home_server_key: &Ed25519PrivateKey,
+    entry_extension: Extension,
+    crl_extension: Extension,
+) -> Vec<u8> {
+    let key_identifier = home_server_key.pubkey().key_identifier().unwrap();
+    let tbs_cert_list = TbsCertList {
+        version: Version::V2,
+        signature: Ed25519Signature::algorithm_identifier().unwrap(),
+        issuer: home_server_subject(),
+        this_update: time(100),
+        next_update: Some(time(500)),
+        revoked_certificates: Some(vec![RevokedCert {
+            serial_number: SerialNumber::new(&[8]).unwrap(),
+            revocation_date: time(50),
+            crl_entry_extensions: Some(vec![entry_extension]),
+        }]),
+        crl_extensions: Some(vec![
+            Extension {
+                extn_id: CrlNumber::OID,
+                critical: false,
+                extn_value: OctetString::new(CrlNumber(Uint::new(&[7]).unwrap()).to_der().unwrap())
+                    .unwrap(),
+            },
+            Extension {
+                extn_id: AuthorityKeyIdentifier::OID,
+                critical: false,
+                extn_value: OctetString::new(
+                    AuthorityKeyIdentifier {
+                        key_identifier: Some(OctetString::new(key_identifier).unwrap()),
+                        authority_cert_issuer: None,
+                        authority_cert_serial_number: None,
+                    }
+                    .to_der()
+                    .unwrap(),
+                )
+                .unwrap(),
+            },
+            crl_extension,
+        ]),
+    };
+    let signature = home_server_key
+        .sign(&tbs_cert_list.to_der().unwrap())
+        .unwrap();
+    CertificateList {
+        tbs_cert_list,
+        signature_algorithm: Ed25519Signature::algorithm_identifier().unwrap(),
+        signature: signature.to_bitstring().unwrap(),
+    }
+    .to_der()
+    .unwrap()
+}
+
+#[test]
+fn third_party_crl_extensions_are_retained() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let home_server_cert = home_server_cert(&home_server_key, 1, default_validity());
+    let der = third_party_crl(
+        &home_server_key,
+        extension("1.3.6.1.4.1.99999.1", false),
+        extension("1.3.6.1.4.1.99999.2", false),
+    );
+
+    let crl = IdCrl::from_der(&der, &home_server_cert, 200).unwrap();
+    assert_eq!(crl.clone().to_der().unwrap(), der);
+    let tbs = &crl.id_crl_tbs();
+    assert_eq!(tbs.crl_number, Some(Uint::new(&[7]).unwrap()));
+    assert_eq!(
+        tbs.authority_key_identifier,
+        home_server_key.pubkey().key_identifier()
+    );
+    assert_eq!(
+        tbs.extensions,
+        vec![extension("1.3.6.1.4.1.99999.2", false)]
+    );
+    assert_eq!(
+        tbs.revoked_certificates[0].extensions,
+        vec![extension("1.3.6.1.4.1.99999.1", false)]
+    );
+    assert!(crl.is_revoked(&Uint::new(&[8]).unwrap(), 200));
+
+    // Re-encoding the contents keeps all extensions
+    let mut modified = crl;
+    modified.id_crl_tbs_mut().next_update = Some(time(600));
+    let tbs_cert_list = TbsCertList::try_from(modified.id_crl_tbs().clone()).unwrap();
+    assert_eq!(tbs_cert_list.crl_extensions.unwrap().len(), 3);
+    assert_eq!(
+        tbs_cert_list.revoked_certificates.unwrap()[0]
+            .crl_entry_extensions
+            .as_ref()
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn unknown_critical_crl_extensions_are_rejected() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let home_server_cert = home_server_cert(&home_server_key, 1, default_validity());
+    for der in [
+        third_party_crl(
+            &home_server_key,
+            extension("1.3.6.1.4.1.99999.1", true),
+            extension("1.3.6.1.4.1.99999.2", false),
+        ),
+        third_party_crl(
+            &home_server_key,
+            extension("1.3.6.1.4.1.99999.1", false),
+            extension("1.3.6.1.4.1.99999.2", true),
+        ),
+    ] {
+        assert!(IdCrl::<Ed25519Signature, Ed25519PublicKey>::from_der(
+            &der,
+            &home_server_cert,
+            200
+        )
+        .is_err());
+    }
+}
+
+#[test]
+fn negative_revoked_serial_number_is_rejected() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let home_server_cert = home_server_cert(&home_server_key, 1, default_validity());
+    let der = third_party_crl(
+        &home_server_key,
+        extension("1.3.6.1.4.1.99999.1", false),
+        extension("1.3.6.1.4.1.99999.2", false),
+    );
+    let mut certificate_list = CertificateList::from_der(&der).unwrap();
+    // DER INTEGER -1, whose content octet equals the positive integer 255
+    certificate_list
+        .tbs_cert_list
+        .revoked_certificates
+        .as_mut()
+        .unwrap()[0]
+        .serial_number = SerialNumber::from_der(&[0x02, 0x01, 0xff]).unwrap();
+    certificate_list.signature = home_server_key
+        .sign(&certificate_list.tbs_cert_list.to_der().unwrap())
+        .unwrap()
+        .to_bitstring()
+        .unwrap();
+    assert!(IdCrl::<Ed25519Signature, Ed25519PublicKey>::from_der(
+        &certificate_list.to_der().unwrap(),
+        &home_server_cert,
+        200
+    )
+    .is_err());
+}
@@ -14,17 +14,22 @@ use std::time::Duration;
 
 use crate::common::*;
 use der::asn1::{BitString, Ia5String, Uint, UtcTime};
+use der::Encode;
 use ed25519_dalek::{Signature as Ed25519DalekSignature, Signer, SigningKey, VerifyingKey};
 use polyproto::certs::capabilities::{self, Capabilities};
 use polyproto::certs::idcert::IdCert;
 use polyproto::certs::idcsr::IdCsr;
 use polyproto::certs::{PublicKeyInfo, Target};
+use polyproto::errors::{
+    ConversionError, InvalidCert, InvalidInput, PublicKeyError, ViolationCode,
+};
 use polyproto::key::{PrivateKey, PublicKey};
 use polyproto::signature::Signature;
+use polyproto::Constrained;
 use spki::{AlgorithmIdentifierOwned, ObjectIdentifier, SignatureBitStringEncoding};
 use thiserror::Error;
 use x509_cert::attr::Attributes;
-use x509_cert::name::RdnSequence;
+use x509_cert::name::{Name, RdnSequence};
 use x509_cert::request::CertReq;
 use x509_cert::time::{Time, Validity};
 use x509_cert::Certificate;
@@ -123,3 +128,266 @@ fn csr_from_der() {
     let csr_from_der = IdCsr::from_der(&data, Some(polyproto::certs::Target::HomeServer)).unwrap();
     assert_eq!(csr_from_der, csr);
 }
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn identity_accessors() {
+    init_logger();
+    let csr = actor_csr("flori", &gen_priv_key());
+    assert_eq!(
+        csr.federation_id().unwrap().to_string(),
+        "flori@polyphony.chat"
+    );
+    assert_eq!(csr.session_id().unwrap().to_string(), "client1");
+    assert_eq!(csr.home_server_domain().unwrap(), "polyphony.chat");
+    assert_eq!(csr.common_name().unwrap(), "flori");
+}
+
+/// Returns an actor CSR whose subject public key has been swapped for a key which did not sign it.
+fn csr_without_proof_of_possession() -> IdCsr<Ed25519Signature, Ed25519PublicKey> {
+    let mut csr = actor_csr("flori", &gen_priv_key());
+    csr.inner_csr_mut().subject_public_key = gen_priv_key().pubkey().clone();
+    csr
+}
+
+#[test]
+fn verify_signature() {
+    init_logger();
+    actor_csr("flori", &gen_priv_key())
+        .verify_signature()
+        .unwrap();
+    assert_eq!(
+        csr_without_proof_of_possession()
+            .verify_signature()
+            .err()
+            .unwrap(),
+        InvalidCert::PublicKeyError(PublicKeyError::BadSignature)
+    );
+}
+
+#[test]
+fn verified_constructors() {
+    init_logger();
+    let csr = actor_csr("flori", &gen_priv_key());
+    let der = csr.clone().to_der().unwrap();
+    let pem = csr.clone().to_pem(der::pem::LineEnding::LF).unwrap();
+    assert_eq!(
+        IdCsr::from_der_verified(&der, Some(Target::Actor)).unwrap(),
+        csr
+    );
+    assert_eq!(
+        IdCsr::from_pem_verified(&pem, Some(Target::Actor)).unwrap(),
+        csr
+    );
+
+    let forged = csr_without_proof_of_possession();
+    let expected =
+        ConversionError::InvalidCert(InvalidCert::PublicKeyError(PublicKeyError::BadSignature));
+    assert_eq!(
+        IdCsr::<Ed25519Signature, Ed25519PublicKey>::from_der_verified(
+            &forged.clone().to_der().unwrap(),
+            Some(Target::Actor)
+        )
+        .err()
+        .unwrap(),
+        expected
+    );
+    assert_eq!(
+        IdCsr::<Ed25519Signature, Ed25519PublicKey>::from_pem_verified(
+            &forged.to_pem(der::pem::LineEnding::LF).unwrap(),
+            Some(Target::Actor)
+        )
+        .err()
+        .unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn issuance_requires_proof_of_possession() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let result = IdCert::from_actor_csr(
+        csr_without_proof_of_possession(),
+        &home_server_key,
+        Uint::new(&[8]).unwrap(),
+        home_server_subject(),
+        default_validity(),
+    );
+    assert_eq!(
+        result.err().unwrap(),
+        ConversionError::InvalidCert(InvalidCert::PublicKeyError(PublicKeyError::BadSignature))
+    );
+
+    let mut csr = home_server_csr(&gen_priv_key());
+    csr.inner_csr_mut().subject_public_key = home_server_key.pubkey().clone();
+    assert!(IdCert::from_ca_csr(
+        csr,
+        &home_server_key,
+        Uint::new(&[9]).unwrap(),
+        home_server_subject(),
+        default_validity(),
+    )
+    .is_err());
+}
+
+#[test]
+fn verify_against_original_encoding() {
+    init_logger();
+    let priv_key = gen_priv_key();
+    let mut cert_req = CertReq::try_from(actor_csr("flori", &priv_key)).unwrap();
+    // Attributes unknown to this crate are not retained when decoding, and are therefore missing
+    // from the re-encoded CertificationRequestInfo
+    let mut values = der::asn1::SetOfVec::new();
+    values
+        .insert(der::Any::encode_from(&Ia5String::new("polyproto").unwrap()).unwrap())
+        .unwrap();
+    cert_req
+        .info
+        .attributes
+        .insert(x509_cert::attr::Attribute {
+            oid: ObjectIdentifier::from_str("1.3.6.1.4.1.99999.1").unwrap(),
+            values,
+        })
+        .unwrap();
+    let info_der = cert_req.info.to_der().unwrap();
+    cert_req.signature = priv_key.sign(&info_der).unwrap().to_bitstring().unwrap();
+    let der = cert_req.to_der().unwrap();
+
+    let decoded =
+        IdCsr::<Ed25519Signature, Ed25519PublicKey>::from_der_verified(&der, Some(Target::Actor))
+            .unwrap();
+    assert_eq!(decoded.signature_data().unwrap(), info_der);
+    assert_eq!(decoded.clone().to_der().unwrap(), der);
+
+    let mut modified = decoded;
+    modified.inner_csr_mut().subject = actor_subject("alice");
+    assert!(modified.verify_signature().is_err());
+}
+
+#[test]
+fn validate_all_reports_every_violation() {
+    init_logger();
+    let priv_key = gen_priv_key();
+    let mut csr = actor_csr("flori", &priv_key);
+    assert!(csr.validate_all(Some(Target::Actor)).is_empty());
+
+    csr.inner_csr_mut().subject =
+        Name::from_str("CN=alice,DC=polyphony,DC=chat,UID=flori@polyphony.chat").unwrap();
+    csr.inner_csr_mut()
+        .capabilities
+        .key_usage
+        .key_usages
+        .clear();
+    let violations = csr.validate_all(Some(Target::Actor));
+    let found = violations
+        .iter()
+        .map(|violation| (violation.code, violation.component.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        vec![
+            (
+                ViolationCode::MissingSigningKeyUsage,
+                "capabilities.key_usage"
+            ),
+            (ViolationCode::UidCommonNameMismatch, "subject.cn"),
+            (
+                ViolationCode::MissingUniqueIdentifier,
+                "subject.uniqueIdentifier"
+            ),
+            (ViolationCode::SignatureMismatch, "signature"),
+        ]
+    );
+    assert_eq!(violations[0].code.as_str(), "missing_signing_key_usage");
+    assert_eq!(
+        csr.validate(Some(Target::Actor)).err().unwrap(),
+        violations[0].error
+    );
+}
+
+#[test]
+fn signature_algorithm_must_match_signature_type() {
+    init_logger();
+    let priv_key = gen_priv_key();
+    let mut cert_req = CertReq::try_from(actor_csr("flori", &priv_key)).unwrap();
+    cert_req.algorithm = AlgorithmIdentifierOwned {
+        // Ed448
+        oid: ObjectIdentifier::from_str("1.3.101.113").unwrap(),
+        parameters: None,
+    };
+    let error = IdCsr::<Ed25519Signature, Ed25519PublicKey>::from_der_unchecked(
+        &cert_req.to_der().unwrap(),
+    )
+    .unwrap_err();
+    assert!(matches!(
+        error,
+        ConversionError::InvalidCert(InvalidCert::AlgorithmMismatch { .. })
+    ));
+
+    let mut csr = actor_csr("flori", &priv_key);
+    csr.signature_algorithm = cert_req.algorithm;
+    assert!(matches!(
+        csr.verify_signature().unwrap_err(),
+        InvalidCert::AlgorithmMismatch { .. }
+    ));
+}
+
+#[test]
+fn malformed_signature_is_rejected() {
+    init_logger();
+    let mut cert_req = CertReq::try_from(actor_csr("flori", &gen_priv_key())).unwrap();
+    let mut signature_bytes = cert_req.signature.raw_bytes().to_vec();
+    signature_bytes.push(0);
+    cert_req.signature = BitString::from_bytes(&signature_bytes).unwrap();
+    assert_eq!(
+        IdCsr::<Ed25519Signature, Ed25519PublicKey>::from_der_unchecked(
+            &cert_req.to_der().unwrap()
+        )
+        .unwrap_err(),
+        ConversionError::InvalidInput(InvalidInput::Length {
+            min_length: 64,
+            max_length: 64,
+            actual_length: "65".to_string(),
+        })
+    );
+}
+
+#[test]
+fn verify_non_canonical_encoding() {
+    init_logger();
+    let priv_key = gen_priv_key();
+    let csr = actor_csr("flori", &priv_key);
+    let attributes = CertReq::try_from(csr.clone()).unwrap().info.attributes;
+    // The attributes are a SET OF, whose elements must be sorted in DER
+    let info_der = swap_encodings(
+        &csr.signature_data().unwrap(),
+        &attributes.get(0).unwrap().to_der().unwrap(),
+        &attributes.get(1).unwrap().to_der().unwrap(),
+    );
+    let der = encode_sequence(&[
+        &info_der,
+        &csr.signature_algorithm.to_der().unwrap(),
+        &priv_key
+            .sign(&info_der)
+            .unwrap()
+            .to_bitstring()
+            .unwrap()
+            .to_der()
+            .unwrap(),
+    ]);
+
+    let decoded =
+        IdCsr::<Ed25519Signature, Ed25519PublicKey>::from_der_verified(&der, Some(Target::Actor))
+            .unwrap();
+    assert_eq!(decoded.signature_data().unwrap(), info_der);
+    assert_eq!(decoded.clone().to_der().unwrap(), der);
+    IdCert::from_actor_csr(
+        decoded,
+        &gen_priv_key(),
+        Uint::new(&[8]).unwrap(),
+        home_server_subject(),
+        default_validity(),
+    )
+    .unwrap();
+}
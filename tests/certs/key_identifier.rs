@@ -0,0 +1,222 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::time::Duration;
+
+use der::asn1::Uint;
+use p256::ecdsa::{DerSignature, SigningKey, VerifyingKey};
+use polyproto::certs::builder::IdCertBuilder;
+use polyproto::certs::capabilities::Capabilities;
+use polyproto::certs::idcert::IdCert;
+use polyproto::certs::idcerttbs::IdCertTbs;
+use polyproto::certs::idcsr::IdCsr;
+use polyproto::certs::Target;
+use polyproto::digest::KeyIdentifierDigest;
+use polyproto::key::{PrivateKey, PublicKey};
+use polyproto::rustcrypto::RustCryptoPrivateKey;
+use polyproto::signature::Signature;
+use rand::rngs::OsRng;
+
+use crate::common::*;
+
+#[test]
+fn key_identifiers_are_emitted() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let root = home_server_cert(&home_server_key, 1, default_validity());
+    let actor = actor_cert(
+        &home_server_key,
+        &actor_subject("flori"),
+        home_server_subject(),
+        2,
+        default_validity(),
+    );
+    let home_server_key_id = home_server_key.pubkey().key_identifier();
+    assert!(home_server_key_id.is_some());
+    assert_eq!(
+        root.id_cert_tbs().subject_key_identifier,
+        home_server_key_id
+    );
+    assert_eq!(
+        root.id_cert_tbs().authority_key_identifier,
+        home_server_key_id
+    );
+    assert_eq!(
+        actor.id_cert_tbs().authority_key_identifier,
+        home_server_key_id
+    );
+    assert_eq!(
+        actor.id_cert_tbs().subject_key_identifier,
+        actor.id_cert_tbs().subject_public_key.key_identifier()
+    );
+    assert_ne!(
+        actor.id_cert_tbs().subject_key_identifier,
+        actor.id_cert_tbs().authority_key_identifier
+    );
+}
+
+#[test]
+fn key_identifiers_round_trip() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let actor = actor_cert(
+        &home_server_key,
+        &actor_subject("flori"),
+        home_server_subject(),
+        2,
+        default_validity(),
+    );
+    let der = actor.clone().to_der().unwrap();
+    let decoded = IdCert::<Ed25519Signature, Ed25519PublicKey>::from_der_unchecked(&der).unwrap();
+    assert_eq!(decoded, actor);
+    assert!(decoded
+        .id_cert_tbs()
+        .capabilities
+        .unknown_extensions
+        .is_empty());
+    decoded
+        .full_verify_actor(100, home_server_key.pubkey())
+        .unwrap();
+}
+
+#[test]
+fn find_issuer_after_key_rotation() {
+    init_logger();
+    let old_key = gen_priv_key();
+    let new_key = gen_priv_key();
+    let old_root = home_server_cert(&old_key, 1, default_validity());
+    let new_root = home_server_cert(&new_key, 1, default_validity());
+    let candidates = [old_root.clone(), new_root.clone()];
+
+    let old_actor = actor_cert(
+        &old_key,
+        &actor_subject("flori"),
+        home_server_subject(),
+        2,
+        default_validity(),
+    );
+    let new_actor = actor_cert(
+        &new_key,
+        &actor_subject("flori"),
+        home_server_subject(),
+        2,
+        default_validity(),
+    );
+    assert_eq!(old_actor.find_issuer(&candidates), Some(&old_root));
+    assert_eq!(new_actor.find_issuer(&candidates), Some(&new_root));
+    assert!(new_actor.is_issued_by(&new_root));
+    assert!(!new_actor.is_issued_by(&old_root));
+    assert_eq!(new_actor.find_issuer(&[old_root]), None);
+}
+
+#[test]
+fn find_issuer_without_authority_key_identifier() {
+    init_logger();
+    let old_key = gen_priv_key();
+    let new_key = gen_priv_key();
+    let candidates = [
+        home_server_cert(&old_key, 1, default_validity()),
+        home_server_cert(&new_key, 1, default_validity()),
+    ];
+
+    let mut actor = actor_cert(
+        &new_key,
+        &actor_subject("flori"),
+        home_server_subject(),
+        2,
+        default_validity(),
+    );
+    actor.id_cert_tbs_mut().authority_key_identifier = None;
+    actor.signature = new_key.sign(&actor.signature_data().unwrap()).unwrap();
+    assert!(actor.is_issued_by(&candidates[0]));
+    assert_eq!(actor.find_issuer(&candidates), Some(&candidates[1]));
+}
+
+#[test]
+fn key_identifiers_default_to_the_key_identifier_digest() {
+    init_logger();
+    // RustCrypto public keys do not override PublicKey::key_identifier()
+    let gen_key = || {
+        RustCryptoPrivateKey::<_, VerifyingKey, DerSignature>::from_keypair(SigningKey::random(
+            &mut OsRng,
+        ))
+        .unwrap()
+    };
+    let home_server_key = gen_key();
+    let actor_csr = IdCsr::new(
+        &actor_subject("flori"),
+        &gen_key(),
+        &Capabilities::default_actor(),
+        Some(Target::Actor),
+    )
+    .unwrap();
+    let actor_cert = IdCert::from_actor_csr(
+        actor_csr,
+        &home_server_key,
+        Uint::new(&[2]).unwrap(),
+        home_server_subject(),
+        default_validity(),
+    )
+    .unwrap();
+    assert_eq!(
+        actor_cert.id_cert_tbs().subject_key_identifier,
+        Some(
+            actor_cert
+                .id_cert_tbs()
+                .subject_public_key
+                .public_key_info()
+                .key_identifier::<KeyIdentifierDigest>()
+        )
+    );
+    assert_eq!(
+        actor_cert.id_cert_tbs().authority_key_identifier,
+        Some(
+            home_server_key
+                .pubkey()
+                .public_key_info()
+                .key_identifier::<KeyIdentifierDigest>()
+        )
+    );
+    let der = actor_cert.clone().to_der().unwrap();
+    assert_eq!(IdCert::from_der_unchecked(&der).unwrap(), actor_cert);
+}
+
+#[test]
+fn id_cert_tbs_carries_authority_key_identifier() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let id_cert_tbs = IdCertTbs::from_actor_csr(
+        actor_csr("flori", &gen_priv_key()),
+        home_server_key.pubkey(),
+        Uint::new(&[2]).unwrap(),
+        Ed25519Signature::algorithm_identifier().unwrap(),
+        home_server_subject(),
+        default_validity(),
+    )
+    .unwrap();
+    assert_eq!(
+        id_cert_tbs.authority_key_identifier,
+        home_server_key.pubkey().key_identifier()
+    );
+    assert!(id_cert_tbs.subject_key_identifier.is_some());
+
+    let builder_tbs = IdCertBuilder::new(home_server_csr(&home_server_key))
+        .serial_number(Uint::new(&[1]).unwrap())
+        .home_server_domain("polyphony.chat")
+        .not_before(10)
+        .valid_for(Duration::from_secs(990))
+        .build_home_server_tbs(
+            home_server_key.pubkey(),
+            Ed25519Signature::algorithm_identifier().unwrap(),
+        )
+        .unwrap();
+    assert_eq!(
+        builder_tbs.authority_key_identifier,
+        home_server_key.pubkey().key_identifier()
+    );
+    assert_eq!(
+        builder_tbs.subject_key_identifier,
+        builder_tbs.authority_key_identifier
+    );
+}
@@ -2,6 +2,16 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+mod async_signer;
+mod builder;
+mod bundle;
 mod capabilities;
+mod chain;
+mod fingerprint;
 mod idcert;
+mod idcrl;
 mod idcsr;
+mod key_identifier;
+mod name;
+mod policy;
+mod store;
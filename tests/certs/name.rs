@@ -0,0 +1,100 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::str::FromStr;
+
+use polyproto::certs::name::{ActorName, HomeServerName};
+use polyproto::certs::SessionId;
+use polyproto::types::FederationId;
+use polyproto::{Constrained, Name};
+
+use crate::common::*;
+
+#[test]
+fn actor_name_from_components() {
+    init_logger();
+    let federation_id = FederationId::new("flori@polyphony.chat").unwrap();
+    let session_id = SessionId::new_validated("client1").unwrap();
+    let actor_name = ActorName::new(&federation_id, &session_id).unwrap();
+    assert_eq!(actor_name.federation_id(), &federation_id);
+    assert_eq!(actor_name.session_id(), &session_id);
+    assert_eq!(actor_name.home_server_domain(), "polyphony.chat");
+    assert_eq!(actor_name.to_string(), actor_subject("flori").to_string());
+    actor_name
+        .as_name()
+        .validate(Some(polyproto::certs::Target::Actor))
+        .unwrap();
+
+    let invalid_domain = FederationId::new("flori@-polyphony.chat").unwrap();
+    assert!(ActorName::new(&invalid_domain, &session_id).is_err());
+}
+
+#[test]
+fn home_server_name_from_domain() {
+    init_logger();
+    let home_server_name = HomeServerName::new("polyphony.chat").unwrap();
+    assert_eq!(home_server_name.domain(), "polyphony.chat");
+    assert_eq!(home_server_name.to_string(), "DC=polyphony,DC=chat");
+    for domain in ["", "polyphony..chat", "Polyphony.chat", "-polyphony.chat"] {
+        assert!(HomeServerName::new(domain).is_err(), "{domain}");
+    }
+}
+
+#[test]
+fn lossless_name_conversion() {
+    init_logger();
+    let name = Name::from_str(
+        "CN=flori,OU=staff,DC=polyphony,DC=chat,UID=flori@polyphony.chat,uniqueIdentifier=client1",
+    )
+    .unwrap();
+    let actor_name = ActorName::try_from(name.clone()).unwrap();
+    assert_eq!(Name::from(actor_name), name);
+
+    let name = Name::from_str("OU=servers,DC=polyphony,DC=chat").unwrap();
+    let home_server_name = HomeServerName::try_from(name.clone()).unwrap();
+    assert_eq!(Name::from(home_server_name), name);
+}
+
+#[test]
+fn invalid_names_are_rejected() {
+    init_logger();
+    assert!(ActorName::try_from(home_server_subject()).is_err());
+    assert!(HomeServerName::try_from(actor_subject("flori")).is_err());
+    // The common name must match the local name of the federation ID
+    assert!(ActorName::try_from(
+        Name::from_str(
+            "CN=alice,DC=polyphony,DC=chat,UID=flori@polyphony.chat,uniqueIdentifier=client1"
+        )
+        .unwrap()
+    )
+    .is_err());
+    // Actor names require a session ID
+    assert!(ActorName::try_from(
+        Name::from_str("CN=flori,DC=polyphony,DC=chat,UID=flori@polyphony.chat").unwrap()
+    )
+    .is_err());
+}
+
+#[test]
+fn typed_name_accessors() {
+    init_logger();
+    let csr = actor_csr("flori", &gen_priv_key());
+    let actor_name = csr.inner_csr().actor_name().unwrap();
+    assert_eq!(actor_name.as_name(), &actor_subject("flori"));
+    assert!(csr.inner_csr().home_server_name().is_err());
+
+    let cert = actor_id_cert("flori");
+    assert_eq!(cert.id_cert_tbs().actor_name().unwrap(), actor_name);
+    assert_eq!(
+        cert.id_cert_tbs().issuer_name().unwrap().domain(),
+        "polyphony.chat"
+    );
+
+    let home_server_cert = home_server_id_cert();
+    assert_eq!(
+        home_server_cert.id_cert_tbs().home_server_name().unwrap(),
+        home_server_cert.id_cert_tbs().issuer_name().unwrap()
+    );
+    assert!(home_server_cert.id_cert_tbs().actor_name().is_err());
+}
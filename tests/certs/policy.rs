@@ -0,0 +1,214 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::str::FromStr;
+use std::time::Duration;
+
+use der::asn1::Uint;
+use polyproto::certs::builder::{IdCertBuilder, IdCsrBuilder};
+use polyproto::certs::capabilities::{Capabilities, KeyUsage, KeyUsages};
+use polyproto::certs::idcert::IdCert;
+use polyproto::certs::policy::ValidationPolicy;
+use polyproto::certs::{SessionId, Target};
+use polyproto::errors::{BuilderError, ConstraintError, InvalidCert, ViolationCode};
+use polyproto::types::FederationId;
+use polyproto::{Constrained, Name};
+use spki::ObjectIdentifier;
+
+use crate::common::*;
+
+const DAY: u64 = 60 * 60 * 24;
+
+fn content_commitment_actor() -> Capabilities {
+    Capabilities {
+        key_usage: KeyUsages::new(&[KeyUsage::ContentCommitment]),
+        ..Capabilities::default_actor()
+    }
+}
+
+fn home_server_cert(signing_key: &Ed25519PrivateKey) -> IdCert<Ed25519Signature, Ed25519PublicKey> {
+    IdCertBuilder::new(home_server_csr(signing_key))
+        .serial_number(Uint::new(&[1]).unwrap())
+        .home_server_domain("polyphony.chat")
+        .not_before(10)
+        .valid_for(Duration::from_secs(300 * DAY))
+        .build_home_server(signing_key)
+        .unwrap()
+}
+
+fn actor_cert_builder(
+    capabilities: Capabilities,
+    valid_for: u64,
+) -> IdCertBuilder<Ed25519Signature, Ed25519PublicKey> {
+    let csr = IdCsrBuilder::new()
+        .federation_id(FederationId::new("flori@polyphony.chat").unwrap())
+        .session_id(SessionId::new_validated("client1").unwrap())
+        .capabilities(capabilities)
+        .build_actor(&gen_priv_key())
+        .unwrap();
+    IdCertBuilder::new(csr)
+        .serial_number(Uint::new(&[2]).unwrap())
+        .home_server_domain("polyphony.chat")
+        .not_before(10)
+        .valid_for(Duration::from_secs(valid_for))
+}
+
+#[test]
+fn spec_default_adds_no_rules() {
+    init_logger();
+    assert_eq!(
+        ValidationPolicy::default(),
+        ValidationPolicy::spec_default()
+    );
+    let priv_key = gen_priv_key();
+    let csr = actor_csr("flori", &priv_key);
+    assert!(csr
+        .validate_all_with_policy(Some(Target::Actor), &ValidationPolicy::default())
+        .is_empty());
+    let cert = actor_id_cert("flori");
+    cert.validate_with_policy(Some(Target::Actor), &ValidationPolicy::default())
+        .unwrap();
+}
+
+#[test]
+fn strict_requires_content_commitment() {
+    init_logger();
+    let priv_key = gen_priv_key();
+    let violations = actor_csr("flori", &priv_key)
+        .validate_all_with_policy(Some(Target::Actor), &ValidationPolicy::strict());
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].code, ViolationCode::ContentCommitmentRequired);
+    assert_eq!(violations[0].component, "capabilities.key_usage");
+
+    // Home servers are CAs and therefore exempt
+    assert!(home_server_csr(&priv_key)
+        .validate_all_with_policy(Some(Target::HomeServer), &ValidationPolicy::strict())
+        .is_empty());
+}
+
+#[test]
+fn strict_limits_names() {
+    init_logger();
+    let policy = ValidationPolicy::strict();
+    let name = Name::from_str("DC=a,DC=b,DC=c,DC=d,DC=e,DC=f").unwrap();
+    let violations = name.validate_all_with_policy(Some(Target::HomeServer), &policy);
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].code, ViolationCode::TooManyDomainComponents);
+    assert_eq!(violations[0].component, "dc");
+
+    let name = Name::from_str("O=Polyphony,OU=Engineering,DC=polyphony,DC=chat").unwrap();
+    let violations = name.validate_all_with_policy(Some(Target::HomeServer), &policy);
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].code, ViolationCode::UnexpectedAttribute);
+    assert_eq!(violations[0].component, "2.5.4.10");
+    assert!(name
+        .validate_with_policy(Some(Target::HomeServer), &ValidationPolicy::spec_default())
+        .is_ok());
+}
+
+#[test]
+fn builder_enforces_policy() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let policy = ValidationPolicy {
+        max_lifetime: Some(Duration::from_secs(30 * DAY)),
+        ..ValidationPolicy::strict()
+    };
+
+    let result = actor_cert_builder(content_commitment_actor(), 31 * DAY)
+        .policy(policy.clone())
+        .build_actor(&home_server_key);
+    assert!(matches!(
+        result.err().unwrap(),
+        BuilderError::ConstraintError(ConstraintError::OutOfBounds { .. })
+    ));
+    assert!(actor_cert_builder(Capabilities::default_actor(), 30 * DAY)
+        .policy(policy.clone())
+        .build_actor(&home_server_key)
+        .is_err());
+
+    let cert = actor_cert_builder(content_commitment_actor(), 30 * DAY)
+        .policy(policy.clone())
+        .build_actor(&home_server_key)
+        .unwrap();
+    let home_server_cert = home_server_cert(&home_server_key);
+    cert.full_verify_actor_with_policy(
+        100,
+        &home_server_cert.id_cert_tbs().subject_public_key,
+        &policy,
+    )
+    .unwrap();
+    home_server_cert
+        .full_verify_home_server_with_policy(100, &policy)
+        .err()
+        .unwrap();
+    home_server_cert
+        .full_verify_home_server_with_policy(100, &ValidationPolicy::strict())
+        .unwrap();
+}
+
+#[test]
+fn full_verify_reports_policy_violations() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let home_server_cert = home_server_cert(&home_server_key);
+    let cert = actor_cert_builder(Capabilities::default_actor(), 30 * DAY)
+        .build_actor(&home_server_key)
+        .unwrap();
+    let public_key = &home_server_cert.id_cert_tbs().subject_public_key;
+    cert.full_verify_actor_with_policy(100, public_key, &ValidationPolicy::spec_default())
+        .unwrap();
+    assert!(matches!(
+        cert.full_verify_actor_with_policy(100, public_key, &ValidationPolicy::strict())
+            .err()
+            .unwrap(),
+        InvalidCert::InvalidProperties(_)
+    ));
+}
+
+const ED25519: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.101.112");
+const ED448: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.101.113");
+
+#[test]
+fn algorithm_allowlist() {
+    init_logger();
+    let cert = actor_id_cert("flori");
+    let policy = ValidationPolicy {
+        allowed_algorithms: Some(vec![ED25519]),
+        ..ValidationPolicy::spec_default()
+    };
+    assert!(policy.permits_algorithm(&ED25519));
+    assert!(cert
+        .validate_all_with_policy(Some(Target::Actor), &policy)
+        .is_empty());
+
+    let policy = ValidationPolicy {
+        allowed_algorithms: Some(vec![ED448]),
+        ..ValidationPolicy::spec_default()
+    };
+    let found = cert
+        .validate_all_with_policy(Some(Target::Actor), &policy)
+        .into_iter()
+        .map(|violation| (violation.code, violation.component))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        vec![
+            (
+                ViolationCode::AlgorithmNotAllowed,
+                "signature_algorithm".to_string()
+            ),
+            (
+                ViolationCode::AlgorithmNotAllowed,
+                "subject_public_key".to_string()
+            ),
+        ]
+    );
+    let csr = actor_csr("flori", &gen_priv_key());
+    assert_eq!(
+        csr.validate_all_with_policy(Some(Target::Actor), &policy)
+            .len(),
+        2
+    );
+}
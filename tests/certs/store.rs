@@ -0,0 +1,163 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::str::FromStr;
+
+use der::asn1::Uint;
+use polyproto::certs::idcert::IdCertExt;
+use polyproto::certs::store::{IdCertStore, MemoryIdCertStore};
+use polyproto::certs::SessionId;
+use polyproto::types::FederationId;
+use polyproto::Name;
+use x509_cert::time::Validity;
+
+use crate::common::*;
+
+type Store = MemoryIdCertStore<Ed25519Signature, Ed25519PublicKey>;
+
+fn session_cert(
+    home_server_key: &Ed25519PrivateKey,
+    session_id: &str,
+    serial_number: u8,
+    validity: Validity,
+) -> IdCertExt<Ed25519Signature, Ed25519PublicKey> {
+    let subject = Name::from_str(&format!(
+        "CN=flori,DC=polyphony,DC=chat,UID=flori@polyphony.chat,uniqueIdentifier={}",
+        session_id
+    ))
+    .unwrap();
+    IdCertExt {
+        id_cert: actor_cert(
+            home_server_key,
+            &subject,
+            home_server_subject(),
+            serial_number,
+            validity,
+        ),
+        invalidated: false,
+    }
+}
+
+fn flori() -> FederationId {
+    FederationId::new("flori@polyphony.chat").unwrap()
+}
+
+#[test]
+fn lookups() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let first = session_cert(&home_server_key, "client1", 1, validity(10, 100));
+    let second = session_cert(&home_server_key, "client2", 2, validity(10, 100));
+    let home_server = IdCertExt {
+        id_cert: home_server_id_cert(),
+        invalidated: false,
+    };
+    let mut store = Store::new();
+    assert!(store.is_empty());
+    for id_cert in [first.clone(), second.clone(), home_server.clone()] {
+        store.insert(id_cert).unwrap();
+    }
+    assert_eq!(store.len(), 3);
+
+    assert_eq!(
+        store.get_by_federation_id(&flori()).unwrap(),
+        vec![first.clone(), second.clone()]
+    );
+    assert_eq!(
+        store
+            .get_by_session_id(&flori(), &SessionId::new_validated("client2").unwrap())
+            .unwrap(),
+        vec![second.clone()]
+    );
+    assert!(store
+        .get_by_federation_id(&FederationId::new("alice@polyphony.chat").unwrap())
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        store
+            .get_by_serial_number(&home_server_subject(), &Uint::new(&[1]).unwrap())
+            .unwrap(),
+        Some(first.clone())
+    );
+    assert_eq!(
+        store
+            .get_by_serial_number(&home_server_subject(), &Uint::new(&[3]).unwrap())
+            .unwrap(),
+        None
+    );
+    // The self-signed home server certificate is issued by the home server, too
+    assert_eq!(
+        store.get_by_issuer(&home_server_subject()).unwrap(),
+        vec![first, second, home_server]
+    );
+}
+
+#[test]
+fn insert_replaces_same_serial_number() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let mut store = Store::new();
+    store
+        .insert(session_cert(
+            &home_server_key,
+            "client1",
+            1,
+            validity(10, 100),
+        ))
+        .unwrap();
+    let replacement = session_cert(&home_server_key, "client2", 1, validity(10, 100));
+    store.insert(replacement.clone()).unwrap();
+    assert_eq!(store.len(), 1);
+    assert!(store
+        .get_by_session_id(&flori(), &SessionId::new_validated("client1").unwrap())
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        store.get_by_federation_id(&flori()).unwrap(),
+        vec![replacement]
+    );
+}
+
+#[test]
+fn valid_at_time() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let session = SessionId::new_validated("client1").unwrap();
+    let old = session_cert(&home_server_key, "client1", 1, validity(10, 100));
+    let new = session_cert(&home_server_key, "client1", 2, validity(90, 200));
+    let other_session = session_cert(&home_server_key, "client2", 3, validity(10, 200));
+    let mut store = Store::new();
+    for id_cert in [old.clone(), new.clone(), other_session.clone()] {
+        store.insert(id_cert).unwrap();
+    }
+
+    assert_eq!(
+        store.valid_at(&flori(), Some(&session), 50).unwrap(),
+        vec![old.clone()]
+    );
+    // Overlapping validity periods: the latest certificate comes first
+    assert_eq!(
+        store.valid_at(&flori(), Some(&session), 95).unwrap(),
+        vec![new.clone(), old.clone()]
+    );
+    assert_eq!(
+        store.valid_at(&flori(), None, 150).unwrap(),
+        vec![new.clone(), other_session]
+    );
+    assert!(store
+        .valid_at(&flori(), Some(&session), 300)
+        .unwrap()
+        .is_empty());
+
+    assert!(store
+        .invalidate(&home_server_subject(), &Uint::new(&[2]).unwrap())
+        .unwrap());
+    assert!(!store
+        .invalidate(&home_server_subject(), &Uint::new(&[4]).unwrap())
+        .unwrap());
+    let valid = store.valid_at(&flori(), Some(&session), 150).unwrap();
+    assert_eq!(valid.len(), 1);
+    assert_eq!(valid[0].id_cert, new.id_cert);
+    assert!(valid[0].invalidated);
+}
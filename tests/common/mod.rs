@@ -6,17 +6,21 @@ use std::str::FromStr;
 use std::time::Duration;
 
 use der::asn1::{BitString, Uint, UtcTime};
+use der::Encode;
 use ed25519_dalek::ed25519::signature::Signer;
 use ed25519_dalek::{Signature as Ed25519DalekSignature, SigningKey, VerifyingKey};
 use polyproto::certs::capabilities::Capabilities;
 use polyproto::certs::idcert::IdCert;
 use polyproto::certs::idcsr::IdCsr;
 use polyproto::certs::PublicKeyInfo;
-use polyproto::errors::composite::ConversionError;
+use polyproto::digest::Digest;
+use polyproto::errors::base::InvalidInput;
+use polyproto::errors::composite::{ConversionError, SigningError};
 use polyproto::key::{PrivateKey, PublicKey};
 use polyproto::signature::Signature;
 use polyproto::Name;
 use rand::rngs::OsRng;
+use sha2::Digest as _;
 use spki::{AlgorithmIdentifierOwned, ObjectIdentifier, SignatureBitStringEncoding};
 use x509_cert::time::{Time, Validity};
 
@@ -35,13 +39,21 @@ pub fn actor_subject(cn: &str) -> Name {
     .unwrap()
 }
 
-pub fn default_validity() -> Validity {
+pub fn time(secs: u64) -> Time {
+    Time::UtcTime(UtcTime::from_unix_duration(Duration::from_secs(secs)).unwrap())
+}
+
+pub fn validity(not_before: u64, not_after: u64) -> Validity {
     Validity {
-        not_before: Time::UtcTime(UtcTime::from_unix_duration(Duration::from_secs(10)).unwrap()),
-        not_after: Time::UtcTime(UtcTime::from_unix_duration(Duration::from_secs(1000)).unwrap()),
+        not_before: time(not_before),
+        not_after: time(not_after),
     }
 }
 
+pub fn default_validity() -> Validity {
+    validity(10, 1000)
+}
+
 pub fn home_server_subject() -> Name {
     Name::from_str("DC=polyphony,DC=chat").unwrap()
 }
@@ -51,20 +63,37 @@ pub fn gen_priv_key() -> Ed25519PrivateKey {
 }
 
 pub fn actor_id_cert(cn: &str) -> IdCert<Ed25519Signature, Ed25519PublicKey> {
-    let priv_key = gen_priv_key();
-    IdCert::from_actor_csr(
-        actor_csr(cn, &priv_key),
-        &priv_key,
-        Uint::new(&[8]).unwrap(),
+    actor_cert(
+        &gen_priv_key(),
+        &actor_subject(cn),
         home_server_subject(),
-        Validity {
-            not_before: Time::UtcTime(
-                UtcTime::from_unix_duration(Duration::from_secs(10)).unwrap(),
-            ),
-            not_after: Time::UtcTime(
-                UtcTime::from_unix_duration(Duration::from_secs(1000)).unwrap(),
-            ),
-        },
+        8,
+        default_validity(),
+    )
+}
+
+/// Issues an actor certificate for a freshly generated key and the given `subject`, signed by
+/// `home_server_key`.
+pub fn actor_cert(
+    home_server_key: &Ed25519PrivateKey,
+    subject: &Name,
+    issuer: Name,
+    serial_number: u8,
+    validity: Validity,
+) -> IdCert<Ed25519Signature, Ed25519PublicKey> {
+    let csr = IdCsr::new(
+        subject,
+        &gen_priv_key(),
+        &Capabilities::default_actor(),
+        Some(polyproto::certs::Target::Actor),
+    )
+    .unwrap();
+    IdCert::from_actor_csr(
+        csr,
+        home_server_key,
+        Uint::new(&[serial_number]).unwrap(),
+        issuer,
+        validity,
     )
     .unwrap()
 }
@@ -83,20 +112,21 @@ pub fn actor_csr(
 }
 
 pub fn home_server_id_cert() -> IdCert<Ed25519Signature, Ed25519PublicKey> {
-    let priv_key = gen_priv_key();
+    home_server_cert(&gen_priv_key(), 8, default_validity())
+}
+
+/// Issues a self-signed home server certificate for `home_server_key`.
+pub fn home_server_cert(
+    home_server_key: &Ed25519PrivateKey,
+    serial_number: u8,
+    validity: Validity,
+) -> IdCert<Ed25519Signature, Ed25519PublicKey> {
     IdCert::from_ca_csr(
-        home_server_csr(&priv_key),
-        &priv_key,
-        Uint::new(&[8]).unwrap(),
+        home_server_csr(home_server_key),
+        home_server_key,
+        Uint::new(&[serial_number]).unwrap(),
         home_server_subject(),
-        Validity {
-            not_before: Time::UtcTime(
-                UtcTime::from_unix_duration(Duration::from_secs(10)).unwrap(),
-            ),
-            not_after: Time::UtcTime(
-                UtcTime::from_unix_duration(Duration::from_secs(1000)).unwrap(),
-            ),
-        },
+        validity,
     )
     .unwrap()
 }
@@ -111,6 +141,31 @@ pub fn home_server_csr(priv_key: &Ed25519PrivateKey) -> IdCsr<Ed25519Signature,
     .unwrap()
 }
 
+/// Swaps the adjacent DER encodings `first` and `second` within `der`, producing an encoding which
+/// is not in canonical DER form, if `first` and `second` are elements of a `SET OF`.
+pub fn swap_encodings(der: &[u8], first: &[u8], second: &[u8]) -> Vec<u8> {
+    let adjacent = [first, second].concat();
+    let position = der
+        .windows(adjacent.len())
+        .position(|window| window == adjacent)
+        .unwrap();
+    let mut swapped = der.to_vec();
+    swapped[position..position + adjacent.len()].copy_from_slice(&[second, first].concat());
+    swapped
+}
+
+/// Encodes a `SEQUENCE` of the already DER encoded `fields`, keeping them byte for byte.
+pub fn encode_sequence(fields: &[&[u8]]) -> Vec<u8> {
+    let content = fields.concat();
+    let length = der::Length::try_from(content.len()).unwrap();
+    let mut der = der::Header::new(der::Tag::Sequence, length)
+        .unwrap()
+        .to_der()
+        .unwrap();
+    der.extend_from_slice(&content);
+    der
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub(crate) struct Ed25519Signature {
     pub(crate) signature: Ed25519DalekSignature,
@@ -135,27 +190,36 @@ impl Signature for Ed25519Signature {
 
     // The algorithm identifier for a given signature implementation is constant. We just need
     // to define it here.
-    fn algorithm_identifier() -> AlgorithmIdentifierOwned {
-        AlgorithmIdentifierOwned {
+    fn algorithm_identifier() -> Option<AlgorithmIdentifierOwned> {
+        Some(AlgorithmIdentifierOwned {
             // This is the OID for Ed25519. It is defined in the IANA registry.
             oid: ObjectIdentifier::from_str("1.3.101.112").unwrap(),
             // For this example, we don't need or want any parameters.
             parameters: None,
-        }
+        })
     }
 
-    fn from_bytes(signature: &[u8]) -> Self {
-        let mut signature_vec = signature.to_vec();
-        signature_vec.resize(64, 0);
-        let signature_array: [u8; 64] = {
-            let mut array = [0; 64];
-            array.copy_from_slice(&signature_vec[..]);
-            array
-        };
-        Self {
+    // The algorithm identifier of a signature value. As our signature type only supports a
+    // single algorithm, this is the same as the algorithm identifier above.
+    fn signature_algorithm(&self) -> AlgorithmIdentifierOwned {
+        self.algorithm.clone()
+    }
+
+    fn try_from_bytes(signature: &[u8]) -> Result<Self, ConversionError> {
+        // Ed25519 signatures are exactly 64 bytes long. Anything else is not a valid signature.
+        let signature_array: [u8; 64] = signature.try_into().map_err(|_| InvalidInput::Length {
+            min_length: 64,
+            max_length: 64,
+            actual_length: signature.len().to_string(),
+        })?;
+        Ok(Self {
             signature: Ed25519DalekSignature::from_bytes(&signature_array),
-            algorithm: Self::algorithm_identifier(),
-        }
+            algorithm: Self::algorithm_identifier().unwrap(),
+        })
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.signature.to_bytes().to_vec()
     }
 }
 
@@ -186,12 +250,15 @@ impl PrivateKey<Ed25519Signature> for Ed25519PrivateKey {
 
     // Signs a message. The beauty of having to wrap the ed25519-dalek crate is that we can
     // harness all of its functionality, such as the `sign` method.
-    fn sign(&self, data: &[u8]) -> Ed25519Signature {
-        let signature = self.key.sign(data);
-        Ed25519Signature {
+    fn sign(&self, data: &[u8]) -> Result<Ed25519Signature, SigningError> {
+        let signature = self
+            .key
+            .try_sign(data)
+            .map_err(|e| SigningError::Failed(e.to_string()))?;
+        Ok(Ed25519Signature {
             signature,
-            algorithm: self.algorithm_identifier(),
-        }
+            algorithm: Ed25519Signature::algorithm_identifier().unwrap(),
+        })
     }
 }
 
@@ -233,21 +300,29 @@ impl PublicKey<Ed25519Signature> for Ed25519PublicKey {
     // or a CSR.
     fn public_key_info(&self) -> PublicKeyInfo {
         PublicKeyInfo {
-            algorithm: Ed25519Signature::algorithm_identifier(),
+            algorithm: Ed25519Signature::algorithm_identifier().unwrap(),
             public_key_bitstring: BitString::from_bytes(&self.key.to_bytes()).unwrap(),
         }
     }
 
     fn try_from_public_key_info(public_key_info: PublicKeyInfo) -> Result<Self, ConversionError> {
-        let mut key_vec = public_key_info.public_key_bitstring.raw_bytes().to_vec();
-        key_vec.resize(32, 0);
-        let signature_array: [u8; 32] = {
-            let mut array = [0; 32];
-            array.copy_from_slice(&key_vec[..]);
-            array
-        };
-        Ok(Self {
-            key: VerifyingKey::from_bytes(&signature_array).unwrap(),
-        })
+        // Ed25519 public keys are exactly 32 bytes long.
+        let key_bytes = public_key_info.public_key_bitstring.raw_bytes();
+        let key_array: [u8; 32] = key_bytes.try_into().map_err(|_| InvalidInput::Length {
+            min_length: 32,
+            max_length: 32,
+            actual_length: key_bytes.len().to_string(),
+        })?;
+        let key = VerifyingKey::from_bytes(&key_array)
+            .map_err(|e| InvalidInput::Malformed(e.to_string()))?;
+        Ok(Self { key })
+    }
+}
+
+pub(crate) struct Sha256;
+
+impl Digest for Sha256 {
+    fn digest(data: &[u8]) -> Vec<u8> {
+        sha2::Sha256::digest(data).to_vec()
     }
 }
@@ -0,0 +1,260 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use der::asn1::Uint;
+use der::pem::LineEnding;
+use polyproto::certs::capabilities::Capabilities;
+use polyproto::certs::idcert::IdCert;
+use polyproto::certs::idcsr::IdCsr;
+use polyproto::certs::{PublicKeyInfo, Target};
+use polyproto::ecdsa::*;
+use polyproto::errors::{ConversionError, InvalidCert, InvalidInput, PublicKeyError};
+use polyproto::key::{PrivateKey, PublicKey};
+use polyproto::signature::Signature;
+use rand::rngs::OsRng;
+use spki::{AlgorithmIdentifierOwned, SignatureBitStringEncoding};
+
+use crate::common::{actor_subject, default_validity, home_server_subject, init_logger};
+
+fn issue_and_verify_certificates<C: EcdsaCurve>() {
+    init_logger();
+    let home_server_key = EcdsaPrivateKey::<C>::generate(&mut OsRng);
+    let home_server_cert = IdCert::from_ca_csr(
+        IdCsr::new(
+            &home_server_subject(),
+            &home_server_key,
+            &Capabilities::default_home_server(),
+            Some(Target::HomeServer),
+        )
+        .unwrap(),
+        &home_server_key,
+        Uint::new(&[1]).unwrap(),
+        home_server_subject(),
+        default_validity(),
+    )
+    .unwrap();
+    home_server_cert.full_verify_home_server(100).unwrap();
+
+    let actor_key = EcdsaPrivateKey::<C>::generate(&mut OsRng);
+    let actor_csr = IdCsr::new(
+        &actor_subject("flori"),
+        &actor_key,
+        &Capabilities::default_actor(),
+        Some(Target::Actor),
+    )
+    .unwrap();
+    let actor_csr = IdCsr::<EcdsaSignature<C>, EcdsaPublicKey<C>>::from_der_verified(
+        &actor_csr.to_der().unwrap(),
+        Some(Target::Actor),
+    )
+    .unwrap();
+    let actor_cert = IdCert::from_actor_csr(
+        actor_csr,
+        &home_server_key,
+        Uint::new(&[2]).unwrap(),
+        home_server_subject(),
+        default_validity(),
+    )
+    .unwrap();
+
+    let actor_der = actor_cert.clone().to_der().unwrap();
+    let decoded =
+        IdCert::from_der(&actor_der, Target::Actor, 100, home_server_key.pubkey()).unwrap();
+    assert_eq!(decoded, actor_cert);
+    assert_eq!(
+        decoded.id_cert_tbs().signature_algorithm,
+        EcdsaSignature::<C>::algorithm_identifier().unwrap()
+    );
+    assert!(decoded.full_verify_actor(100, actor_key.pubkey()).is_err());
+}
+
+#[test]
+fn p256_issues_and_verifies_certificates() {
+    issue_and_verify_certificates::<NistP256>();
+}
+
+#[test]
+fn p384_issues_and_verifies_certificates() {
+    issue_and_verify_certificates::<NistP384>();
+}
+
+#[test]
+fn algorithm_identifiers_follow_rfc_5758_and_5480() {
+    assert_eq!(
+        P256Signature::algorithm_identifier(),
+        Some(AlgorithmIdentifierOwned {
+            oid: OID_ECDSA_WITH_SHA256,
+            parameters: None
+        })
+    );
+    assert_eq!(
+        P384Signature::algorithm_identifier(),
+        Some(AlgorithmIdentifierOwned {
+            oid: OID_ECDSA_WITH_SHA384,
+            parameters: None
+        })
+    );
+
+    let algorithm = P256PrivateKey::generate(&mut OsRng)
+        .pubkey()
+        .public_key_info()
+        .algorithm;
+    assert_eq!(algorithm.oid, OID_EC_PUBLIC_KEY);
+    assert_eq!(
+        algorithm
+            .parameters
+            .unwrap()
+            .decode_as::<spki::ObjectIdentifier>(),
+        Ok(OID_SECP256R1)
+    );
+    let algorithm = P384PrivateKey::generate(&mut OsRng)
+        .pubkey()
+        .public_key_info()
+        .algorithm;
+    assert_eq!(algorithm.oid, OID_EC_PUBLIC_KEY);
+    assert_eq!(
+        algorithm
+            .parameters
+            .unwrap()
+            .decode_as::<spki::ObjectIdentifier>(),
+        Ok(OID_SECP384R1)
+    );
+}
+
+fn signatures_are_der_encoded<C: EcdsaCurve>() {
+    let key = EcdsaPrivateKey::<C>::generate(&mut OsRng);
+    let signature = key.sign(b"polyproto").unwrap();
+    key.pubkey()
+        .verify_signature(&signature, b"polyproto")
+        .unwrap();
+    assert_eq!(
+        key.pubkey().verify_signature(&signature, b"polyphony"),
+        Err(PublicKeyError::BadSignature)
+    );
+
+    let der = signature.to_bytes();
+    // Ecdsa-Sig-Value ::= SEQUENCE { r INTEGER, s INTEGER }
+    assert_eq!(der[0], 0x30);
+    assert_eq!(
+        signature.to_bitstring().unwrap().raw_bytes(),
+        der.as_slice()
+    );
+    assert_eq!(
+        EcdsaSignature::<C>::try_from_bytes(&der).unwrap(),
+        signature
+    );
+
+    // Trailing data and truncated encodings are not valid DER
+    let mut trailing = der.clone();
+    trailing.push(0);
+    assert!(matches!(
+        EcdsaSignature::<C>::try_from_bytes(&trailing),
+        Err(ConversionError::InvalidInput(InvalidInput::Malformed(_)))
+    ));
+    assert!(matches!(
+        EcdsaSignature::<C>::try_from_bytes(&der[..der.len() - 1]),
+        Err(ConversionError::InvalidInput(InvalidInput::Malformed(_)))
+    ));
+}
+
+#[test]
+fn p256_signatures_are_der_encoded() {
+    signatures_are_der_encoded::<NistP256>();
+}
+
+#[test]
+fn p384_signatures_are_der_encoded() {
+    signatures_are_der_encoded::<NistP384>();
+}
+
+fn keys_round_trip<C: EcdsaCurve>() {
+    let key = EcdsaPrivateKey::<C>::generate(&mut OsRng);
+    let document = key.to_pkcs8_der().unwrap();
+    assert_eq!(
+        EcdsaPrivateKey::<C>::from_pkcs8_der(document.as_bytes()).unwrap(),
+        key
+    );
+    let pem = document.to_pem("PRIVATE KEY", LineEnding::LF).unwrap();
+    assert_eq!(EcdsaPrivateKey::<C>::from_pkcs8_pem(&pem).unwrap(), key);
+
+    let public_key = key.pubkey();
+    let der = public_key.to_public_key_der().unwrap();
+    assert_eq!(
+        EcdsaPublicKey::<C>::from_public_key_der(&der).unwrap(),
+        *public_key
+    );
+    let pem = public_key.to_public_key_pem(LineEnding::LF).unwrap();
+    assert_eq!(
+        EcdsaPublicKey::<C>::from_public_key_pem(&pem).unwrap(),
+        *public_key
+    );
+    assert_eq!(public_key.key_identifier().unwrap().len(), 20);
+}
+
+#[test]
+fn p256_keys_round_trip() {
+    keys_round_trip::<NistP256>();
+}
+
+#[test]
+fn p384_keys_round_trip() {
+    keys_round_trip::<NistP384>();
+}
+
+#[test]
+fn keys_of_other_curves_are_rejected() {
+    let p384_key = P384PrivateKey::generate(&mut OsRng);
+    assert!(matches!(
+        P256PublicKey::try_from_public_key_info(p384_key.pubkey().public_key_info()),
+        Err(ConversionError::InvalidCert(
+            InvalidCert::AlgorithmMismatch { .. }
+        ))
+    ));
+    assert!(matches!(
+        P256PrivateKey::from_pkcs8_der(p384_key.to_pkcs8_der().unwrap().as_bytes()),
+        Err(ConversionError::InvalidInput(InvalidInput::Malformed(_)))
+    ));
+
+    // A P-384 point declared to be on P-256
+    let mislabeled = PublicKeyInfo {
+        algorithm: P256PublicKey::public_key_algorithm(),
+        ..p384_key.pubkey().public_key_info()
+    };
+    assert!(matches!(
+        P256PublicKey::try_from_public_key_info(mislabeled),
+        Err(ConversionError::InvalidInput(InvalidInput::Malformed(_)))
+    ));
+}
+
+#[test]
+fn public_key_parameters_are_checked() {
+    let public_key_info = P256PrivateKey::generate(&mut OsRng)
+        .pubkey()
+        .public_key_info();
+
+    let without_parameters = PublicKeyInfo {
+        algorithm: AlgorithmIdentifierOwned {
+            oid: OID_EC_PUBLIC_KEY,
+            parameters: None,
+        },
+        ..public_key_info.clone()
+    };
+    assert!(matches!(
+        P256PublicKey::try_from_public_key_info(without_parameters),
+        Err(ConversionError::InvalidCert(
+            InvalidCert::AlgorithmMismatch { .. }
+        ))
+    ));
+
+    let signature_algorithm = PublicKeyInfo {
+        algorithm: P256Signature::algorithm_identifier().unwrap(),
+        ..public_key_info
+    };
+    assert!(matches!(
+        P256PublicKey::try_from_public_key_info(signature_algorithm),
+        Err(ConversionError::InvalidCert(
+            InvalidCert::AlgorithmMismatch { .. }
+        ))
+    ));
+}
@@ -0,0 +1,236 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::str::FromStr;
+
+use der::asn1::{BitString, Uint};
+use der::pem::LineEnding;
+use der::{Any, Tag};
+use polyproto::certs::capabilities::Capabilities;
+use polyproto::certs::idcert::IdCert;
+use polyproto::certs::idcsr::IdCsr;
+use polyproto::certs::{PublicKeyInfo, Target};
+use polyproto::digest::KeyIdentifierDigest;
+use polyproto::ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature, OID_ED25519};
+use polyproto::errors::{ConversionError, InvalidCert, InvalidInput, PublicKeyError};
+use polyproto::key::{PrivateKey, PublicKey};
+use polyproto::signature::Signature;
+use rand::rngs::OsRng;
+use spki::{AlgorithmIdentifierOwned, ObjectIdentifier};
+
+use crate::common::{actor_subject, default_validity, home_server_subject, init_logger};
+
+#[test]
+fn algorithm_identifier_has_no_parameters() {
+    let algorithm = Ed25519Signature::algorithm_identifier().unwrap();
+    assert_eq!(
+        algorithm.oid,
+        ObjectIdentifier::from_str("1.3.101.112").unwrap()
+    );
+    assert_eq!(algorithm.oid, OID_ED25519);
+    assert!(algorithm.parameters.is_none());
+
+    let public_key_info = Ed25519PrivateKey::generate(&mut OsRng)
+        .pubkey()
+        .public_key_info();
+    assert_eq!(public_key_info.algorithm, algorithm);
+    assert_eq!(public_key_info.public_key_bitstring.raw_bytes().len(), 32);
+}
+
+#[test]
+fn issues_and_verifies_certificates() {
+    init_logger();
+    let home_server_key = Ed25519PrivateKey::generate(&mut OsRng);
+    let home_server_cert = IdCert::from_ca_csr(
+        IdCsr::new(
+            &home_server_subject(),
+            &home_server_key,
+            &Capabilities::default_home_server(),
+            Some(Target::HomeServer),
+        )
+        .unwrap(),
+        &home_server_key,
+        Uint::new(&[1]).unwrap(),
+        home_server_subject(),
+        default_validity(),
+    )
+    .unwrap();
+    home_server_cert.full_verify_home_server(100).unwrap();
+
+    let actor_key = Ed25519PrivateKey::generate(&mut OsRng);
+    let actor_csr = IdCsr::new(
+        &actor_subject("flori"),
+        &actor_key,
+        &Capabilities::default_actor(),
+        Some(Target::Actor),
+    )
+    .unwrap();
+    let actor_csr = IdCsr::<Ed25519Signature, Ed25519PublicKey>::from_der_verified(
+        &actor_csr.to_der().unwrap(),
+        Some(Target::Actor),
+    )
+    .unwrap();
+    let actor_cert = IdCert::from_actor_csr(
+        actor_csr,
+        &home_server_key,
+        Uint::new(&[2]).unwrap(),
+        home_server_subject(),
+        default_validity(),
+    )
+    .unwrap();
+
+    let actor_der = actor_cert.clone().to_der().unwrap();
+    let decoded =
+        IdCert::from_der(&actor_der, Target::Actor, 100, home_server_key.pubkey()).unwrap();
+    assert_eq!(decoded, actor_cert);
+    decoded
+        .full_verify_actor(100, home_server_key.pubkey())
+        .unwrap();
+    assert!(decoded.full_verify_actor(100, actor_key.pubkey()).is_err());
+}
+
+#[test]
+fn signatures_are_verified() {
+    let key = Ed25519PrivateKey::generate(&mut OsRng);
+    let signature = key.sign(b"polyproto").unwrap();
+    key.pubkey()
+        .verify_signature(&signature, b"polyproto")
+        .unwrap();
+    assert_eq!(
+        key.pubkey().verify_signature(&signature, b"polyphony"),
+        Err(PublicKeyError::BadSignature)
+    );
+
+    let decoded = Ed25519Signature::try_from_bytes(&signature.to_bytes()).unwrap();
+    assert_eq!(decoded, signature);
+    assert_eq!(
+        Ed25519Signature::try_from_bytes(&[0; 63]),
+        Err(ConversionError::InvalidInput(InvalidInput::Length {
+            min_length: 64,
+            max_length: 64,
+            actual_length: "63".to_string()
+        }))
+    );
+}
+
+#[test]
+fn private_keys_round_trip_pkcs8() {
+    let key = Ed25519PrivateKey::generate(&mut OsRng);
+    let document = key.to_pkcs8_der().unwrap();
+    let decoded = Ed25519PrivateKey::from_pkcs8_der(document.as_bytes()).unwrap();
+    assert_eq!(decoded, key);
+    assert_eq!(decoded.pubkey(), key.pubkey());
+
+    let pem = document.to_pem("PRIVATE KEY", LineEnding::LF).unwrap();
+    assert_eq!(Ed25519PrivateKey::from_pkcs8_pem(&pem).unwrap(), key);
+
+    assert!(matches!(
+        Ed25519PrivateKey::from_pkcs8_der(&document.as_bytes()[1..]),
+        Err(ConversionError::InvalidInput(InvalidInput::Malformed(_)))
+    ));
+}
+
+#[test]
+fn public_keys_round_trip_spki() {
+    let key = Ed25519PrivateKey::generate(&mut OsRng);
+    let public_key = key.pubkey();
+
+    let der = public_key.to_public_key_der().unwrap();
+    assert_eq!(
+        Ed25519PublicKey::from_public_key_der(&der).unwrap(),
+        *public_key
+    );
+    let pem = public_key.to_public_key_pem(LineEnding::LF).unwrap();
+    assert!(pem.starts_with("-----BEGIN PUBLIC KEY-----"));
+    assert_eq!(
+        Ed25519PublicKey::from_public_key_pem(&pem).unwrap(),
+        *public_key
+    );
+    assert_eq!(
+        Ed25519PublicKey::try_from_public_key_info(public_key.public_key_info()).unwrap(),
+        *public_key
+    );
+}
+
+#[test]
+fn foreign_public_key_infos_are_rejected() {
+    let public_key_info = Ed25519PrivateKey::generate(&mut OsRng)
+        .pubkey()
+        .public_key_info();
+
+    let with_parameters = PublicKeyInfo {
+        algorithm: AlgorithmIdentifierOwned {
+            oid: OID_ED25519,
+            parameters: Some(Any::new(Tag::Null, Vec::new()).unwrap()),
+        },
+        ..public_key_info.clone()
+    };
+    assert!(matches!(
+        Ed25519PublicKey::try_from_public_key_info(with_parameters),
+        Err(ConversionError::InvalidCert(
+            InvalidCert::AlgorithmMismatch { .. }
+        ))
+    ));
+
+    let ed448 = PublicKeyInfo {
+        algorithm: AlgorithmIdentifierOwned {
+            oid: ObjectIdentifier::from_str("1.3.101.113").unwrap(),
+            parameters: None,
+        },
+        ..public_key_info.clone()
+    };
+    assert!(matches!(
+        Ed25519PublicKey::try_from_public_key_info(ed448),
+        Err(ConversionError::InvalidCert(
+            InvalidCert::AlgorithmMismatch { .. }
+        ))
+    ));
+
+    let truncated = PublicKeyInfo {
+        public_key_bitstring: BitString::from_bytes(
+            &public_key_info.public_key_bitstring.raw_bytes()[..31],
+        )
+        .unwrap(),
+        ..public_key_info
+    };
+    assert!(matches!(
+        Ed25519PublicKey::try_from_public_key_info(truncated),
+        Err(ConversionError::InvalidInput(InvalidInput::Length { .. }))
+    ));
+}
+
+#[test]
+fn weak_public_keys_are_rejected() {
+    // The identity point, which is of small order
+    let mut identity = [0u8; 32];
+    identity[0] = 1;
+    let public_key_info = PublicKeyInfo {
+        algorithm: Ed25519Signature::algorithm_identifier().unwrap(),
+        public_key_bitstring: BitString::from_bytes(&identity).unwrap(),
+    };
+    assert!(matches!(
+        Ed25519PublicKey::try_from_public_key_info(public_key_info),
+        Err(ConversionError::InvalidInput(InvalidInput::Malformed(_)))
+    ));
+}
+
+#[test]
+fn key_identifiers_use_the_default_digest() {
+    let key = Ed25519PrivateKey::generate(&mut OsRng);
+    let key_identifier = key.pubkey().key_identifier().unwrap();
+    assert_eq!(
+        key_identifier,
+        key.pubkey()
+            .public_key_info()
+            .key_identifier::<KeyIdentifierDigest>()
+    );
+    assert_eq!(key_identifier.len(), 20);
+    assert_ne!(
+        key_identifier,
+        Ed25519PrivateKey::generate(&mut OsRng)
+            .pubkey()
+            .key_identifier()
+            .unwrap()
+    );
+}
@@ -2,8 +2,12 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+pub(crate) mod any;
 pub(crate) mod api;
 pub(crate) mod certs;
 pub(crate) mod common;
+pub(crate) mod ecdsa;
+pub(crate) mod ed25519;
+pub(crate) mod rustcrypto;
 
 use polyproto::Constrained;
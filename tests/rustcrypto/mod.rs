@@ -0,0 +1,158 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use der::asn1::Uint;
+use p256::ecdsa::signature::{self, Signer};
+use p256::ecdsa::{DerSignature, SigningKey, VerifyingKey};
+use polyproto::certs::capabilities::Capabilities;
+use polyproto::certs::idcert::IdCert;
+use polyproto::certs::idcsr::IdCsr;
+use polyproto::certs::Target;
+use polyproto::ecdsa::{P256PublicKey, P256Signature, P384PrivateKey, OID_ECDSA_WITH_SHA256};
+use polyproto::errors::{ConversionError, InvalidInput, PublicKeyError};
+use polyproto::key::{PrivateKey, PublicKey};
+use polyproto::rustcrypto::{RustCryptoPrivateKey, RustCryptoPublicKey, RustCryptoSignature};
+use polyproto::signature::Signature;
+use rand::rngs::OsRng;
+use spki::{AlgorithmIdentifierOwned, EncodePublicKey};
+
+use crate::common::{actor_subject, default_validity, home_server_subject, init_logger};
+
+type Sig = RustCryptoSignature<DerSignature, VerifyingKey>;
+type PubKey = RustCryptoPublicKey<VerifyingKey, DerSignature>;
+type PrivKey = RustCryptoPrivateKey<SigningKey, VerifyingKey, DerSignature>;
+
+fn gen_priv_key() -> PrivKey {
+    PrivKey::from_keypair(SigningKey::random(&mut OsRng)).unwrap()
+}
+
+/// A signer which encodes its own public key, instead of implementing `Keypair`.
+struct EncodingSigner(SigningKey);
+
+impl Signer<DerSignature> for EncodingSigner {
+    fn try_sign(&self, msg: &[u8]) -> Result<DerSignature, signature::Error> {
+        self.0.try_sign(msg)
+    }
+}
+
+impl EncodePublicKey for EncodingSigner {
+    fn to_public_key_der(&self) -> spki::Result<der::Document> {
+        self.0.verifying_key().to_public_key_der()
+    }
+}
+
+#[test]
+fn adapters_issue_and_verify_certificates() {
+    init_logger();
+    let home_server_key = gen_priv_key();
+    let home_server_cert = IdCert::from_ca_csr(
+        IdCsr::new(
+            &home_server_subject(),
+            &home_server_key,
+            &Capabilities::default_home_server(),
+            Some(Target::HomeServer),
+        )
+        .unwrap(),
+        &home_server_key,
+        Uint::new(&[1]).unwrap(),
+        home_server_subject(),
+        default_validity(),
+    )
+    .unwrap();
+    home_server_cert.full_verify_home_server(100).unwrap();
+
+    let actor_csr = IdCsr::new(
+        &actor_subject("flori"),
+        &gen_priv_key(),
+        &Capabilities::default_actor(),
+        Some(Target::Actor),
+    )
+    .unwrap();
+    let actor_cert = IdCert::from_actor_csr(
+        actor_csr,
+        &home_server_key,
+        Uint::new(&[2]).unwrap(),
+        home_server_subject(),
+        default_validity(),
+    )
+    .unwrap();
+    let actor_der = actor_cert.clone().to_der().unwrap();
+    let decoded =
+        IdCert::from_der(&actor_der, Target::Actor, 100, home_server_key.pubkey()).unwrap();
+    assert_eq!(decoded, actor_cert);
+
+    // The adapters produce the same encoding as the built-in ECDSA implementation
+    let home_server_public_key =
+        P256PublicKey::try_from_public_key_info(home_server_key.pubkey().public_key_info())
+            .unwrap();
+    IdCert::<P256Signature, P256PublicKey>::from_der(
+        &actor_der,
+        Target::Actor,
+        100,
+        &home_server_public_key,
+    )
+    .unwrap();
+}
+
+#[test]
+fn public_key_info_is_derived_from_spki() {
+    let signing_key = SigningKey::random(&mut OsRng);
+    let verifying_key = *signing_key.verifying_key();
+    let private_key = PrivKey::from_keypair(signing_key).unwrap();
+
+    let public_key_info = private_key.pubkey().public_key_info();
+    assert_eq!(
+        public_key_info.to_der().unwrap(),
+        verifying_key.to_public_key_der().unwrap().as_bytes()
+    );
+    let decoded = PubKey::try_from_public_key_info(public_key_info).unwrap();
+    assert_eq!(decoded.verifying_key(), &verifying_key);
+    assert_eq!(&decoded, private_key.pubkey());
+    assert_eq!(
+        Sig::algorithm_identifier(),
+        Some(AlgorithmIdentifierOwned {
+            oid: OID_ECDSA_WITH_SHA256,
+            parameters: None
+        })
+    );
+}
+
+#[test]
+fn signers_encoding_their_public_key_are_supported() {
+    let signing_key = SigningKey::random(&mut OsRng);
+    let from_keypair = PrivKey::from_keypair(signing_key.clone()).unwrap();
+    let private_key =
+        RustCryptoPrivateKey::<_, VerifyingKey, DerSignature>::new(EncodingSigner(signing_key))
+            .unwrap();
+    assert_eq!(private_key.pubkey(), from_keypair.pubkey());
+
+    let signature = private_key.sign(b"polyproto").unwrap();
+    from_keypair
+        .pubkey()
+        .verify_signature(&signature, b"polyproto")
+        .unwrap();
+    assert_eq!(
+        from_keypair
+            .pubkey()
+            .verify_signature(&signature, b"polyphony"),
+        Err(PublicKeyError::BadSignature)
+    );
+    assert_eq!(
+        Sig::try_from_bytes(&signature.to_bytes()).unwrap(),
+        signature
+    );
+    assert!(matches!(
+        Sig::try_from_bytes(&[0x30, 0x00, 0x00]),
+        Err(ConversionError::InvalidInput(InvalidInput::Malformed(_)))
+    ));
+}
+
+#[test]
+fn keys_of_other_algorithms_are_rejected() {
+    let p384_key = P384PrivateKey::generate(&mut OsRng);
+    assert!(matches!(
+        PubKey::try_from_public_key_info(p384_key.pubkey().public_key_info()),
+        Err(ConversionError::InvalidInput(InvalidInput::Malformed(_)))
+    ));
+}